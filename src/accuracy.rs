@@ -0,0 +1,158 @@
+//! Classify how trustworthy a conversion is from where the point lies relative to its zone.
+//!
+//! Inside its zone a UTM coordinate is as accurate as the series behind it. Projected into a
+//! neighboring zone it is still fine for navigation near the boundary, but the series and the
+//! scale distortion both degrade with distance from the central meridian. `AccuracyClass` puts a
+//! name on each regime, so downstream code can accept a coordinate for survey work or only for
+//! navigation.
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+#[cfg(feature = "forward")]
+use super::to_utm_wgs84;
+use super::{is_standard_zone, lat_to_zone_letter, ZONE_EXCEPTIONS};
+#[cfg(feature = "inverse")]
+use super::{wsg84_utm_to_lat_lon, WSG84ToLatLonError};
+
+/// How far outside of a zone, in degrees of longitude, a point is still in its overlap region.
+pub const OVERLAP_DEGREES: f64 = 0.5;
+
+/// How far from a zone's central meridian, in degrees of longitude, a point is still in the
+/// extended domain of the zone.
+pub const EXTENDED_DEGREES: f64 = 6.;
+
+/// Where a point lies relative to the zone it was converted in, from best to worst.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AccuracyClass {
+    /// Inside the zone, including the Norway and Svalbard exceptions.
+    Nominal,
+    /// Outside of the zone by at most `OVERLAP_DEGREES`, where neighboring zones customarily
+    /// overlap.
+    OverlapRegion,
+    /// Further outside of the zone, but within `EXTENDED_DEGREES` of its central meridian. The
+    /// legacy series is off by meters out here.
+    OutOfZoneExtended,
+    /// Beyond the extended domain, outside of the UTM latitude bands, or not a number.
+    OutOfDomain,
+}
+
+/// Classifies a latitude and longitude in decimal degrees relative to a zone.
+///
+/// Zones that don't exist in a band, such as 32X, have no nominal area.
+///
+/// # Examples
+///
+/// ```
+/// use utm::accuracy::{accuracy_class, AccuracyClass};
+/// assert_eq!(accuracy_class(45., 9., 32), AccuracyClass::Nominal);
+/// assert_eq!(accuracy_class(45., 12.3, 32), AccuracyClass::OverlapRegion);
+/// assert_eq!(accuracy_class(45., 14., 32), AccuracyClass::OutOfZoneExtended);
+/// assert_eq!(accuracy_class(45., 20., 32), AccuracyClass::OutOfDomain);
+/// // Zone 32V is widened to 3°E
+/// assert_eq!(accuracy_class(60., 4., 32), AccuracyClass::Nominal);
+/// ```
+pub fn accuracy_class(latitude: f64, longitude: f64, zone: u8) -> AccuracyClass {
+    let zone_letter = match lat_to_zone_letter(latitude) {
+        Some(zone_letter) if longitude.is_finite() && (1..=60).contains(&zone) => zone_letter,
+        _ => return AccuracyClass::OutOfDomain,
+    };
+    let central_meridian = f64::from(zone) * 6. - 183.;
+    let (west, east) = match ZONE_EXCEPTIONS
+        .iter()
+        .find(|exception| exception.zone_num == zone && exception.zone_letter == zone_letter)
+    {
+        Some(exception) => (exception.min_longitude, exception.max_longitude),
+        None if is_standard_zone(zone, zone_letter) => {
+            (central_meridian - 3., central_meridian + 3.)
+        }
+        None => (central_meridian, central_meridian),
+    };
+    let offset = (longitude - central_meridian + 540.) % 360. - 180.;
+    let (west, east) = (west - central_meridian, east - central_meridian);
+    if west <= offset && offset < east {
+        AccuracyClass::Nominal
+    } else if west - offset <= OVERLAP_DEGREES && offset - east <= OVERLAP_DEGREES {
+        AccuracyClass::OverlapRegion
+    } else if offset.abs() <= EXTENDED_DEGREES {
+        AccuracyClass::OutOfZoneExtended
+    } else {
+        AccuracyClass::OutOfDomain
+    }
+}
+
+#[cfg(feature = "forward")]
+/// Converts a latitude and longitude in decimal degrees to UTM coordinates in a zone, like
+/// `to_utm_wgs84`, and classifies the result.
+///
+/// Returns the northing, easting, meridian convergence, and accuracy class. Values classed as
+/// `OutOfDomain` should not be used.
+///
+/// # Examples
+///
+/// ```
+/// use utm::accuracy::{to_utm_checked, AccuracyClass};
+/// // Just over the boundary into zone 33
+/// let (_, easting, _, class) = to_utm_checked(45., 12.2, 32);
+/// assert_eq!(class, AccuracyClass::OverlapRegion);
+/// assert!(easting > 750000.);
+/// ```
+pub fn to_utm_checked(latitude: f64, longitude: f64, zone: u8) -> (f64, f64, f64, AccuracyClass) {
+    let (northing, easting, convergence) = to_utm_wgs84(latitude, longitude, zone);
+    (
+        northing,
+        easting,
+        convergence,
+        accuracy_class(latitude, longitude, zone),
+    )
+}
+
+#[cfg(feature = "inverse")]
+/// Converts a UTM coordinate to a latitude and longitude, like `wsg84_utm_to_lat_lon`, and
+/// classifies the result relative to the coordinate's zone.
+///
+/// # Examples
+///
+/// ```
+/// use utm::accuracy::{to_lat_lon_checked, AccuracyClass};
+/// let (_, _, class) = to_lat_lon_checked(261878., 6243186., 34, 'H').unwrap();
+/// assert_eq!(class, AccuracyClass::Nominal);
+/// ```
+pub fn to_lat_lon_checked(
+    easting: f64,
+    northing: f64,
+    zone_num: u8,
+    zone_letter: char,
+) -> Result<(f64, f64, AccuracyClass), WSG84ToLatLonError> {
+    let (latitude, longitude) = wsg84_utm_to_lat_lon(easting, northing, zone_num, zone_letter)?;
+    Ok((
+        latitude,
+        longitude,
+        accuracy_class(latitude, longitude, zone_num),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boundaries() {
+        // The eastern edge belongs to the next zone.
+        assert_eq!(accuracy_class(0., 6., 31), AccuracyClass::OverlapRegion);
+        assert_eq!(accuracy_class(0., 0., 31), AccuracyClass::Nominal);
+        assert_eq!(accuracy_class(0., -0.5, 31), AccuracyClass::OverlapRegion);
+        // Across the antimeridian
+        assert_eq!(accuracy_class(0., -179.8, 60), AccuracyClass::OverlapRegion);
+        assert_eq!(accuracy_class(85., 3., 31), AccuracyClass::OutOfDomain);
+        assert_eq!(accuracy_class(0., f64::NAN, 31), AccuracyClass::OutOfDomain);
+        // Zone 31V is narrowed to 3°E, and 32X doesn't exist.
+        assert_eq!(
+            accuracy_class(60., 4., 31),
+            AccuracyClass::OutOfZoneExtended
+        );
+        assert_eq!(accuracy_class(75., 9., 32), AccuracyClass::OverlapRegion);
+        assert!(AccuracyClass::Nominal < AccuracyClass::OutOfDomain);
+    }
+}
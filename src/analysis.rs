@@ -0,0 +1,257 @@
+//! Compare conversion algorithms over a region, behind the `analysis` feature.
+//!
+//! The crate's `to_utm_wgs84` and `wsg84_utm_to_lat_lon` use the classic truncated series, which
+//! lose accuracy away from the central meridian. `heat_map` samples a region on a grid, converts
+//! every sample with two algorithms, and records how far apart the results are, to show where
+//! the difference matters for a given dataset.
+
+use super::bbox::LatLonBbox;
+use super::tm::TransverseMercator;
+use super::{series_to_utm_wgs84, series_wsg84_utm_to_lat_lon, WGS84};
+
+/// The mean radius of the earth in meters, used for geodetic discrepancies.
+const EARTH_RADIUS: f64 = 6371008.8;
+
+/// A conversion algorithm.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// The series of `to_utm_wgs84` and `wsg84_utm_to_lat_lon`.
+    Series,
+    /// Krüger's series to sixth order, as in `tm::TransverseMercator`, which is accurate to
+    /// nanometers and can stand in for the exact projection.
+    Kruger,
+}
+
+/// Which conversion to compare.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Latitude and longitude to UTM. Discrepancies are the distance between the two eastings
+    /// and northings.
+    Forward,
+    /// UTM to latitude and longitude. Each sample is projected with `Algorithm::Kruger` and
+    /// inverted with both algorithms, and discrepancies are the ground distance between the two
+    /// results.
+    Inverse,
+}
+
+/// The algorithms and direction for `heat_map` to compare.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Comparison {
+    /// The conversion to compare.
+    pub direction: Direction,
+    /// The first algorithm.
+    pub a: Algorithm,
+    /// The second algorithm.
+    pub b: Algorithm,
+}
+
+/// A grid of discrepancies in meters between two algorithms.
+///
+/// Row zero is the southernmost and column zero the westernmost. Samples that one of the
+/// algorithms can't convert, such as eastings the series inverse rejects, are NaN.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeatMap {
+    bbox: LatLonBbox,
+    rows: usize,
+    columns: usize,
+    values: Vec<f64>,
+}
+
+impl HeatMap {
+    /// Returns the number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Returns the discrepancy of a cell.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row or column is out of range.
+    pub fn get(&self, row: usize, column: usize) -> f64 {
+        assert!(row < self.rows && column < self.columns);
+        self.values[row * self.columns + column]
+    }
+
+    /// Returns the latitude and longitude of the center of a cell, where it was sampled.
+    pub fn cell_center(&self, row: usize, column: usize) -> (f64, f64) {
+        let bbox = &self.bbox;
+        (
+            bbox.min_latitude
+                + (bbox.max_latitude - bbox.min_latitude) * (row as f64 + 0.5) / self.rows as f64,
+            bbox.min_longitude
+                + (bbox.max_longitude - bbox.min_longitude) * (column as f64 + 0.5)
+                    / self.columns as f64,
+        )
+    }
+
+    /// Returns every discrepancy, row by row.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Returns the largest discrepancy, ignoring NaN, or zero for an empty map.
+    pub fn max(&self) -> f64 {
+        self.values.iter().cloned().fold(0., f64::max)
+    }
+}
+
+/// Samples a region at the centers of a `rows` by `columns` grid and compares two algorithms
+/// at each sample in the given zone.
+///
+/// # Examples
+///
+/// ```
+/// use utm::analysis::{heat_map, Algorithm, Comparison, Direction};
+/// use utm::bbox::LatLonBbox;
+/// // Zone 33 and the zone to its east
+/// let bbox = LatLonBbox {
+///     min_latitude: 40.,
+///     min_longitude: 15.,
+///     max_latitude: 50.,
+///     max_longitude: 24.,
+/// };
+/// let comparison = Comparison {
+///     direction: Direction::Forward,
+///     a: Algorithm::Series,
+///     b: Algorithm::Kruger,
+/// };
+/// let map = heat_map(&bbox, 4, 6, 33, comparison);
+/// // Close to the central meridian the algorithms agree, far from it they don't.
+/// assert!(map.get(0, 0) < 0.01);
+/// assert!(map.get(0, 5) > map.get(0, 0));
+/// ```
+pub fn heat_map(
+    bbox: &LatLonBbox,
+    rows: usize,
+    columns: usize,
+    zone: u8,
+    comparison: Comparison,
+) -> HeatMap {
+    let mut map = HeatMap {
+        bbox: *bbox,
+        rows,
+        columns,
+        values: Vec::with_capacity(rows * columns),
+    };
+    for row in 0..rows {
+        for column in 0..columns {
+            let (latitude, longitude) = map.cell_center(row, column);
+            let discrepancy = match comparison.direction {
+                Direction::Forward => {
+                    let (northing_a, easting_a) = forward(comparison.a, latitude, longitude, zone);
+                    let (northing_b, easting_b) = forward(comparison.b, latitude, longitude, zone);
+                    (northing_a - northing_b).hypot(easting_a - easting_b)
+                }
+                Direction::Inverse => {
+                    let (northing, easting) = forward(Algorithm::Kruger, latitude, longitude, zone);
+                    let northern = latitude >= 0.;
+                    let a = inverse(comparison.a, easting, northing, zone, northern);
+                    let b = inverse(comparison.b, easting, northing, zone, northern);
+                    match (a, b) {
+                        (Some(a), Some(b)) => ground_distance(a, b),
+                        _ => f64::NAN,
+                    }
+                }
+            };
+            map.values.push(discrepancy);
+        }
+    }
+    map
+}
+
+fn projection(zone: u8, northern: bool) -> TransverseMercator {
+    let false_northing = if northern { 0. } else { 10000000. };
+    TransverseMercator::new(
+        WGS84,
+        0.,
+        f64::from(zone) * 6. - 183.,
+        0.9996,
+        500000.,
+        false_northing,
+    )
+}
+
+pub(crate) fn forward(algorithm: Algorithm, latitude: f64, longitude: f64, zone: u8) -> (f64, f64) {
+    let (northing, easting, _) = match algorithm {
+        Algorithm::Series => series_to_utm_wgs84(latitude, longitude, zone),
+        // Match the series, which only adds the false northing south of the equator.
+        Algorithm::Kruger => projection(zone, latitude >= 0.).forward(latitude, longitude),
+    };
+    (northing, easting)
+}
+
+pub(crate) fn inverse(
+    algorithm: Algorithm,
+    easting: f64,
+    northing: f64,
+    zone: u8,
+    northern: bool,
+) -> Option<(f64, f64)> {
+    match algorithm {
+        Algorithm::Series => {
+            let letter = if northern { 'N' } else { 'M' };
+            series_wsg84_utm_to_lat_lon(easting, northing, zone, letter).ok()
+        }
+        Algorithm::Kruger => Some(projection(zone, northern).inverse(easting, northing)),
+    }
+}
+
+pub(crate) fn ground_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let latitude = a.0.to_radians();
+    let dlat = (b.0 - a.0).to_radians();
+    let dlon = ((b.1 - a.1 + 540.) % 360. - 180.).to_radians();
+    EARTH_RADIUS * dlat.hypot(dlon * latitude.cos())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_algorithm_agrees() {
+        let bbox = LatLonBbox {
+            min_latitude: -10.,
+            min_longitude: 10.,
+            max_latitude: 10.,
+            max_longitude: 20.,
+        };
+        for &direction in &[Direction::Forward, Direction::Inverse] {
+            let comparison = Comparison {
+                direction,
+                a: Algorithm::Kruger,
+                b: Algorithm::Kruger,
+            };
+            let map = heat_map(&bbox, 3, 3, 33, comparison);
+            assert_eq!(map.values().len(), 9);
+            assert_eq!(map.max(), 0.);
+        }
+    }
+
+    #[test]
+    fn inverse_grows_away_from_central_meridian() {
+        let bbox = LatLonBbox {
+            min_latitude: 30.,
+            min_longitude: 15.,
+            max_latitude: 31.,
+            max_longitude: 21.,
+        };
+        let comparison = Comparison {
+            direction: Direction::Inverse,
+            a: Algorithm::Series,
+            b: Algorithm::Kruger,
+        };
+        let map = heat_map(&bbox, 1, 3, 33, comparison);
+        assert!(map.get(0, 0) < map.get(0, 2));
+        // About a meter and a half at the edge of the zone.
+        assert!(map.get(0, 2) > 1.);
+        let (latitude, longitude) = map.cell_center(0, 2);
+        assert_eq!(latitude, 30.5);
+        assert_eq!(longitude, 20.);
+    }
+}
@@ -0,0 +1,222 @@
+//! The table of UTM latitude bands.
+//!
+//! UTM divides the latitudes between 80°S and 84°N into twenty bands lettered C to X, skipping I
+//! and O. Every band is eight degrees tall except X, which is twelve. These are the same bands
+//! `lat_to_zone_letter` assigns, exposed for code that draws band rows or validates letters.
+
+use std::fmt;
+
+use super::converter::Converter;
+use super::{band_index, Hemisphere, ZONE_LETTERS};
+
+/// A latitude band.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Band {
+    /// The band letter.
+    pub letter: char,
+    /// The southern edge, in decimal degrees.
+    pub min_latitude: f64,
+    /// The northern edge, in decimal degrees.
+    pub max_latitude: f64,
+}
+
+impl Band {
+    /// Returns the band with a letter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::band::Band;
+    /// let band = Band::from_letter('X').unwrap();
+    /// assert_eq!((band.min_latitude, band.max_latitude), (72., 84.));
+    /// assert_eq!(Band::from_letter('I'), None);
+    /// ```
+    pub fn from_letter(letter: char) -> Option<Band> {
+        ZONE_LETTERS[..20].find(letter).map(band_at)
+    }
+
+    /// Returns the hemisphere of the band, south for C to M and north for N to X.
+    pub fn hemisphere(&self) -> Hemisphere {
+        if self.letter >= 'N' {
+            Hemisphere::North
+        } else {
+            Hemisphere::South
+        }
+    }
+
+    /// Returns true if a latitude is in this band.
+    ///
+    /// Like `lat_to_zone_letter`, the southern edge is included and the northern edge is not,
+    /// except for 84°N, the northern edge of band X.
+    pub fn contains(&self, latitude: f64) -> bool {
+        latitude >= self.min_latitude
+            && (latitude < self.max_latitude || (self.letter == 'X' && latitude == 84.))
+    }
+
+    /// Returns the approximate range of northings in meters covered by the band in a regular
+    /// six degree zone.
+    ///
+    /// Parallels curve on the grid, so the range spans the northing of each edge from the
+    /// central meridian out to the zone boundary. The widened zones in bands V and X reach a
+    /// little further.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::band::Band;
+    /// let (min, max) = Band::from_letter('N').unwrap().northing_range();
+    /// assert_eq!(min, 0.);
+    /// assert!((max - 885000.).abs() < 1000.);
+    /// ```
+    pub fn northing_range(&self) -> (f64, f64) {
+        let projection = Converter::new().projection(31, self.hemisphere() == Hemisphere::North);
+        let mut min = f64::INFINITY;
+        let mut max = f64::NEG_INFINITY;
+        for &latitude in &[self.min_latitude, self.max_latitude] {
+            for &longitude in &[3., 6.] {
+                let (northing, _, _) = projection.forward(latitude, longitude);
+                min = min.min(northing);
+                max = max.max(northing);
+            }
+        }
+        (min, max)
+    }
+}
+
+/// Returns an iterator over the bands from south to north.
+///
+/// # Examples
+///
+/// ```
+/// let letters: String = utm::band::bands().map(|band| band.letter).collect();
+/// assert_eq!(letters, "CDEFGHJKLMNPQRSTUVWX");
+/// ```
+pub fn bands() -> impl Iterator<Item = Band> {
+    (0..20).map(band_at)
+}
+
+/// Returns the band containing a latitude, or `None` if it is not between 80°S and 84°N.
+///
+/// # Examples
+///
+/// ```
+/// use utm::band::band_containing;
+/// assert_eq!(band_containing(-33.92487).unwrap().letter, 'H');
+/// assert_eq!(band_containing(0.).unwrap().letter, 'N');
+/// assert_eq!(band_containing(84.5), None);
+/// ```
+pub fn band_containing(latitude: f64) -> Option<Band> {
+    if (-80. ..=84.).contains(&latitude) {
+        Some(band_at(band_index(latitude)))
+    } else {
+        None
+    }
+}
+
+/// Returns the band containing a latitude, or why there isn't one.
+///
+/// Unlike `band_containing` and `lat_to_zone_letter`, this tells polar latitudes, which belong
+/// to the Universal Polar Stereographic grid, apart from latitudes that aren't on the earth.
+///
+/// # Examples
+///
+/// ```
+/// use utm::band::{lat_to_band, Error};
+/// assert_eq!(lat_to_band(60.).unwrap().letter, 'V');
+/// assert_eq!(lat_to_band(84.5), Err(Error::NorthPolar));
+/// assert_eq!(lat_to_band(-85.), Err(Error::SouthPolar));
+/// assert_eq!(lat_to_band(f64::NAN), Err(Error::InvalidLatitude));
+/// ```
+pub fn lat_to_band(latitude: f64) -> Result<Band, Error> {
+    if let Some(band) = band_containing(latitude) {
+        Ok(band)
+    } else if latitude > 84. && latitude <= 90. {
+        Err(Error::NorthPolar)
+    } else if (-90. ..-80.).contains(&latitude) {
+        Err(Error::SouthPolar)
+    } else {
+        Err(Error::InvalidLatitude)
+    }
+}
+
+/// Errors that can occur when looking up the band of a latitude.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The latitude is north of 84°N, in the UPS zones Y and Z.
+    NorthPolar,
+    /// The latitude is south of 80°S, in the UPS zones A and B.
+    SouthPolar,
+    /// The latitude is not between -90° and 90°.
+    InvalidLatitude,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NorthPolar => write!(f, "Latitude north of 84°N, use UPS zones Y and Z"),
+            Error::SouthPolar => write!(f, "Latitude south of 80°S, use UPS zones A and B"),
+            Error::InvalidLatitude => {
+                write!(f, "Latitude out of range, must be between -90 and 90")
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for Error {}
+
+/// Returns the band at an index into `ZONE_LETTERS`, between 0 and 19.
+fn band_at(index: usize) -> Band {
+    let min_latitude = -80. + 8. * index as f64;
+    Band {
+        letter: char::from(ZONE_LETTERS.as_bytes()[index]),
+        min_latitude,
+        max_latitude: if index == 19 { 84. } else { min_latitude + 8. },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lat_to_zone_letter;
+
+    #[test]
+    fn matches_lat_to_zone_letter() {
+        for band in bands() {
+            for &latitude in &[
+                band.min_latitude,
+                (band.min_latitude + band.max_latitude) / 2.,
+            ] {
+                assert_eq!(lat_to_zone_letter(latitude), Some(band.letter));
+                assert!(band.contains(latitude));
+                assert_eq!(band_containing(latitude), Some(band));
+            }
+            assert_eq!(Band::from_letter(band.letter), Some(band));
+        }
+        assert!(Band::from_letter('X').unwrap().contains(84.));
+        assert!(!Band::from_letter('W').unwrap().contains(72.));
+    }
+
+    #[test]
+    fn polar_latitudes() {
+        assert_eq!(lat_to_band(84.).unwrap().letter, 'X');
+        assert_eq!(lat_to_band(-80.).unwrap().letter, 'C');
+        assert_eq!(lat_to_band(90.), Err(Error::NorthPolar));
+        assert_eq!(lat_to_band(-90.), Err(Error::SouthPolar));
+        assert_eq!(lat_to_band(90.5), Err(Error::InvalidLatitude));
+        assert_eq!(lat_to_band(f64::NEG_INFINITY), Err(Error::InvalidLatitude));
+    }
+
+    #[test]
+    fn northing_ranges() {
+        let (min, max) = Band::from_letter('M').unwrap().northing_range();
+        assert!(min > 9000000. && min < 9200000.);
+        assert_eq!(max, 10000000.);
+        let mut previous = 0.;
+        for band in bands().skip_while(|band| band.letter < 'N') {
+            let (min, max) = band.northing_range();
+            assert!(min >= previous - 20000. && max > min);
+            previous = max;
+        }
+    }
+}
@@ -0,0 +1,971 @@
+//! Convert many coordinates at once.
+//!
+//! The batch functions write into caller-provided slices, so they never allocate and are
+//! available without the standard library. The `_extend` variants append to any collection
+//! instead, for callers that keep their own buffers. The zone and band lookups are available
+//! without either conversion direction, for assigning zones before projecting.
+
+#[cfg(feature = "forward")]
+use std::fmt;
+#[cfg(feature = "forward")]
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(all(feature = "inverse", not(feature = "no_std")))]
+use super::converter::global_workspace;
+#[cfg(feature = "forward")]
+use super::datum::DatumTag;
+#[cfg(any(feature = "forward", feature = "inverse"))]
+use super::UtmCoordinate;
+#[cfg(feature = "forward")]
+use super::{band_index, series_to_utm_wgs84, to_utm_wgs84, ZONE_LETTERS};
+use super::{lat_lon_to_zone_number, lat_to_zone_letter};
+#[cfg(feature = "inverse")]
+use super::{utm_to_lat_lon_unchecked, wsg84_utm_to_lat_lon, WSG84ToLatLonError};
+
+/// A flag for cancelling a batch conversion from another thread.
+///
+/// Share it by reference, e.g. in a scoped thread or an `Arc`, and pass it to a conversion
+/// through `Control::with_cancellation`.
+#[derive(Debug, Default)]
+pub struct Cancellation {
+    cancelled: AtomicBool,
+}
+
+impl Cancellation {
+    /// Creates a flag that isn't set.
+    pub fn new() -> Cancellation {
+        Cancellation::default()
+    }
+
+    /// Asks conversions using this flag to stop at their next check.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if `cancel` has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Progress reporting and cancellation for the `_with` batch conversions.
+///
+/// Every `interval` entries, and once at the end, the progress callback is called with the
+/// number of entries converted so far and the total, and the cancellation flag is checked. A
+/// cancelled conversion stops there and returns the number of entries written, like one that
+/// ran out of output space.
+pub struct Control<'a> {
+    progress: Option<&'a mut dyn FnMut(usize, usize)>,
+    cancellation: Option<&'a Cancellation>,
+    interval: usize,
+}
+
+impl<'a> Control<'a> {
+    /// Creates a control without a callback or a cancellation flag, checking every 1024 entries.
+    pub fn new() -> Control<'a> {
+        Control {
+            progress: None,
+            cancellation: None,
+            interval: 1024,
+        }
+    }
+
+    /// Calls `progress` with the number of entries converted and the total.
+    pub fn with_progress(mut self, progress: &'a mut dyn FnMut(usize, usize)) -> Control<'a> {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Stops the conversion once `cancellation` is set.
+    pub fn with_cancellation(mut self, cancellation: &'a Cancellation) -> Control<'a> {
+        self.cancellation = Some(cancellation);
+        self
+    }
+
+    /// Sets the number of entries between checks, at least one.
+    pub fn with_interval(mut self, interval: usize) -> Control<'a> {
+        self.interval = interval.max(1);
+        self
+    }
+
+    #[cfg(any(feature = "forward", feature = "inverse"))]
+    /// Reports progress if it's time to, and returns false if the conversion should stop.
+    fn check(&mut self, done: usize, total: usize) -> bool {
+        if !done.is_multiple_of(self.interval) && done != total {
+            return true;
+        }
+        self.report(done, total)
+    }
+
+    #[cfg(any(feature = "forward", feature = "inverse"))]
+    /// Reports progress regardless of the interval, and returns false if the conversion should
+    /// stop.
+    pub(crate) fn report(&mut self, done: usize, total: usize) -> bool {
+        if let Some(progress) = self.progress.as_mut() {
+            progress(done, total);
+        }
+        !self
+            .cancellation
+            .is_some_and(|cancellation| cancellation.is_cancelled())
+    }
+}
+
+impl<'a> Default for Control<'a> {
+    fn default() -> Control<'a> {
+        Control::new()
+    }
+}
+
+#[cfg(feature = "forward")]
+/// Why a latitude and longitude could not be converted to UTM.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The latitude is not between 80°S and 84°N, or is not a number.
+    LatitudeOutOfRange,
+    /// The longitude is not between -180° and 180°, or is not a number.
+    LongitudeOutOfRange,
+}
+
+#[cfg(feature = "forward")]
+/// An error converting one entry of a batch, with the index of the entry that failed.
+///
+/// Every entry before `index` has been converted and written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Error {
+    /// The index of the entry that could not be converted.
+    pub index: usize,
+    /// What was wrong with it.
+    pub kind: ErrorKind,
+}
+
+#[cfg(feature = "forward")]
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ErrorKind::LatitudeOutOfRange => write!(
+                f,
+                "Latitude at index {} out of range, must be between -80 and 84",
+                self.index
+            ),
+            ErrorKind::LongitudeOutOfRange => write!(
+                f,
+                "Longitude at index {} out of range, must be between -180 and 180",
+                self.index
+            ),
+        }
+    }
+}
+
+#[cfg(all(feature = "forward", not(feature = "no_std")))]
+impl std::error::Error for Error {}
+
+#[cfg(feature = "forward")]
+/// Converts latitudes and longitudes in decimal degrees to UTM coordinates in their own zones.
+///
+/// Converts as many entries as fit in both slices and returns how many were written, so a
+/// stream of fixes can be processed in blocks through a fixed size buffer. Conversion stops at
+/// the first entry that is out of range and returns its index, leaving the outputs from that
+/// point on untouched.
+///
+/// # Examples
+///
+/// ```
+/// use utm::batch::to_utm;
+/// use utm::UtmCoordinate;
+/// let fixes = [(-33.92487, 18.42406), (-41.28646, 174.77624), (50.77535, 6.08389)];
+/// let mut coordinates = [UtmCoordinate::new(0., 0., 1, 'C'); 2];
+/// assert_eq!(to_utm(&fixes, &mut coordinates), Ok(2));
+/// assert_eq!(coordinates[0].zone_num, 34);
+/// assert_eq!(coordinates[1].zone_letter, 'G');
+/// ```
+pub fn to_utm(lat_lons: &[(f64, f64)], coordinates: &mut [UtmCoordinate]) -> Result<usize, Error> {
+    to_utm_with(lat_lons, coordinates, &mut Control::new())
+}
+
+#[cfg(feature = "forward")]
+/// Converts latitudes and longitudes in decimal degrees to UTM coordinates in their own zones,
+/// with progress reporting and cancellation.
+///
+/// Behaves like `to_utm`, except that a cancelled conversion returns early with the number of
+/// entries written.
+///
+/// # Examples
+///
+/// ```
+/// use utm::batch::{to_utm_with, Cancellation, Control};
+/// use utm::UtmCoordinate;
+/// let fixes = [(-33.92487, 18.42406); 10];
+/// let mut coordinates = [UtmCoordinate::new(0., 0., 1, 'C'); 10];
+/// let cancellation = Cancellation::new();
+/// let mut progress = |done: usize, total: usize| {
+///     if done == 4 {
+///         cancellation.cancel();
+///     }
+/// };
+/// let mut control = Control::new()
+///     .with_progress(&mut progress)
+///     .with_cancellation(&cancellation)
+///     .with_interval(2);
+/// assert_eq!(to_utm_with(&fixes, &mut coordinates, &mut control), Ok(4));
+/// ```
+pub fn to_utm_with(
+    lat_lons: &[(f64, f64)],
+    coordinates: &mut [UtmCoordinate],
+    control: &mut Control,
+) -> Result<usize, Error> {
+    let total = lat_lons.len().min(coordinates.len());
+    let mut written = 0;
+    for (index, (&(latitude, longitude), coordinate)) in
+        lat_lons.iter().zip(coordinates.iter_mut()).enumerate()
+    {
+        *coordinate = lat_lon_to_utm(latitude, longitude).map_err(|kind| Error { index, kind })?;
+        written += 1;
+        if !control.check(written, total) {
+            break;
+        }
+    }
+    Ok(written)
+}
+
+#[cfg(feature = "forward")]
+/// Converts latitudes and longitudes in decimal degrees to UTM coordinates in their own zones,
+/// writing into uninitialized memory.
+///
+/// Behaves like `to_utm`. On success the first `n` elements of `coordinates` are initialized,
+/// where `n` is the returned count; on failure the first `index` elements are. The rest are left
+/// as they were.
+///
+/// # Examples
+///
+/// ```
+/// use std::mem::MaybeUninit;
+/// use utm::batch::to_utm_uninit;
+/// let fixes = [(-33.92487, 18.42406), (-41.28646, 174.77624)];
+/// let mut buffer = [MaybeUninit::uninit(); 16];
+/// let written = to_utm_uninit(&fixes, &mut buffer).unwrap();
+/// assert_eq!(written, 2);
+/// let capetown = unsafe { buffer[0].assume_init() };
+/// assert_eq!(capetown.zone_letter, 'H');
+/// ```
+pub fn to_utm_uninit(
+    lat_lons: &[(f64, f64)],
+    coordinates: &mut [MaybeUninit<UtmCoordinate>],
+) -> Result<usize, Error> {
+    let mut written = 0;
+    for (index, (&(latitude, longitude), coordinate)) in
+        lat_lons.iter().zip(coordinates.iter_mut()).enumerate()
+    {
+        let value = lat_lon_to_utm(latitude, longitude).map_err(|kind| Error { index, kind })?;
+        *coordinate = MaybeUninit::new(value);
+        written += 1;
+    }
+    Ok(written)
+}
+
+#[cfg(feature = "forward")]
+/// Converts latitudes and longitudes in decimal degrees to UTM coordinates in their own zones,
+/// appending them to any collection that implements `Extend`.
+///
+/// Behaves like `to_utm`, but the output can be a reused `Vec`, a `SmallVec`, an `ArrayVec`, or
+/// anything else that can be extended, so the caller decides where the coordinates live.
+/// Conversion stops at the first entry that is out of range, after appending every entry before
+/// it.
+///
+/// # Examples
+///
+/// ```
+/// use utm::batch::to_utm_extend;
+/// let fixes = [(-33.92487, 18.42406), (-41.28646, 174.77624)];
+/// let mut coordinates = Vec::with_capacity(16);
+/// assert_eq!(to_utm_extend(&fixes, &mut coordinates), Ok(2));
+/// coordinates.clear();
+/// assert_eq!(to_utm_extend(&fixes[1..], &mut coordinates), Ok(1));
+/// assert_eq!(coordinates[0].zone_num, 60);
+/// ```
+pub fn to_utm_extend<E: Extend<UtmCoordinate>>(
+    lat_lons: &[(f64, f64)],
+    coordinates: &mut E,
+) -> Result<usize, Error> {
+    let mut written = 0;
+    let mut error = None;
+    coordinates.extend(
+        lat_lons.iter().enumerate().map_while(
+            |(index, &(latitude, longitude))| match lat_lon_to_utm(latitude, longitude) {
+                Ok(coordinate) => {
+                    written += 1;
+                    Some(coordinate)
+                }
+                Err(kind) => {
+                    error = Some(Error { index, kind });
+                    None
+                }
+            },
+        ),
+    );
+    match error {
+        Some(error) => Err(error),
+        None => Ok(written),
+    }
+}
+
+#[cfg(feature = "forward")]
+/// A UTM coordinate converted from a point on another datum, returned by `to_utm_from_datums`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FromDatum {
+    /// The WGS84 UTM coordinate, in its own zone.
+    pub coordinate: UtmCoordinate,
+    /// The datum the point was tagged with.
+    pub datum: DatumTag,
+}
+
+#[cfg(feature = "forward")]
+/// Converts latitudes and longitudes in decimal degrees, each tagged with its datum, to WGS84
+/// UTM coordinates in their own zones.
+///
+/// Every point is transformed to WGS84 with `DatumTag::to_wgs84` first, so an archive that
+/// mixes datums comes out on one. The points are converted one datum at a time, but each output
+/// is written at the index of its point and keeps its datum. Otherwise behaves like `to_utm`,
+/// with out of range errors for the transformed latitude and longitude, except that outputs
+/// after the failing index may have been written too.
+///
+/// # Examples
+///
+/// ```
+/// use utm::batch::{to_utm_from_datums, FromDatum};
+/// use utm::datum::DatumTag;
+/// use utm::UtmCoordinate;
+/// let points = [
+///     (DatumTag::Nad27, 39.224079, -98.541807),
+///     (DatumTag::Nad83, 39.224087, -98.542151),
+/// ];
+/// let empty = FromDatum {
+///     coordinate: UtmCoordinate::new(0., 0., 1, 'C'),
+///     datum: DatumTag::Wgs84,
+/// };
+/// let mut output = [empty; 2];
+/// assert_eq!(to_utm_from_datums(&points, &mut output), Ok(2));
+/// assert_eq!(output[0].datum, DatumTag::Nad27);
+/// // The same monument, surveyed on either datum, lands within a few meters.
+/// let (a, b) = (output[0].coordinate, output[1].coordinate);
+/// assert!((a.easting - b.easting).hypot(a.northing - b.northing) < 10.);
+/// ```
+pub fn to_utm_from_datums(
+    points: &[(DatumTag, f64, f64)],
+    output: &mut [FromDatum],
+) -> Result<usize, Error> {
+    let len = points.len().min(output.len());
+    let mut end = len;
+    let mut error = None;
+    for group in 0..DATUM_GROUPS {
+        for (index, (&(datum, latitude, longitude), output)) in
+            points[..end].iter().zip(output.iter_mut()).enumerate()
+        {
+            if datum_group(datum) != group {
+                continue;
+            }
+            let (latitude, longitude) = datum.to_wgs84(latitude, longitude);
+            match lat_lon_to_utm(latitude, longitude) {
+                Ok(coordinate) => *output = FromDatum { coordinate, datum },
+                Err(kind) => {
+                    // Later groups only need the points before this one.
+                    end = index;
+                    error = Some(Error { index, kind });
+                    break;
+                }
+            }
+        }
+    }
+    match error {
+        Some(error) => Err(error),
+        None => Ok(len),
+    }
+}
+
+#[cfg(feature = "forward")]
+const DATUM_GROUPS: usize = 4;
+
+#[cfg(feature = "forward")]
+/// Returns the group `to_utm_from_datums` converts points on this datum in.
+fn datum_group(datum: DatumTag) -> usize {
+    match datum {
+        DatumTag::Wgs84 => 0,
+        DatumTag::Nad83 => 1,
+        DatumTag::Nad27 => 2,
+        DatumTag::Ed50 => 3,
+    }
+}
+
+#[cfg(feature = "forward")]
+/// Converts latitudes and longitudes in decimal degrees to UTM coordinates in their own zones
+/// without checking them.
+///
+/// The forward counterpart of `to_lat_lon_unchecked`, for fixes that have already been validated
+/// upstream. Latitudes outside of 80°S to 84°N get the letter of the nearest band and are
+/// projected anyway, with degrading accuracy, and longitudes outside of ±180° get the nearest
+/// zone. Invalid input never causes undefined behavior, just meaningless output, which may
+/// include NaN. Always uses the classic series on WGS84, even if a global converter was set with
+/// `converter::set_global`.
+///
+/// Converts as many entries as fit in both slices and returns how many were written, like
+/// `to_utm`.
+///
+/// # Examples
+///
+/// ```
+/// use utm::batch::{to_utm, to_utm_unchecked};
+/// use utm::UtmCoordinate;
+/// let fixes = [(-33.92487, 18.42406), (-41.28646, 174.77624)];
+/// let mut checked = [UtmCoordinate::new(0., 0., 1, 'C'); 2];
+/// let mut unchecked = checked;
+/// assert_eq!(to_utm(&fixes, &mut checked), Ok(2));
+/// assert_eq!(to_utm_unchecked(&fixes, &mut unchecked), 2);
+/// assert_eq!(checked, unchecked);
+/// ```
+pub fn to_utm_unchecked(lat_lons: &[(f64, f64)], coordinates: &mut [UtmCoordinate]) -> usize {
+    let written = lat_lons.len().min(coordinates.len());
+    for (&(latitude, longitude), coordinate) in lat_lons.iter().zip(coordinates.iter_mut()) {
+        let zone_num = lat_lon_to_zone_number(latitude, longitude);
+        let zone_letter = char::from(ZONE_LETTERS.as_bytes()[band_index(latitude)]);
+        let (northing, easting, _) = series_to_utm_wgs84(latitude, longitude, zone_num);
+        *coordinate = UtmCoordinate::new(easting, northing, zone_num, zone_letter);
+    }
+    written
+}
+
+#[cfg(feature = "forward")]
+fn lat_lon_to_utm(latitude: f64, longitude: f64) -> Result<UtmCoordinate, ErrorKind> {
+    let zone_letter = lat_to_zone_letter(latitude).ok_or(ErrorKind::LatitudeOutOfRange)?;
+    if !(-180. ..=180.).contains(&longitude) {
+        return Err(ErrorKind::LongitudeOutOfRange);
+    }
+    let zone_num = lat_lon_to_zone_number(latitude, longitude);
+    let (northing, easting, _) = to_utm_wgs84(latitude, longitude, zone_num);
+    Ok(UtmCoordinate::new(easting, northing, zone_num, zone_letter))
+}
+
+#[cfg(feature = "inverse")]
+/// Converts UTM coordinates to latitudes and longitudes in decimal degrees.
+///
+/// Converts as many entries as fit in both slices and returns how many were written, like
+/// `to_utm`. Every coordinate is checked like `wsg84_utm_to_lat_lon`. Conversion stops at the
+/// first invalid coordinate and returns its error, leaving the outputs from that point on
+/// untouched.
+///
+/// # Examples
+///
+/// ```
+/// use utm::batch::to_lat_lon;
+/// use utm::UtmCoordinate;
+/// let coordinates = [
+///     UtmCoordinate::new(261878., 6243186., 34, 'H'),
+///     UtmCoordinate::new(313784., 5427057., 60, 'G'),
+/// ];
+/// let mut lat_lons = [(0., 0.); 2];
+/// assert_eq!(to_lat_lon(&coordinates, &mut lat_lons), Ok(2));
+/// assert!((lat_lons[0].0 - -33.92487).abs() < 1e-4);
+/// ```
+pub fn to_lat_lon(
+    coordinates: &[UtmCoordinate],
+    lat_lons: &mut [(f64, f64)],
+) -> Result<usize, WSG84ToLatLonError> {
+    to_lat_lon_with(coordinates, lat_lons, &mut Control::new())
+}
+
+#[cfg(feature = "inverse")]
+/// Converts UTM coordinates to latitudes and longitudes in decimal degrees, with progress
+/// reporting and cancellation.
+///
+/// Converts as many entries as fit in both slices and returns how many were written, stopping
+/// early if cancelled. Every coordinate is checked like `wsg84_utm_to_lat_lon`.
+///
+/// # Examples
+///
+/// ```
+/// use utm::batch::{to_lat_lon_with, Control};
+/// use utm::UtmCoordinate;
+/// let coordinates = [UtmCoordinate::new(261878., 6243186., 34, 'H'); 3];
+/// let mut lat_lons = [(0., 0.); 3];
+/// let mut reports = Vec::new();
+/// let mut progress = |done, total| reports.push((done, total));
+/// let mut control = Control::new().with_progress(&mut progress).with_interval(2);
+/// assert_eq!(to_lat_lon_with(&coordinates, &mut lat_lons, &mut control), Ok(3));
+/// drop(control);
+/// assert_eq!(reports, [(2, 3), (3, 3)]);
+/// ```
+pub fn to_lat_lon_with(
+    coordinates: &[UtmCoordinate],
+    lat_lons: &mut [(f64, f64)],
+    control: &mut Control,
+) -> Result<usize, WSG84ToLatLonError> {
+    let total = coordinates.len().min(lat_lons.len());
+    let mut written = 0;
+    for (coordinate, lat_lon) in coordinates.iter().zip(lat_lons.iter_mut()) {
+        *lat_lon = wsg84_utm_to_lat_lon(
+            coordinate.easting,
+            coordinate.northing,
+            coordinate.zone_num,
+            coordinate.zone_letter,
+        )?;
+        written += 1;
+        if !control.check(written, total) {
+            break;
+        }
+    }
+    Ok(written)
+}
+
+#[cfg(feature = "inverse")]
+/// Converts UTM coordinates to latitudes and longitudes in decimal degrees, appending them to
+/// any collection that implements `Extend`.
+///
+/// Every coordinate is checked like `wsg84_utm_to_lat_lon`. Conversion stops at the first
+/// invalid coordinate, after appending every entry before it, and returns its error.
+///
+/// # Examples
+///
+/// ```
+/// use utm::batch::to_lat_lon_extend;
+/// use utm::UtmCoordinate;
+/// let coordinates = [UtmCoordinate::new(261878., 6243186., 34, 'H'); 2];
+/// let mut lat_lons = Vec::new();
+/// assert_eq!(to_lat_lon_extend(&coordinates, &mut lat_lons), Ok(2));
+/// assert!((lat_lons[1].0 - -33.92487).abs() < 1e-4);
+/// ```
+pub fn to_lat_lon_extend<E: Extend<(f64, f64)>>(
+    coordinates: &[UtmCoordinate],
+    lat_lons: &mut E,
+) -> Result<usize, WSG84ToLatLonError> {
+    let mut written = 0;
+    let mut error = None;
+    lat_lons.extend(coordinates.iter().map_while(|coordinate| {
+        match wsg84_utm_to_lat_lon(
+            coordinate.easting,
+            coordinate.northing,
+            coordinate.zone_num,
+            coordinate.zone_letter,
+        ) {
+            Ok(lat_lon) => {
+                written += 1;
+                Some(lat_lon)
+            }
+            Err(err) => {
+                error = Some(err);
+                None
+            }
+        }
+    }));
+    match error {
+        Some(error) => Err(error),
+        None => Ok(written),
+    }
+}
+
+#[cfg(feature = "inverse")]
+/// Converts UTM coordinates to latitudes and longitudes in decimal degrees without checking
+/// them.
+///
+/// This is the fast path for input that has already been validated upstream. Nothing about the
+/// coordinates is checked, and the hemisphere is selected without branching, so the loop body
+/// is the same straight-line arithmetic for every coordinate.
+///
+/// The caller is responsible for the contract that `wsg84_utm_to_lat_lon` would otherwise
+/// enforce: zone numbers between 1 and 60 and zone letters between C and X. Letters from N
+/// upwards are treated as northern and everything else as southern. Eastings and northings
+/// outside of the usual ranges are converted anyway, with degrading accuracy the further out
+/// they are. Invalid input never causes undefined behavior, just meaningless output, which may
+/// include NaN.
+///
+/// Uses the global converter instead, if one was set with `converter::set_global`, so that it
+/// agrees with `to_lat_lon`. Zone numbers outside of 1 to 60 then convert to NaN.
+///
+/// Converts as many entries as fit in both slices and returns how many were written, like
+/// `to_lat_lon`.
+///
+/// # Examples
+///
+/// ```
+/// use utm::batch::to_lat_lon_unchecked;
+/// use utm::UtmCoordinate;
+/// let coordinates = [UtmCoordinate::new(261878., 6243186., 34, 'H')];
+/// let mut lat_lons = [(0., 0.); 4];
+/// assert_eq!(to_lat_lon_unchecked(&coordinates, &mut lat_lons), 1);
+/// assert!((lat_lons[0].1 - 18.42406).abs() < 1e-4);
+/// ```
+///
+/// With a global converter, both variants use it:
+///
+/// ```
+/// # #[cfg(not(feature = "no_std"))] {
+/// use utm::batch::{to_lat_lon, to_lat_lon_unchecked};
+/// use utm::converter::{self, Converter};
+/// use utm::UtmCoordinate;
+/// converter::set_global(Converter::new().with_false_easting(1000000.)).unwrap();
+/// let coordinates = [UtmCoordinate::new(1092430., 6243186., 34, 'H')];
+/// let (mut checked, mut unchecked) = ([(0., 0.)], [(0., 0.)]);
+/// to_lat_lon(&coordinates, &mut checked).unwrap();
+/// to_lat_lon_unchecked(&coordinates, &mut unchecked);
+/// assert_eq!(checked, unchecked);
+/// assert!((checked[0].1 - 22.).abs() < 0.01);
+/// # }
+/// ```
+pub fn to_lat_lon_unchecked(coordinates: &[UtmCoordinate], lat_lons: &mut [(f64, f64)]) -> usize {
+    let written = coordinates.len().min(lat_lons.len());
+    #[cfg(not(feature = "no_std"))]
+    {
+        if let Some(workspace) = global_workspace() {
+            for (coordinate, lat_lon) in coordinates.iter().zip(lat_lons.iter_mut()) {
+                *lat_lon = match workspace
+                    .projection(coordinate.zone_num, coordinate.zone_letter >= 'N')
+                {
+                    Some(projection) => projection.inverse(coordinate.easting, coordinate.northing),
+                    None => (f64::NAN, f64::NAN),
+                };
+            }
+            return written;
+        }
+    }
+    for (coordinate, lat_lon) in coordinates.iter().zip(lat_lons.iter_mut()) {
+        *lat_lon = utm_to_lat_lon_unchecked(
+            coordinate.easting,
+            coordinate.northing,
+            coordinate.zone_num,
+            coordinate.zone_letter >= 'N',
+        );
+    }
+    written
+}
+
+#[cfg(all(feature = "forward", feature = "inverse"))]
+/// A coordinate converted back to a latitude and longitude and into another zone.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Reprojected {
+    /// The latitude and longitude in decimal degrees.
+    pub lat_lon: (f64, f64),
+    /// The coordinate in the target zone.
+    pub coordinate: UtmCoordinate,
+}
+
+#[cfg(all(feature = "forward", feature = "inverse"))]
+/// Converts UTM coordinates to latitudes and longitudes and projects those into a target
+/// zone, in one pass.
+///
+/// Merging data from neighboring zones needs both results, and this computes the inverse once
+/// for both. The projected coordinates get the band letter of their latitude, or keep their
+/// own letter if the latitude has come back just outside of the bands.
+///
+/// Converts as many entries as fit in both slices and returns how many were written. Every
+/// coordinate is checked like `wsg84_utm_to_lat_lon`, and conversion stops at the first
+/// invalid one and returns its error.
+///
+/// # Examples
+///
+/// ```
+/// use utm::batch::{reproject, Reprojected};
+/// use utm::UtmCoordinate;
+/// let coordinates = [UtmCoordinate::new(261878., 6243186., 34, 'H')];
+/// let mut outputs = [Reprojected {
+///     lat_lon: (0., 0.),
+///     coordinate: UtmCoordinate::new(0., 0., 1, 'C'),
+/// }];
+/// assert_eq!(reproject(&coordinates, 33, &mut outputs), Ok(1));
+/// assert!((outputs[0].lat_lon.1 - 18.42406).abs() < 1e-4);
+/// assert_eq!(outputs[0].coordinate.zone_num, 33);
+/// assert!(outputs[0].coordinate.easting > 800000.);
+/// ```
+pub fn reproject(
+    coordinates: &[UtmCoordinate],
+    zone_num: u8,
+    outputs: &mut [Reprojected],
+) -> Result<usize, WSG84ToLatLonError> {
+    if !(1..=60).contains(&zone_num) {
+        return Err(WSG84ToLatLonError::ZoneNumOutOfRange);
+    }
+    let mut written = 0;
+    for (coordinate, output) in coordinates.iter().zip(outputs.iter_mut()) {
+        let (latitude, longitude) = wsg84_utm_to_lat_lon(
+            coordinate.easting,
+            coordinate.northing,
+            coordinate.zone_num,
+            coordinate.zone_letter,
+        )?;
+        let (northing, easting, _) = to_utm_wgs84(latitude, longitude, zone_num);
+        let zone_letter = lat_to_zone_letter(latitude).unwrap_or(coordinate.zone_letter);
+        *output = Reprojected {
+            lat_lon: (latitude, longitude),
+            coordinate: UtmCoordinate::new(easting, northing, zone_num, zone_letter),
+        };
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// Looks up the zone numbers of latitudes and longitudes in decimal degrees, like
+/// `lat_lon_to_zone_number`, including the Norway and Svalbard exceptions.
+///
+/// Looks up as many entries as fit in both slices and returns how many were written.
+///
+/// # Examples
+///
+/// ```
+/// use utm::batch::zone_numbers;
+/// let points = [(-33.92487, 18.42406), (60., 5.), (78.22, 15.65)];
+/// let mut zones = [0; 3];
+/// assert_eq!(zone_numbers(&points, &mut zones), 3);
+/// assert_eq!(zones, [34, 32, 33]);
+/// ```
+pub fn zone_numbers(lat_lons: &[(f64, f64)], zones: &mut [u8]) -> usize {
+    let len = lat_lons.len().min(zones.len());
+    for (&(latitude, longitude), zone) in lat_lons.iter().zip(zones.iter_mut()) {
+        *zone = lat_lon_to_zone_number(latitude, longitude);
+    }
+    len
+}
+
+/// Looks up the band letters of the latitudes of latitude and longitude pairs, like
+/// `lat_to_zone_letter`. Latitudes outside of 80°S to 84°N get `None`.
+///
+/// Looks up as many entries as fit in both slices and returns how many were written.
+///
+/// # Examples
+///
+/// ```
+/// use utm::batch::zone_letters;
+/// let points = [(-33.92487, 18.42406), (84., 0.), (85., 0.)];
+/// let mut letters = [None; 3];
+/// assert_eq!(zone_letters(&points, &mut letters), 3);
+/// assert_eq!(letters, [Some('H'), Some('X'), None]);
+/// ```
+pub fn zone_letters(lat_lons: &[(f64, f64)], letters: &mut [Option<char>]) -> usize {
+    let len = lat_lons.len().min(letters.len());
+    for (&(latitude, _), letter) in lat_lons.iter().zip(letters.iter_mut()) {
+        *letter = lat_to_zone_letter(latitude);
+    }
+    len
+}
+
+#[cfg(all(test, feature = "forward", feature = "inverse"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zone_lookups_match_scalar() {
+        let points = [
+            (63.9, 3.),
+            (64., 2.9),
+            (72., 8.9),
+            (83.9, 41.9),
+            (55.9, 3.),
+            (-80., -180.),
+            (0., 180.),
+        ];
+        let mut zones = [0; 7];
+        let mut letters = [None; 6];
+        assert_eq!(zone_numbers(&points, &mut zones), 7);
+        assert_eq!(zone_letters(&points, &mut letters), 6);
+        for (i, &(latitude, longitude)) in points.iter().enumerate() {
+            assert_eq!(zones[i], lat_lon_to_zone_number(latitude, longitude));
+            if i < 6 {
+                assert_eq!(letters[i], lat_to_zone_letter(latitude));
+            }
+        }
+        assert_eq!(zones[..5], [32, 31, 31, 37, 31]);
+    }
+
+    #[test]
+    fn datums_keep_their_order() {
+        let points = [
+            (DatumTag::Nad27, 39.224079, -98.541807),
+            (DatumTag::Wgs84, -33.92487, 18.42406),
+            (DatumTag::Ed50, 48.85, 2.35),
+            (DatumTag::Nad27, 40., -105.),
+            (DatumTag::Nad83, 39.224087, -98.542151),
+            (DatumTag::Wgs84, 60.9679875497, -149.119325194),
+        ];
+        let empty = FromDatum {
+            coordinate: UtmCoordinate::new(0., 0., 1, 'C'),
+            datum: DatumTag::Wgs84,
+        };
+        let mut output = [empty; 6];
+        assert_eq!(to_utm_from_datums(&points, &mut output), Ok(6));
+        for (&(datum, latitude, longitude), output) in points.iter().zip(output.iter()) {
+            let (latitude, longitude) = datum.to_wgs84(latitude, longitude);
+            assert_eq!(output.datum, datum);
+            assert_eq!(
+                output.coordinate,
+                lat_lon_to_utm(latitude, longitude).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn datums_report_the_first_error() {
+        let points = [
+            (DatumTag::Nad27, 39.224079, -98.541807),
+            (DatumTag::Ed50, 85., 2.35),
+            (DatumTag::Wgs84, -33.92487, 18.42406),
+            (DatumTag::Wgs84, 0., 181.),
+        ];
+        let empty = FromDatum {
+            coordinate: UtmCoordinate::new(0., 0., 1, 'C'),
+            datum: DatumTag::Wgs84,
+        };
+        let mut output = [empty; 4];
+        assert_eq!(
+            to_utm_from_datums(&points, &mut output),
+            Err(Error {
+                index: 1,
+                kind: ErrorKind::LatitudeOutOfRange
+            })
+        );
+        assert_eq!(output[0].datum, DatumTag::Nad27);
+    }
+
+    #[test]
+    fn extend_stops_at_the_first_error() {
+        let fixes = [(-33.92487, 18.42406), (85., 0.), (50.77535, 6.08389)];
+        let mut coordinates = Vec::new();
+        assert_eq!(
+            to_utm_extend(&fixes, &mut coordinates),
+            Err(Error {
+                index: 1,
+                kind: ErrorKind::LatitudeOutOfRange
+            })
+        );
+        assert_eq!(coordinates.len(), 1);
+        let mut lat_lons = Vec::new();
+        coordinates.push(UtmCoordinate::new(261878., 6243186., 61, 'H'));
+        assert_eq!(
+            to_lat_lon_extend(&coordinates, &mut lat_lons),
+            Err(WSG84ToLatLonError::ZoneNumOutOfRange)
+        );
+        assert_eq!(lat_lons.len(), 1);
+    }
+
+    #[test]
+    fn checked_and_unchecked_agree() {
+        let coordinates = [
+            UtmCoordinate::new(261878., 6243186., 34, 'H'),
+            UtmCoordinate::new(385273.02, 6761077.20, 6, 'V'),
+            UtmCoordinate::new(500000., 0., 31, 'N'),
+        ];
+        let mut checked = [(0., 0.); 3];
+        let mut unchecked = [(0., 0.); 3];
+        assert_eq!(to_lat_lon(&coordinates, &mut checked), Ok(3));
+        assert_eq!(to_lat_lon_unchecked(&coordinates, &mut unchecked), 3);
+        assert_eq!(checked, unchecked);
+        // Both truncate to the shorter slice, like `to_utm`
+        assert_eq!(to_lat_lon(&coordinates, &mut checked[..2]), Ok(2));
+        assert_eq!(to_lat_lon_unchecked(&coordinates[..1], &mut unchecked), 1);
+    }
+
+    #[cfg(feature = "forward")]
+    #[test]
+    fn to_utm_checked_and_unchecked_agree() {
+        let fixes = [(-33.92487, 18.42406), (0., 0.), (60., 4.), (84., 179.99)];
+        let mut checked = [UtmCoordinate::new(0., 0., 1, 'C'); 4];
+        let mut unchecked = checked;
+        assert_eq!(to_utm(&fixes, &mut checked), Ok(4));
+        assert_eq!(to_utm_unchecked(&fixes, &mut unchecked), 4);
+        assert_eq!(checked, unchecked);
+        // Out of range fixes still get a zone and the nearest band
+        assert_eq!(
+            to_utm_unchecked(&[(-85., 190.), (90., 0.)], &mut unchecked),
+            2
+        );
+        assert_eq!((unchecked[0].zone_num, unchecked[0].zone_letter), (60, 'C'));
+        assert_eq!(unchecked[1].zone_letter, 'X');
+        assert_eq!(to_utm_unchecked(&fixes, &mut unchecked[..1]), 1);
+    }
+
+    #[test]
+    fn checked_stops_at_first_error() {
+        let coordinates = [
+            UtmCoordinate::new(261878., 6243186., 34, 'H'),
+            UtmCoordinate::new(261878., 6243186., 61, 'H'),
+        ];
+        let mut lat_lons = [(0., 0.); 2];
+        assert_eq!(
+            to_lat_lon(&coordinates, &mut lat_lons).unwrap_err(),
+            WSG84ToLatLonError::ZoneNumOutOfRange
+        );
+        assert_ne!(lat_lons[0], (0., 0.));
+        assert_eq!(lat_lons[1], (0., 0.));
+    }
+
+    #[test]
+    fn to_utm_stops_at_first_error() {
+        let fixes = [(10., 10.), (20., 20.), (85., 30.), (40., 40.)];
+        let mut coordinates = [UtmCoordinate::new(0., 0., 1, 'C'); 4];
+        assert_eq!(
+            to_utm(&fixes, &mut coordinates),
+            Err(Error {
+                index: 2,
+                kind: ErrorKind::LatitudeOutOfRange
+            })
+        );
+        assert_eq!(coordinates[1].zone_num, 34);
+        assert_eq!(coordinates[2], UtmCoordinate::new(0., 0., 1, 'C'));
+        assert_eq!(
+            to_utm(&[(10., 181.)], &mut coordinates).unwrap_err().kind,
+            ErrorKind::LongitudeOutOfRange
+        );
+    }
+
+    #[test]
+    fn cancelled_before_start() {
+        let cancellation = Cancellation::new();
+        cancellation.cancel();
+        let mut control = Control::new()
+            .with_cancellation(&cancellation)
+            .with_interval(1);
+        let coordinates = [UtmCoordinate::new(261878., 6243186., 34, 'H'); 3];
+        let mut lat_lons = [(0., 0.); 3];
+        // The flag is only checked after an entry, so the first one is always converted.
+        assert_eq!(
+            to_lat_lon_with(&coordinates, &mut lat_lons, &mut control),
+            Ok(1)
+        );
+        assert_eq!(lat_lons[1], (0., 0.));
+    }
+
+    #[test]
+    fn to_utm_round_trip() {
+        let fixes = [(-33.92487, 18.42406), (60.9679, -149.9032)];
+        let mut coordinates = [MaybeUninit::uninit(); 2];
+        assert_eq!(to_utm_uninit(&fixes, &mut coordinates), Ok(2));
+        for (&(latitude, longitude), coordinate) in fixes.iter().zip(coordinates.iter()) {
+            let coordinate = unsafe { coordinate.assume_init() };
+            let (lat, lon) = coordinate.to_lat_lon().unwrap();
+            assert!((lat - latitude).abs() < 1e-5);
+            assert!((lon - longitude).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn reproject_matches_two_passes() {
+        let coordinates = [
+            UtmCoordinate::new(261878., 6243186., 34, 'H'),
+            UtmCoordinate::new(740000., 5000000., 32, 'T'),
+        ];
+        let mut outputs = [Reprojected {
+            lat_lon: (0., 0.),
+            coordinate: UtmCoordinate::new(0., 0., 1, 'C'),
+        }; 2];
+        assert_eq!(reproject(&coordinates, 33, &mut outputs), Ok(2));
+        let mut lat_lons = [(0., 0.); 2];
+        to_lat_lon(&coordinates, &mut lat_lons).unwrap();
+        for (output, &(latitude, longitude)) in outputs.iter().zip(lat_lons.iter()) {
+            assert_eq!(output.lat_lon, (latitude, longitude));
+            let (northing, easting, _) = to_utm_wgs84(latitude, longitude, 33);
+            assert_eq!(
+                output.coordinate,
+                UtmCoordinate::new(easting, northing, 33, lat_to_zone_letter(latitude).unwrap())
+            );
+        }
+        assert_eq!(
+            reproject(&coordinates, 61, &mut outputs),
+            Err(WSG84ToLatLonError::ZoneNumOutOfRange)
+        );
+    }
+}
@@ -0,0 +1,304 @@
+//! Bounding boxes on the UTM grid.
+//!
+//! `UtmBbox` is an axis-aligned box of eastings and northings in one zone, for scheduling tiles
+//! and filtering points without leaving the grid. A geodetic box does not map to a rectangle on
+//! the grid, so the conversions sample each edge at several points and take the extent of the
+//! result, which keeps curved edges from being cut off. Both directions use the classic series
+//! on standard UTM, whether or not a global converter is set.
+
+#[cfg(feature = "forward")]
+use super::converter::UTM_FALSE_NORTHING_SOUTH;
+#[cfg(feature = "forward")]
+use super::series_to_utm_wgs84;
+#[cfg(feature = "inverse")]
+use super::utm_to_lat_lon_unchecked;
+use super::Hemisphere;
+
+/// A box of latitudes and longitudes in decimal degrees.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LatLonBbox {
+    /// The southern edge.
+    pub min_latitude: f64,
+    /// The western edge.
+    pub min_longitude: f64,
+    /// The northern edge.
+    pub max_latitude: f64,
+    /// The eastern edge.
+    pub max_longitude: f64,
+}
+
+/// A box of eastings and northings in meters, in one UTM zone and hemisphere.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UtmBbox {
+    /// The zone number.
+    pub zone: u8,
+    /// The hemisphere, which decides the false northing.
+    pub hemisphere: Hemisphere,
+    /// The western edge.
+    pub min_easting: f64,
+    /// The southern edge.
+    pub min_northing: f64,
+    /// The eastern edge.
+    pub max_easting: f64,
+    /// The northern edge.
+    pub max_northing: f64,
+}
+
+impl UtmBbox {
+    /// Creates a box from its zone, hemisphere, and edges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::bbox::UtmBbox;
+    /// use utm::Hemisphere;
+    /// let bbox = UtmBbox::new(10, Hemisphere::North, 400000., 4500000., 410000., 4510000.);
+    /// ```
+    pub fn new(
+        zone: u8,
+        hemisphere: Hemisphere,
+        min_easting: f64,
+        min_northing: f64,
+        max_easting: f64,
+        max_northing: f64,
+    ) -> UtmBbox {
+        UtmBbox {
+            zone,
+            hemisphere,
+            min_easting,
+            min_northing,
+            max_easting,
+            max_northing,
+        }
+    }
+
+    /// Returns true if the box contains an easting and northing, including its edges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::bbox::UtmBbox;
+    /// use utm::Hemisphere;
+    /// let bbox = UtmBbox::new(10, Hemisphere::North, 400000., 4500000., 410000., 4510000.);
+    /// assert!(bbox.contains(405000., 4500000.));
+    /// assert!(!bbox.contains(399999., 4505000.));
+    /// ```
+    pub fn contains(&self, easting: f64, northing: f64) -> bool {
+        (self.min_easting..=self.max_easting).contains(&easting)
+            && (self.min_northing..=self.max_northing).contains(&northing)
+    }
+
+    /// Returns the overlap of two boxes, or `None` if they don't overlap or are in different
+    /// zones or hemispheres.
+    ///
+    /// Boxes that only share an edge overlap in a box with no area.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::bbox::UtmBbox;
+    /// use utm::Hemisphere;
+    /// let a = UtmBbox::new(10, Hemisphere::North, 400000., 4500000., 410000., 4510000.);
+    /// let b = UtmBbox::new(10, Hemisphere::North, 405000., 4505000., 415000., 4515000.);
+    /// let overlap = a.intersection(&b).unwrap();
+    /// assert_eq!((overlap.min_easting, overlap.max_easting), (405000., 410000.));
+    /// ```
+    pub fn intersection(&self, other: &UtmBbox) -> Option<UtmBbox> {
+        if !self.same_grid(other) {
+            return None;
+        }
+        let bbox = UtmBbox {
+            min_easting: self.min_easting.max(other.min_easting),
+            min_northing: self.min_northing.max(other.min_northing),
+            max_easting: self.max_easting.min(other.max_easting),
+            max_northing: self.max_northing.min(other.max_northing),
+            ..*self
+        };
+        if bbox.min_easting <= bbox.max_easting && bbox.min_northing <= bbox.max_northing {
+            Some(bbox)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the smallest box containing both boxes, or `None` if they are in different zones
+    /// or hemispheres.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::bbox::UtmBbox;
+    /// use utm::Hemisphere;
+    /// let a = UtmBbox::new(10, Hemisphere::North, 400000., 4500000., 410000., 4510000.);
+    /// let b = UtmBbox::new(10, Hemisphere::North, 405000., 4505000., 415000., 4515000.);
+    /// let union = a.union(&b).unwrap();
+    /// assert_eq!((union.min_easting, union.max_easting), (400000., 415000.));
+    /// ```
+    pub fn union(&self, other: &UtmBbox) -> Option<UtmBbox> {
+        if !self.same_grid(other) {
+            return None;
+        }
+        Some(UtmBbox {
+            min_easting: self.min_easting.min(other.min_easting),
+            min_northing: self.min_northing.min(other.min_northing),
+            max_easting: self.max_easting.max(other.max_easting),
+            max_northing: self.max_northing.max(other.max_northing),
+            ..*self
+        })
+    }
+
+    #[cfg(feature = "forward")]
+    /// Returns the grid extent of a geodetic box, projected into a zone and hemisphere.
+    ///
+    /// Each edge of the geodetic box is split into `segments` pieces and every resulting point is
+    /// projected. Northings use the false northing of `hemisphere` even for the part of a box
+    /// across the equator, so the result stays continuous.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::bbox::{LatLonBbox, UtmBbox};
+    /// use utm::Hemisphere;
+    /// let bbox = LatLonBbox {
+    ///     min_latitude: 40.,
+    ///     min_longitude: -124.,
+    ///     max_latitude: 41.,
+    ///     max_longitude: -122.,
+    /// };
+    /// let grid = UtmBbox::from_lat_lon_bbox(&bbox, 10, Hemisphere::North, 16);
+    /// // The western edge bulges out furthest at its southern end.
+    /// assert!(grid.min_easting < 416000.);
+    /// ```
+    pub fn from_lat_lon_bbox(
+        bbox: &LatLonBbox,
+        zone: u8,
+        hemisphere: Hemisphere,
+        segments: usize,
+    ) -> UtmBbox {
+        let mut grid = UtmBbox::new(
+            zone,
+            hemisphere,
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        );
+        let corners = [
+            (bbox.min_latitude, bbox.min_longitude),
+            (bbox.min_latitude, bbox.max_longitude),
+            (bbox.max_latitude, bbox.max_longitude),
+            (bbox.max_latitude, bbox.min_longitude),
+        ];
+        for (latitude, longitude) in densify(&corners, segments) {
+            let (mut northing, easting, _) = series_to_utm_wgs84(latitude, longitude, zone);
+            // The series picks the false northing from the latitude.
+            match hemisphere {
+                Hemisphere::North if latitude < 0. => northing -= UTM_FALSE_NORTHING_SOUTH,
+                Hemisphere::South if latitude >= 0. => northing += UTM_FALSE_NORTHING_SOUTH,
+                _ => {}
+            }
+            grid.extend(easting, northing);
+        }
+        grid
+    }
+
+    #[cfg(feature = "inverse")]
+    /// Returns the geodetic extent of this box.
+    ///
+    /// Each edge of the box is split into `segments` pieces and every resulting point is
+    /// converted. Longitudes are not wrapped, so a box in zone 1 or 60 that extends past the
+    /// antimeridian has a minimum longitude below -180 or a maximum above 180.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::bbox::UtmBbox;
+    /// use utm::Hemisphere;
+    /// let grid = UtmBbox::new(10, Hemisphere::North, 400000., 4500000., 410000., 4510000.);
+    /// let bbox = grid.to_lat_lon_bbox(16);
+    /// assert!(bbox.min_latitude > 40.6 && bbox.max_latitude < 40.8);
+    /// ```
+    pub fn to_lat_lon_bbox(&self, segments: usize) -> LatLonBbox {
+        let mut bbox = LatLonBbox {
+            min_latitude: f64::INFINITY,
+            min_longitude: f64::INFINITY,
+            max_latitude: f64::NEG_INFINITY,
+            max_longitude: f64::NEG_INFINITY,
+        };
+        let corners = [
+            (self.min_easting, self.min_northing),
+            (self.max_easting, self.min_northing),
+            (self.max_easting, self.max_northing),
+            (self.min_easting, self.max_northing),
+        ];
+        let northern = self.hemisphere == Hemisphere::North;
+        for (easting, northing) in densify(&corners, segments) {
+            let (latitude, longitude) =
+                utm_to_lat_lon_unchecked(easting, northing, self.zone, northern);
+            bbox.min_latitude = bbox.min_latitude.min(latitude);
+            bbox.min_longitude = bbox.min_longitude.min(longitude);
+            bbox.max_latitude = bbox.max_latitude.max(latitude);
+            bbox.max_longitude = bbox.max_longitude.max(longitude);
+        }
+        bbox
+    }
+
+    #[cfg(feature = "forward")]
+    fn extend(&mut self, easting: f64, northing: f64) {
+        self.min_easting = self.min_easting.min(easting);
+        self.min_northing = self.min_northing.min(northing);
+        self.max_easting = self.max_easting.max(easting);
+        self.max_northing = self.max_northing.max(northing);
+    }
+
+    fn same_grid(&self, other: &UtmBbox) -> bool {
+        self.zone == other.zone && self.hemisphere == other.hemisphere
+    }
+}
+
+/// Returns the points along the closed ring through `corners`, with each side split into
+/// `segments` pieces.
+#[cfg(any(feature = "forward", feature = "inverse"))]
+fn densify(corners: &[(f64, f64); 4], segments: usize) -> impl Iterator<Item = (f64, f64)> + '_ {
+    let segments = segments.max(1);
+    (0..4).flat_map(move |side| {
+        let (x0, y0) = corners[side];
+        let (x1, y1) = corners[(side + 1) % 4];
+        (0..segments).map(move |i| {
+            let t = i as f64 / segments as f64;
+            (x0 + t * (x1 - x0), y0 + t * (y1 - y0))
+        })
+    })
+}
+
+#[cfg(all(test, feature = "forward", feature = "inverse"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_contains_original() {
+        let bbox = LatLonBbox {
+            min_latitude: -1.,
+            min_longitude: 14.,
+            max_latitude: 1.,
+            max_longitude: 16.,
+        };
+        let grid = UtmBbox::from_lat_lon_bbox(&bbox, 33, Hemisphere::South, 32);
+        assert!(grid.max_northing > 10000000.);
+        let back = grid.to_lat_lon_bbox(32);
+        assert!(back.min_latitude <= bbox.min_latitude + 1e-6);
+        assert!(back.max_latitude >= bbox.max_latitude - 1e-6);
+        assert!(back.min_longitude <= bbox.min_longitude + 1e-6);
+        assert!(back.max_longitude >= bbox.max_longitude - 1e-6);
+    }
+
+    #[test]
+    fn different_zones() {
+        let a = UtmBbox::new(10, Hemisphere::North, 0., 0., 1., 1.);
+        let b = UtmBbox::new(11, Hemisphere::North, 0., 0., 1., 1.);
+        assert_eq!(a.intersection(&b), None);
+        assert_eq!(a.union(&b), None);
+        let c = UtmBbox::new(10, Hemisphere::North, 2., 2., 3., 3.);
+        assert_eq!(a.intersection(&c), None);
+    }
+}
@@ -0,0 +1,285 @@
+//! Bring two coordinates from different zones onto one grid.
+//!
+//! Distances and bearings between points in different zones can't be taken from their eastings
+//! and northings, which live in different planes. The usual fixes are ad hoc: re-zone one point
+//! into the other's zone, whichever comes first, and accept the distortion of a point far from
+//! its central meridian. `bridge` instead tries both zones and a transverse Mercator grid
+//! centered between the two points, and returns the two points on whichever distorts the
+//! distance between them least, along with how much it does.
+//!
+//! Distortion is measured against the geodesic between the points from `geodesic`: the
+//! relative error of the grid distance, and the angle between the grid bearing of the straight
+//! line and the geodesic's bearing turned to grid north, the arc-to-chord correction.
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use std::f64::consts::PI;
+use std::fmt;
+
+use super::converter::{Converter, UTM_FALSE_EASTING};
+use super::geodesic::{self, Geodesic};
+use super::tm::TransverseMercator;
+use super::{UtmCoordinate, WSG84ToLatLonError};
+
+/// A grid two coordinates can be brought onto.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Grid {
+    /// A UTM zone.
+    Zone(u8),
+    /// A transverse Mercator grid with a scale factor of one on a central meridian in decimal
+    /// degrees, and the UTM false easting and northing.
+    Central {
+        /// The central meridian in decimal degrees.
+        central_meridian: f64,
+    },
+}
+
+/// Errors that can occur when bridging two coordinates.
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// A coordinate's zone number or letter is out of range.
+    Coordinate(WSG84ToLatLonError),
+    /// The geodesic between the points could not be solved.
+    Geodesic(geodesic::Error),
+}
+
+impl From<WSG84ToLatLonError> for Error {
+    fn from(err: WSG84ToLatLonError) -> Error {
+        Error::Coordinate(err)
+    }
+}
+
+impl From<geodesic::Error> for Error {
+    fn from(err: geodesic::Error) -> Error {
+        Error::Geodesic(err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Coordinate(err) => write!(f, "Invalid coordinate: {}", err),
+            Error::Geodesic(err) => write!(f, "Invalid geodesic: {}", err),
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for Error {}
+
+/// Two coordinates on a common grid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bridge {
+    /// The common grid.
+    pub grid: Grid,
+    /// The projection of the common grid, in the hemisphere of the first coordinate.
+    pub projection: TransverseMercator,
+    /// The easting and northing of the first coordinate on the common grid.
+    pub first: (f64, f64),
+    /// The easting and northing of the second coordinate on the common grid.
+    pub second: (f64, f64),
+    /// The grid distance over the geodesic distance, minus one.
+    pub distance_error: f64,
+    /// The grid bearing of the straight line from the first coordinate to the second minus the
+    /// grid bearing of the geodesic, in radians.
+    pub bearing_error: f64,
+}
+
+impl Bridge {
+    /// Returns the straight line distance in meters between the two coordinates on the grid.
+    pub fn grid_distance(&self) -> f64 {
+        (self.second.0 - self.first.0).hypot(self.second.1 - self.first.1)
+    }
+
+    /// Returns the bearing in radians clockwise from grid north of the straight line from the
+    /// first coordinate to the second, between zero and a full turn.
+    pub fn grid_bearing(&self) -> f64 {
+        wrap((self.second.0 - self.first.0).atan2(self.second.1 - self.first.1))
+    }
+}
+
+/// Brings two coordinates onto the grid, of their two zones and a grid centered between them,
+/// that distorts the distance between them least.
+///
+/// # Examples
+///
+/// ```
+/// use utm::bridge::{bridge, Grid};
+/// use utm::converter::Converter;
+/// // Either side of the boundary between zones 31 and 32
+/// let converter = Converter::new();
+/// let first = converter.to_utm(48., 5.8).unwrap();
+/// let second = converter.to_utm(48.2, 6.3).unwrap();
+/// let bridge = bridge(&first, &second).unwrap();
+/// match bridge.grid {
+///     Grid::Central { central_meridian } => assert!((central_meridian - 6.05).abs() < 1e-9),
+///     Grid::Zone(_) => panic!("A zone distorts more than a grid between the points"),
+/// }
+/// assert!(bridge.distance_error.abs() < 1e-5);
+/// assert!((bridge.grid_distance() - 43375.).abs() < 1.);
+/// ```
+pub fn bridge(first: &UtmCoordinate, second: &UtmCoordinate) -> Result<Bridge, Error> {
+    let converter = Converter::new();
+    let (_, first_longitude) = converter.to_lat_lon(first)?;
+    let (_, second_longitude) = converter.to_lat_lon(second)?;
+    let central_meridian = midpoint(first_longitude, second_longitude);
+    let mut best = bridge_in(first, second, Grid::Zone(first.zone_num))?;
+    for &grid in &[
+        Grid::Zone(second.zone_num),
+        Grid::Central { central_meridian },
+    ] {
+        let candidate = bridge_in(first, second, grid)?;
+        if candidate.distance_error.abs() < best.distance_error.abs() {
+            best = candidate;
+        }
+    }
+    Ok(best)
+}
+
+/// Brings two coordinates onto a given grid.
+///
+/// # Examples
+///
+/// ```
+/// use utm::bridge::{bridge_in, Grid};
+/// use utm::converter::Converter;
+/// let converter = Converter::new();
+/// let first = converter.to_utm(48., 5.8).unwrap();
+/// let second = converter.to_utm(48.2, 6.3).unwrap();
+/// // In zone 31, the second point is about 250 km from the central meridian
+/// let bridge = bridge_in(&first, &second, Grid::Zone(31)).unwrap();
+/// assert!(bridge.distance_error > 2e-4);
+/// assert_eq!(bridge.first, (first.easting, first.northing));
+/// ```
+pub fn bridge_in(
+    first: &UtmCoordinate,
+    second: &UtmCoordinate,
+    grid: Grid,
+) -> Result<Bridge, Error> {
+    let converter = Converter::new();
+    let start = converter.to_lat_lon(first)?;
+    let end = converter.to_lat_lon(second)?;
+    let northern = first.zone_letter >= 'N';
+    let projection = match grid {
+        Grid::Zone(zone_num) => {
+            if !(1..=60).contains(&zone_num) {
+                return Err(WSG84ToLatLonError::ZoneNumOutOfRange.into());
+            }
+            converter.projection(zone_num, northern)
+        }
+        Grid::Central { central_meridian } => TransverseMercator::new(
+            converter.ellipsoid(),
+            0.,
+            central_meridian,
+            1.,
+            UTM_FALSE_EASTING,
+            converter.false_northing(northern),
+        ),
+    };
+    let (northing, easting, convergence) = projection.forward(start.0, start.1);
+    let first_grid = (easting, northing);
+    let (northing, easting, _) = projection.forward(end.0, end.1);
+    let second_grid = (easting, northing);
+    let geodesic = Geodesic::between(converter.ellipsoid(), start, end)?;
+    let mut bridge = Bridge {
+        grid,
+        projection,
+        first: first_grid,
+        second: second_grid,
+        distance_error: 0.,
+        bearing_error: 0.,
+    };
+    if geodesic.distance() > 0. {
+        bridge.distance_error = bridge.grid_distance() / geodesic.distance() - 1.;
+        let geodesic_bearing = geodesic.initial_azimuth().to_radians() + convergence;
+        bridge.bearing_error = wrap(bridge.grid_bearing() - geodesic_bearing + PI) - PI;
+    }
+    Ok(bridge)
+}
+
+/// Returns the longitude in decimal degrees halfway along the shorter way between two
+/// longitudes.
+fn midpoint(a: f64, b: f64) -> f64 {
+    let delta = wrap_degrees(b - a + 180.) - 180.;
+    wrap_degrees(a + delta / 2. + 180.) - 180.
+}
+
+/// Wraps an angle in radians into [0, 2π).
+fn wrap(angle: f64) -> f64 {
+    let wrapped = angle % (2. * PI);
+    if wrapped < 0. {
+        wrapped + 2. * PI
+    } else {
+        wrapped
+    }
+}
+
+/// Wraps an angle in decimal degrees into [0, 360).
+fn wrap_degrees(angle: f64) -> f64 {
+    let wrapped = angle % 360.;
+    if wrapped < 0. {
+        wrapped + 360.
+    } else {
+        wrapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn across_a_boundary() {
+        let converter = Converter::new();
+        let first = converter.to_utm(48., 5.8).unwrap();
+        let second = converter.to_utm(48.2, 6.3).unwrap();
+        let west = bridge_in(&first, &second, Grid::Zone(31)).unwrap();
+        let east = bridge_in(&first, &second, Grid::Zone(32)).unwrap();
+        let central = bridge(&first, &second).unwrap();
+        // Every grid agrees on the distance once its distortion is taken out
+        let distance = central.grid_distance() / (1. + central.distance_error);
+        for bridge in &[west, east] {
+            let corrected = bridge.grid_distance() / (1. + bridge.distance_error);
+            assert!((corrected - distance).abs() < 1e-6);
+            assert!(bridge.distance_error > 100. * central.distance_error.abs());
+        }
+        // Arc-to-chord corrections are well under a minute of arc this close
+        for bridge in &[west, east, central] {
+            assert!(
+                bridge.bearing_error.abs() < 1e-4,
+                "{}",
+                bridge.bearing_error
+            );
+        }
+        assert_eq!(east.second, (second.easting, second.northing));
+    }
+
+    #[test]
+    fn same_point() {
+        let coordinate = Converter::new().to_utm(-33.92487, 18.42406).unwrap();
+        let bridge = bridge(&coordinate, &coordinate).unwrap();
+        assert_eq!(bridge.grid, Grid::Zone(34));
+        assert_eq!((bridge.distance_error, bridge.bearing_error), (0., 0.));
+        assert_eq!(bridge.grid_distance(), 0.);
+    }
+
+    #[test]
+    fn across_the_antimeridian() {
+        assert_eq!(midpoint(179., -179.), -180.);
+        assert_eq!(midpoint(-179.5, 178.5), 179.5);
+        let converter = Converter::new();
+        let first = converter.to_utm(-17., 179.6).unwrap();
+        let second = converter.to_utm(-17.1, -179.8).unwrap();
+        let bridge = bridge(&first, &second).unwrap();
+        match bridge.grid {
+            Grid::Central { central_meridian } => {
+                assert!((central_meridian - 179.9).abs() < 1e-9)
+            }
+            Grid::Zone(_) => panic!("{:?}", bridge.grid),
+        }
+        assert!(bridge.distance_error.abs() < 1e-5);
+        assert!(bridge.grid_distance() > 60000. && bridge.grid_distance() < 70000.);
+    }
+}
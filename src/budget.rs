@@ -0,0 +1,201 @@
+//! Flag conversions whose estimated error exceeds a stated budget.
+//!
+//! Precision agriculture and survey pipelines state how much error they can take, e.g. a
+//! centimeter, and would rather set a point aside than silently use a worse one. A budgeted
+//! conversion returns `Budgeted::Within` when its estimated error fits the budget and
+//! `Budgeted::Exceeded`, with the estimate, when it doesn't, so those points can be quarantined.
+//!
+//! Errors are estimated against Krüger's series, which is within `KRUGER_ERROR` of the exact
+//! transverse Mercator projection everywhere in the UTM domain. The classic series behind
+//! `to_utm_wgs84` and `wsg84_utm_to_lat_lon` is good to millimeters near the central meridian,
+//! but is off by meters far out of its zone, so `to_utm_budgeted` and `to_lat_lon_budgeted`
+//! convert each point both ways and use the difference. They always use the classic series,
+//! even with a global converter set. `converter::Converter` conversions use Krüger's series
+//! directly; see `Converter::with_error_budget`.
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+#[cfg(any(feature = "forward", feature = "inverse"))]
+use super::converter::Converter;
+#[cfg(feature = "forward")]
+use super::series_to_utm_wgs84;
+#[cfg(feature = "inverse")]
+use super::{series_wsg84_utm_to_lat_lon, WSG84ToLatLonError};
+
+/// The error in meters of Krüger's series to sixth order within 3,900 km of the central
+/// meridian, after Karney (2011).
+pub const KRUGER_ERROR: f64 = 5e-9;
+
+/// The mean radius of the earth in meters, used for ground distances.
+#[cfg(feature = "inverse")]
+const EARTH_RADIUS: f64 = 6371008.8;
+
+/// The result of a conversion with an error budget.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Budgeted<T> {
+    /// The estimated error is within the budget.
+    Within(T),
+    /// The estimated error exceeds the budget.
+    Exceeded {
+        /// The converted value.
+        value: T,
+        /// The estimated error in meters.
+        estimated_error: f64,
+    },
+}
+
+impl<T> Budgeted<T> {
+    /// Returns `Within` if the estimated error in meters is within the budget, and `Exceeded`
+    /// if it is larger or not a number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::budget::Budgeted;
+    /// assert_eq!(Budgeted::new(1, 0.005, 0.01), Budgeted::Within(1));
+    /// assert!(!Budgeted::new(1, 0.02, 0.01).is_within());
+    /// ```
+    pub fn new(value: T, estimated_error: f64, budget: f64) -> Budgeted<T> {
+        if estimated_error <= budget {
+            Budgeted::Within(value)
+        } else {
+            Budgeted::Exceeded {
+                value,
+                estimated_error,
+            }
+        }
+    }
+
+    /// Returns true if the estimated error is within the budget.
+    pub fn is_within(&self) -> bool {
+        match self {
+            Budgeted::Within(_) => true,
+            Budgeted::Exceeded { .. } => false,
+        }
+    }
+
+    /// Returns the value if the estimated error is within the budget.
+    pub fn within(self) -> Option<T> {
+        match self {
+            Budgeted::Within(value) => Some(value),
+            Budgeted::Exceeded { .. } => None,
+        }
+    }
+
+    /// Returns the value, whether or not it is within the budget.
+    pub fn value(self) -> T {
+        match self {
+            Budgeted::Within(value) | Budgeted::Exceeded { value, .. } => value,
+        }
+    }
+}
+
+#[cfg(feature = "forward")]
+/// Converts a latitude and longitude in decimal degrees into a zone, like `to_utm_wgs84`, and
+/// checks the estimated error of the northing and easting against a budget in meters.
+///
+/// # Examples
+///
+/// ```
+/// use utm::budget::to_utm_budgeted;
+/// // Inside the zone, the classic series is good to well under a centimeter
+/// assert!(to_utm_budgeted(45., 10., 32, 0.01).is_within());
+/// // Ten degrees out of it, it isn't
+/// assert!(!to_utm_budgeted(45., 19., 32, 0.01).is_within());
+/// ```
+pub fn to_utm_budgeted(
+    latitude: f64,
+    longitude: f64,
+    zone: u8,
+    budget: f64,
+) -> Budgeted<(f64, f64, f64)> {
+    let value = series_to_utm_wgs84(latitude, longitude, zone);
+    let (northing, easting, _) = Converter::new()
+        .projection(zone, latitude >= 0.)
+        .forward(latitude, longitude);
+    let estimated_error = (value.0 - northing).hypot(value.1 - easting) + KRUGER_ERROR;
+    Budgeted::new(value, estimated_error, budget)
+}
+
+#[cfg(feature = "inverse")]
+/// Converts a UTM coordinate to a latitude and longitude, like `wsg84_utm_to_lat_lon`, and
+/// checks the estimated ground error in meters against a budget.
+///
+/// # Examples
+///
+/// ```
+/// use utm::budget::{to_lat_lon_budgeted, Budgeted};
+/// // Close to the central meridian, the classic series is good to a centimeter
+/// assert!(to_lat_lon_budgeted(499000., 6243186., 34, 'H', 0.01).unwrap().is_within());
+/// // 240 km from it, it isn't
+/// match to_lat_lon_budgeted(261878., 6243186., 34, 'H', 0.01).unwrap() {
+///     Budgeted::Exceeded { value: (latitude, _), estimated_error } => {
+///         assert!((latitude + 33.92487).abs() < 1e-4);
+///         assert!(estimated_error > 0.01 && estimated_error < 1.);
+///     }
+///     Budgeted::Within(_) => unreachable!(),
+/// }
+/// ```
+pub fn to_lat_lon_budgeted(
+    easting: f64,
+    northing: f64,
+    zone_num: u8,
+    zone_letter: char,
+    budget: f64,
+) -> Result<Budgeted<(f64, f64)>, WSG84ToLatLonError> {
+    let value = series_wsg84_utm_to_lat_lon(easting, northing, zone_num, zone_letter)?;
+    let reference = Converter::new()
+        .projection(zone_num, zone_letter >= 'N')
+        .inverse(easting, northing);
+    let estimated_error = ground_distance(value, reference) + KRUGER_ERROR;
+    Ok(Budgeted::new(value, estimated_error, budget))
+}
+
+/// Returns the approximate ground distance in meters between two nearby latitudes and
+/// longitudes in decimal degrees.
+#[cfg(feature = "inverse")]
+fn ground_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let dlat = (b.0 - a.0).to_radians();
+    let dlon = ((b.1 - a.1 + 540.) % 360. - 180.).to_radians();
+    EARTH_RADIUS * dlat.hypot(dlon * a.0.to_radians().cos())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(all(feature = "forward", feature = "inverse"))]
+    #[test]
+    fn series_error_grows_away_from_the_central_meridian() {
+        let mut previous = 0.;
+        for &longitude in &[9., 11., 13., 15., 17., 19.] {
+            let estimated_error = match to_utm_budgeted(45., longitude, 32, 0.) {
+                Budgeted::Exceeded {
+                    estimated_error, ..
+                } => estimated_error,
+                Budgeted::Within(_) => panic!("a zero budget can't be met"),
+            };
+            assert!(estimated_error >= previous);
+            previous = estimated_error;
+            assert_eq!(estimated_error <= 0.01, longitude < 16.);
+        }
+        // The classic inverse degrades faster, to centimeters inside the zone
+        let (northing, easting, _) = series_to_utm_wgs84(45., 9., 32);
+        assert!(to_lat_lon_budgeted(easting, northing, 32, 'T', 0.01)
+            .unwrap()
+            .is_within());
+        let (northing, easting, _) = series_to_utm_wgs84(45., 11., 32);
+        assert!(!to_lat_lon_budgeted(easting, northing, 32, 'T', 0.01)
+            .unwrap()
+            .is_within());
+    }
+
+    #[test]
+    fn nan_exceeds() {
+        assert!(!Budgeted::new((), f64::NAN, 1.).is_within());
+        assert_eq!(Budgeted::new(2, f64::NAN, 1.).within(), None);
+        assert_eq!(Budgeted::new(2, f64::NAN, 1.).value(), 2);
+    }
+}
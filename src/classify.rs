@@ -0,0 +1,109 @@
+//! Guess what kind of coordinate a pair of numbers is, for cleaning up tabular data.
+//!
+//! Swapped columns are the most common defect in coordinate files. `classify` looks at the
+//! ranges of two values and guesses whether they are a latitude and longitude, a longitude and
+//! latitude, or a UTM easting and northing (in either order).
+
+/// How sure `classify` is about its guess.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Confidence {
+    /// More than one interpretation fits, and this is the conventional one.
+    Low,
+    /// Only one interpretation fits, but the values are unusual for it.
+    Medium,
+    /// Only one interpretation fits.
+    High,
+}
+
+/// The guessed meaning of a pair of numbers `(a, b)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Classification {
+    /// `a` is a latitude and `b` is a longitude, in decimal degrees.
+    LatLon(Confidence),
+    /// `a` is a longitude and `b` is a latitude, in decimal degrees.
+    LonLat(Confidence),
+    /// `a` is a UTM easting and `b` is a UTM northing, in meters.
+    EastingNorthing(Confidence),
+    /// `a` is a UTM northing and `b` is a UTM easting, in meters.
+    NorthingEasting(Confidence),
+    /// The values don't look like any kind of coordinate.
+    Unknown,
+}
+
+/// Guesses whether `(a, b)` is a latitude and longitude, a longitude and latitude, or a UTM
+/// easting and northing.
+///
+/// Eastings are expected in the range accepted by `wsg84_utm_to_lat_lon`, 100,000 to 1,000,000
+/// meters, and an easting outside of the 166,000 to 834,000 meters a zone spans at the equator
+/// lowers the confidence. Pairs that fit both geodetic orders, i.e. both values are within
+/// ±90°, are reported as a low confidence `LatLon`.
+///
+/// # Examples
+///
+/// ```
+/// use utm::classify::{classify, Classification, Confidence};
+/// assert_eq!(classify(40.62, -123.45), Classification::LatLon(Confidence::High));
+/// assert_eq!(classify(-123.45, 40.62), Classification::LonLat(Confidence::High));
+/// assert_eq!(classify(40.62, 23.45), Classification::LatLon(Confidence::Low));
+/// assert_eq!(
+///     classify(261878., 6243186.),
+///     Classification::EastingNorthing(Confidence::High)
+/// );
+/// assert_eq!(
+///     classify(6243186., 261878.),
+///     Classification::NorthingEasting(Confidence::High)
+/// );
+/// ```
+pub fn classify(a: f64, b: f64) -> Classification {
+    if !a.is_finite() || !b.is_finite() {
+        return Classification::Unknown;
+    }
+    let lat_lon = a.abs() <= 90. && b.abs() <= 180.;
+    let lon_lat = b.abs() <= 90. && a.abs() <= 180.;
+    let easting_northing = is_easting(a) && is_northing(b);
+    let northing_easting = is_easting(b) && is_northing(a);
+    match (lat_lon, lon_lat, easting_northing, northing_easting) {
+        (true, true, _, _) => Classification::LatLon(Confidence::Low),
+        (true, false, _, _) => Classification::LatLon(Confidence::High),
+        (false, true, _, _) => Classification::LonLat(Confidence::High),
+        (_, _, true, true) => Classification::EastingNorthing(Confidence::Low),
+        (_, _, true, false) => Classification::EastingNorthing(easting_confidence(a)),
+        (_, _, false, true) => Classification::NorthingEasting(easting_confidence(b)),
+        _ => Classification::Unknown,
+    }
+}
+
+fn is_easting(value: f64) -> bool {
+    (100000. ..1000000.).contains(&value)
+}
+
+fn is_northing(value: f64) -> bool {
+    (0. ..=10000000.).contains(&value)
+}
+
+fn easting_confidence(easting: f64) -> Confidence {
+    if (166000. ..=834000.).contains(&easting) {
+        Confidence::High
+    } else {
+        Confidence::Medium
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ambiguous_and_unknown() {
+        assert_eq!(
+            classify(500000., 600000.),
+            Classification::EastingNorthing(Confidence::Low)
+        );
+        assert_eq!(
+            classify(950000., 5000000.),
+            Classification::EastingNorthing(Confidence::Medium)
+        );
+        assert_eq!(classify(-500., 12.), Classification::Unknown);
+        assert_eq!(classify(f64::NAN, 12.), Classification::Unknown);
+    }
+}
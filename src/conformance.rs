@@ -0,0 +1,519 @@
+//! Differential testing between conversion backends, behind the `conformance` feature.
+//!
+//! Downstream projects that wrap this crate, or swap in their own projection code, can use
+//! `compare` to check that two backends agree over a region and fail their own CI when a change
+//! makes them drift. Samples are taken on a regular grid or from a seeded random generator, so
+//! a run is reproducible, and the result is a `Statistics` summary that prints as a single line
+//! of `key=value` pairs for logs and dashboards. Any type that implements `Backend` can be
+//! compared, and `analysis::Algorithm::Kruger` is the bundled reference. `distribution` keeps
+//! every discrepancy, for quantiles.
+//!
+//! With the `generic` feature, `SinglePrecision` runs the series in `f32`, so teams whose
+//! embedded targets only have single precision can check that it meets their accuracy
+//! requirement over their operating region during development.
+
+use std::fmt;
+
+use super::analysis::{self, Algorithm, Direction};
+use super::bbox::LatLonBbox;
+#[cfg(feature = "generic")]
+use super::generic;
+use super::lat_lon_to_zone_number;
+
+/// A conversion backend to compare.
+pub trait Backend {
+    /// Projects a latitude and longitude in decimal degrees into a zone and returns the
+    /// northing and easting in meters, or `None` if the backend can't convert it.
+    ///
+    /// Like `to_utm_wgs84`, the false northing is added south of the equator.
+    fn forward(&self, latitude: f64, longitude: f64, zone: u8) -> Option<(f64, f64)>;
+
+    /// Converts an easting and northing in meters in a zone and hemisphere back to a latitude
+    /// and longitude in decimal degrees, or returns `None` if the backend can't convert it.
+    fn inverse(&self, easting: f64, northing: f64, zone: u8, northern: bool) -> Option<(f64, f64)>;
+}
+
+impl Backend for Algorithm {
+    fn forward(&self, latitude: f64, longitude: f64, zone: u8) -> Option<(f64, f64)> {
+        Some(analysis::forward(*self, latitude, longitude, zone))
+    }
+
+    fn inverse(&self, easting: f64, northing: f64, zone: u8, northern: bool) -> Option<(f64, f64)> {
+        analysis::inverse(*self, easting, northing, zone, northern)
+    }
+}
+
+/// How to choose the latitudes and longitudes to sample.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Sampling {
+    /// The centers of the cells of a grid over the region.
+    Grid {
+        /// The number of rows.
+        rows: usize,
+        /// The number of columns.
+        columns: usize,
+    },
+    /// Points drawn uniformly in latitude and longitude from a generator with a fixed seed.
+    Random {
+        /// The number of points.
+        count: usize,
+        /// The seed, so that a run can be reproduced.
+        seed: u64,
+    },
+}
+
+/// What `compare` samples and which conversion it compares.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Config {
+    /// The region to sample.
+    pub bbox: LatLonBbox,
+    /// How to sample it.
+    pub sampling: Sampling,
+    /// The conversion to compare, as in `analysis::heat_map`.
+    pub direction: Direction,
+    /// The zone to project every sample into, or `None` for each sample's own zone.
+    pub zone: Option<u8>,
+}
+
+/// A summary of the discrepancies in meters between two backends.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Statistics {
+    /// The number of samples taken.
+    pub samples: usize,
+    /// The number of samples one of the backends couldn't convert, or converted to something
+    /// that isn't finite. They are left out of the other statistics.
+    pub failures: usize,
+    /// The largest discrepancy.
+    pub max: f64,
+    /// The mean discrepancy.
+    pub mean: f64,
+    /// The root mean square discrepancy.
+    pub rms: f64,
+    /// The latitude and longitude of the sample with the largest discrepancy.
+    pub worst: Option<(f64, f64)>,
+}
+
+impl Statistics {
+    /// Returns true if every sample converted and none differed by more than `tolerance`
+    /// meters.
+    pub fn within(&self, tolerance: f64) -> bool {
+        self.failures == 0 && self.max <= tolerance
+    }
+}
+
+impl fmt::Display for Statistics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "samples={} failures={} max={} mean={} rms={}",
+            self.samples, self.failures, self.max, self.mean, self.rms
+        )?;
+        if let Some((latitude, longitude)) = self.worst {
+            write!(
+                f,
+                " worst_latitude={} worst_longitude={}",
+                latitude, longitude
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares two backends over the samples of a configuration.
+///
+/// For `Direction::Inverse`, each sample is projected with the reference, Krüger's series, and
+/// inverted with both backends, and discrepancies are ground distances.
+///
+/// # Examples
+///
+/// ```
+/// use utm::analysis::{Algorithm, Direction};
+/// use utm::bbox::LatLonBbox;
+/// use utm::conformance::{compare, Config, Sampling};
+/// let config = Config {
+///     bbox: LatLonBbox {
+///         min_latitude: -60.,
+///         min_longitude: -180.,
+///         max_latitude: 60.,
+///         max_longitude: 180.,
+///     },
+///     sampling: Sampling::Random { count: 1000, seed: 7 },
+///     direction: Direction::Forward,
+///     zone: None,
+/// };
+/// let statistics = compare(&Algorithm::Series, &Algorithm::Kruger, &config);
+/// assert_eq!(statistics.samples, 1000);
+/// assert!(statistics.within(0.01), "{}", statistics);
+/// ```
+pub fn compare<A: Backend + ?Sized, B: Backend + ?Sized>(
+    a: &A,
+    b: &B,
+    config: &Config,
+) -> Statistics {
+    let mut accumulator = Accumulator::new();
+    sample(a, b, config, |latitude, longitude, discrepancy| {
+        accumulator.add(latitude, longitude, discrepancy)
+    });
+    accumulator.finish()
+}
+
+/// Compares two backends like `compare`, and keeps every discrepancy for quantiles.
+///
+/// # Examples
+///
+/// ```
+/// use utm::analysis::Direction;
+/// use utm::bbox::LatLonBbox;
+/// use utm::conformance::{distribution, Config, Sampling};
+/// # let config = Config {
+/// #     bbox: LatLonBbox {
+/// #         min_latitude: 40.,
+/// #         min_longitude: -126.,
+/// #         max_latitude: 49.,
+/// #         max_longitude: -120.,
+/// #     },
+/// #     sampling: Sampling::Grid { rows: 10, columns: 10 },
+/// #     direction: Direction::Forward,
+/// #     zone: Some(10),
+/// # };
+/// use utm::analysis::Algorithm;
+/// let distribution = distribution(&Algorithm::Series, &Algorithm::Kruger, &config);
+/// assert!(distribution.quantile(0.5).unwrap() <= distribution.statistics().max);
+/// assert_eq!(distribution.fraction_within(1.), 1.);
+/// ```
+pub fn distribution<A: Backend + ?Sized, B: Backend + ?Sized>(
+    a: &A,
+    b: &B,
+    config: &Config,
+) -> Distribution {
+    let mut accumulator = Accumulator::new();
+    let mut discrepancies = Vec::new();
+    sample(a, b, config, |latitude, longitude, discrepancy| {
+        accumulator.add(latitude, longitude, discrepancy);
+        if discrepancy.is_finite() {
+            discrepancies.push(discrepancy);
+        }
+    });
+    discrepancies.sort_by(|a, b| a.total_cmp(b));
+    Distribution {
+        statistics: accumulator.finish(),
+        discrepancies,
+    }
+}
+
+/// Every discrepancy in meters of a comparison, from `distribution`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Distribution {
+    statistics: Statistics,
+    discrepancies: Vec<f64>,
+}
+
+impl Distribution {
+    /// Returns the summary statistics, the same as `compare` returns.
+    pub fn statistics(&self) -> Statistics {
+        self.statistics
+    }
+
+    /// Returns the discrepancy that a fraction `q` of the converted samples are at or below,
+    /// e.g. 0.95 for the 95th percentile, or `None` if no sample converted.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.discrepancies.is_empty() {
+            return None;
+        }
+        let last = self.discrepancies.len() - 1;
+        let index = (q.clamp(0., 1.) * last as f64).round() as usize;
+        Some(self.discrepancies[index])
+    }
+
+    /// Returns the fraction of all samples, failures included, within `tolerance` meters.
+    pub fn fraction_within(&self, tolerance: f64) -> f64 {
+        if self.statistics.samples == 0 {
+            return 0.;
+        }
+        let within = self
+            .discrepancies
+            .iter()
+            .take_while(|&&discrepancy| discrepancy <= tolerance)
+            .count();
+        within as f64 / self.statistics.samples as f64
+    }
+}
+
+/// Calls `f` with the latitude, longitude, and discrepancy of every sample of a configuration,
+/// with a NaN discrepancy for samples that failed.
+fn sample<A: Backend + ?Sized, B: Backend + ?Sized, F: FnMut(f64, f64, f64)>(
+    a: &A,
+    b: &B,
+    config: &Config,
+    mut f: F,
+) {
+    let mut sample = |latitude: f64, longitude: f64| {
+        let zone = config
+            .zone
+            .unwrap_or_else(|| lat_lon_to_zone_number(latitude, longitude));
+        let discrepancy = match config.direction {
+            Direction::Forward => match (
+                a.forward(latitude, longitude, zone),
+                b.forward(latitude, longitude, zone),
+            ) {
+                (Some(a), Some(b)) => (a.0 - b.0).hypot(a.1 - b.1),
+                _ => f64::NAN,
+            },
+            Direction::Inverse => {
+                let (northing, easting) =
+                    analysis::forward(Algorithm::Kruger, latitude, longitude, zone);
+                let northern = latitude >= 0.;
+                match (
+                    a.inverse(easting, northing, zone, northern),
+                    b.inverse(easting, northing, zone, northern),
+                ) {
+                    (Some(a), Some(b)) => analysis::ground_distance(a, b),
+                    _ => f64::NAN,
+                }
+            }
+        };
+        f(latitude, longitude, discrepancy);
+    };
+    let bbox = &config.bbox;
+    let height = bbox.max_latitude - bbox.min_latitude;
+    let width = bbox.max_longitude - bbox.min_longitude;
+    match config.sampling {
+        Sampling::Grid { rows, columns } => {
+            for row in 0..rows {
+                for column in 0..columns {
+                    sample(
+                        bbox.min_latitude + height * (row as f64 + 0.5) / rows as f64,
+                        bbox.min_longitude + width * (column as f64 + 0.5) / columns as f64,
+                    );
+                }
+            }
+        }
+        Sampling::Random { count, seed } => {
+            let mut state = seed;
+            for _ in 0..count {
+                let latitude = bbox.min_latitude + height * unit(&mut state);
+                let longitude = bbox.min_longitude + width * unit(&mut state);
+                sample(latitude, longitude);
+            }
+        }
+    }
+}
+
+/// Running sums for `Statistics`.
+struct Accumulator {
+    statistics: Statistics,
+    sum: f64,
+    sum_of_squares: f64,
+}
+
+impl Accumulator {
+    fn new() -> Accumulator {
+        Accumulator {
+            statistics: Statistics {
+                samples: 0,
+                failures: 0,
+                max: 0.,
+                mean: 0.,
+                rms: 0.,
+                worst: None,
+            },
+            sum: 0.,
+            sum_of_squares: 0.,
+        }
+    }
+
+    fn add(&mut self, latitude: f64, longitude: f64, discrepancy: f64) {
+        let statistics = &mut self.statistics;
+        statistics.samples += 1;
+        if !discrepancy.is_finite() {
+            statistics.failures += 1;
+            return;
+        }
+        self.sum += discrepancy;
+        self.sum_of_squares += discrepancy * discrepancy;
+        if statistics.worst.is_none() || discrepancy > statistics.max {
+            statistics.max = discrepancy;
+            statistics.worst = Some((latitude, longitude));
+        }
+    }
+
+    fn finish(mut self) -> Statistics {
+        let converted = self.statistics.samples - self.statistics.failures;
+        if converted > 0 {
+            self.statistics.mean = self.sum / converted as f64;
+            self.statistics.rms = (self.sum_of_squares / converted as f64).sqrt();
+        }
+        self.statistics
+    }
+}
+
+#[cfg(feature = "generic")]
+/// The series of `to_utm_wgs84` and `wsg84_utm_to_lat_lon` evaluated in single precision, with
+/// `generic::to_utm` and `generic::to_lat_lon` for `f32`.
+///
+/// Compare it with `Algorithm::Series`, the same series in double precision, to see what an
+/// `f32` build costs over an operating region, or with `against_reference` for its total error.
+///
+/// # Examples
+///
+/// ```
+/// use utm::analysis::{Algorithm, Direction};
+/// use utm::bbox::LatLonBbox;
+/// use utm::conformance::{distribution, Config, Sampling, SinglePrecision};
+/// let config = Config {
+///     bbox: LatLonBbox {
+///         min_latitude: 46.,
+///         min_longitude: -120.,
+///         max_latitude: 49.,
+///         max_longitude: -117.,
+///     },
+///     sampling: Sampling::Random { count: 1000, seed: 1 },
+///     direction: Direction::Forward,
+///     zone: None,
+/// };
+/// let distribution = distribution(&Algorithm::Series, &SinglePrecision, &config);
+/// // Single precision is only good to a meter or two this far from the equator
+/// assert!(distribution.quantile(0.95).unwrap() < 2.);
+/// assert!(distribution.fraction_within(0.1) < 0.5);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SinglePrecision;
+
+#[cfg(feature = "generic")]
+impl Backend for SinglePrecision {
+    fn forward(&self, latitude: f64, longitude: f64, zone: u8) -> Option<(f64, f64)> {
+        let (northing, easting, _) = generic::to_utm(latitude as f32, longitude as f32, zone);
+        Some((f64::from(northing), f64::from(easting)))
+    }
+
+    fn inverse(&self, easting: f64, northing: f64, zone: u8, northern: bool) -> Option<(f64, f64)> {
+        let zone_letter = if northern { 'N' } else { 'M' };
+        let (latitude, longitude) =
+            generic::to_lat_lon(easting as f32, northing as f32, zone, zone_letter).ok()?;
+        Some((f64::from(latitude), f64::from(longitude)))
+    }
+}
+
+/// Compares a backend against the bundled reference, Krüger's series.
+pub fn against_reference<B: Backend + ?Sized>(backend: &B, config: &Config) -> Statistics {
+    compare(&Algorithm::Kruger, backend, config)
+}
+
+/// Returns the next number in [0, 1) from a splitmix64 generator.
+fn unit(state: &mut u64) -> f64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Shifted(f64);
+
+    impl Backend for Shifted {
+        fn forward(&self, latitude: f64, longitude: f64, zone: u8) -> Option<(f64, f64)> {
+            let (northing, easting) = Algorithm::Kruger.forward(latitude, longitude, zone)?;
+            Some((northing + self.0, easting))
+        }
+
+        fn inverse(&self, _: f64, _: f64, _: u8, _: bool) -> Option<(f64, f64)> {
+            None
+        }
+    }
+
+    fn config(sampling: Sampling, direction: Direction) -> Config {
+        Config {
+            bbox: LatLonBbox {
+                min_latitude: 10.,
+                min_longitude: 10.,
+                max_latitude: 20.,
+                max_longitude: 20.,
+            },
+            sampling,
+            direction,
+            zone: Some(33),
+        }
+    }
+
+    #[test]
+    fn custom_backend() {
+        let grid = config(
+            Sampling::Grid {
+                rows: 3,
+                columns: 4,
+            },
+            Direction::Forward,
+        );
+        let statistics = against_reference(&Shifted(0.25), &grid);
+        assert_eq!(statistics.samples, 12);
+        assert!((statistics.max - 0.25).abs() < 1e-9);
+        assert!((statistics.rms - 0.25).abs() < 1e-9);
+        assert!(!statistics.within(0.1));
+        let inverse = config(
+            Sampling::Grid {
+                rows: 3,
+                columns: 4,
+            },
+            Direction::Inverse,
+        );
+        let statistics = against_reference(&Shifted(0.25), &inverse);
+        assert_eq!(statistics.failures, 12);
+        assert_eq!(statistics.worst, None);
+    }
+
+    #[test]
+    fn random_is_reproducible() {
+        let random = Config {
+            zone: None,
+            ..config(Sampling::Random { count: 50, seed: 1 }, Direction::Inverse)
+        };
+        let first = compare(&Algorithm::Series, &Algorithm::Kruger, &random);
+        let second = compare(&Algorithm::Series, &Algorithm::Kruger, &random);
+        assert_eq!(first, second);
+        assert!(first.max > 0. && first.within(5.));
+        let (latitude, longitude) = first.worst.unwrap();
+        assert!((10. ..20.).contains(&latitude) && (10. ..20.).contains(&longitude));
+        assert!(first.to_string().starts_with("samples=50 failures=0 max="));
+    }
+
+    #[cfg(feature = "generic")]
+    #[test]
+    fn single_precision() {
+        let grid = config(
+            Sampling::Grid {
+                rows: 10,
+                columns: 10,
+            },
+            Direction::Forward,
+        );
+        let distribution = distribution(&Algorithm::Series, &SinglePrecision, &grid);
+        let statistics = distribution.statistics();
+        assert_eq!(
+            statistics,
+            compare(&Algorithm::Series, &SinglePrecision, &grid)
+        );
+        assert!(
+            statistics.max > 0. && statistics.within(2.),
+            "{}",
+            statistics
+        );
+        let median = distribution.quantile(0.5).unwrap();
+        assert!(median <= distribution.quantile(0.95).unwrap());
+        assert_eq!(distribution.quantile(1.), Some(statistics.max));
+        assert_eq!(distribution.fraction_within(statistics.max), 1.);
+        assert_eq!(distribution.fraction_within(-1.), 0.);
+        let inverse = Config {
+            direction: Direction::Inverse,
+            zone: None,
+            ..grid
+        };
+        let statistics = compare(&Algorithm::Series, &SinglePrecision, &inverse);
+        assert_eq!(statistics.failures, 0);
+        assert!(statistics.within(2.), "{}", statistics);
+    }
+}
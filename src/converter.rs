@@ -0,0 +1,357 @@
+//! A UTM converter with adjustable grid constants.
+//!
+//! Many legacy site grids are UTM in all but their scale factor or false origin, e.g. a mine
+//! grid with a scale factor of one, or a survey grid with a false northing that keeps its
+//! coordinates distinct from real UTM. `Converter` keeps the UTM zones and bands, and lets those
+//! constants be overridden. Projections use Krüger's series from `tm`.
+//!
+//! With the standard library, a converter can also be made the process-wide default with
+//! `set_global`, once at startup. The free functions such as `to_utm_wgs84` and
+//! `wsg84_utm_to_lat_lon` then use its ellipsoid, grid constants, and Krüger's series instead
+//! of the classic series on WGS84, so a whole application switches over without passing a
+//! converter to every call site.
+
+#[cfg(not(feature = "no_std"))]
+use std::sync::OnceLock;
+
+use super::budget::{Budgeted, KRUGER_ERROR};
+use super::tm::TransverseMercator;
+#[cfg(not(feature = "no_std"))]
+use super::workspace::Workspace;
+use super::{lat_lon_to_zone_number, lat_to_zone_letter, Ellipsoid, UtmCoordinate, WGS84};
+#[cfg(feature = "inverse")]
+use super::{WSG84ToLatLonError, ZONE_LETTERS};
+
+/// The UTM scale factor on the central meridian.
+pub const UTM_SCALE_FACTOR: f64 = 0.9996;
+
+/// The UTM false easting, in meters.
+pub const UTM_FALSE_EASTING: f64 = 500000.;
+
+/// The UTM false northing in the southern hemisphere, in meters.
+pub const UTM_FALSE_NORTHING_SOUTH: f64 = 10000000.;
+
+/// Converts between latitude and longitude and a UTM-like grid.
+///
+/// The default converter is standard UTM on WGS84.
+///
+/// # Examples
+///
+/// ```
+/// use utm::converter::Converter;
+/// // A site grid with no scale reduction and a 1,000 km false easting
+/// let converter = Converter::new()
+///     .with_scale_factor(1.)
+///     .with_false_easting(1000000.);
+/// let coordinate = converter.to_utm(-33.92487, 18.42406).unwrap();
+/// assert_eq!((coordinate.zone_num, coordinate.zone_letter), (34, 'H'));
+/// assert!((coordinate.easting - 761783.).abs() < 1.);
+/// # #[cfg(feature = "inverse")] {
+/// let (latitude, longitude) = converter.to_lat_lon(&coordinate).unwrap();
+/// assert!((latitude + 33.92487).abs() < 1e-9 && (longitude - 18.42406).abs() < 1e-9);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Converter {
+    ellipsoid: Ellipsoid,
+    scale_factor: f64,
+    false_easting: f64,
+    false_northing_north: f64,
+    false_northing_south: f64,
+    error_budget: f64,
+}
+
+impl Converter {
+    /// Creates a standard UTM converter on WGS84.
+    pub fn new() -> Converter {
+        Converter {
+            ellipsoid: WGS84,
+            scale_factor: UTM_SCALE_FACTOR,
+            false_easting: UTM_FALSE_EASTING,
+            false_northing_north: 0.,
+            false_northing_south: UTM_FALSE_NORTHING_SOUTH,
+            error_budget: f64::INFINITY,
+        }
+    }
+
+    /// Returns this converter on another ellipsoid.
+    pub fn with_ellipsoid(mut self, ellipsoid: Ellipsoid) -> Converter {
+        self.ellipsoid = ellipsoid;
+        self
+    }
+
+    /// Returns this converter with another scale factor on the central meridian.
+    pub fn with_scale_factor(mut self, scale_factor: f64) -> Converter {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// Returns this converter with another false easting, in meters.
+    pub fn with_false_easting(mut self, false_easting: f64) -> Converter {
+        self.false_easting = false_easting;
+        self
+    }
+
+    /// Returns this converter with other false northings for the northern and southern
+    /// hemispheres, in meters.
+    pub fn with_false_northing(mut self, north: f64, south: f64) -> Converter {
+        self.false_northing_north = north;
+        self.false_northing_south = south;
+        self
+    }
+
+    /// Returns this converter with an error budget in meters for `to_utm_budgeted` and
+    /// `to_lat_lon_budgeted`.
+    pub fn with_error_budget(mut self, error_budget: f64) -> Converter {
+        self.error_budget = error_budget;
+        self
+    }
+
+    /// Returns the ellipsoid.
+    pub fn ellipsoid(&self) -> Ellipsoid {
+        self.ellipsoid
+    }
+
+    /// Returns the scale factor on the central meridian.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Returns the false easting in meters.
+    pub fn false_easting(&self) -> f64 {
+        self.false_easting
+    }
+
+    /// Returns the false northing in meters of the northern or southern hemisphere.
+    pub fn false_northing(&self, northern: bool) -> f64 {
+        if northern {
+            self.false_northing_north
+        } else {
+            self.false_northing_south
+        }
+    }
+
+    /// Returns the error budget in meters, infinite unless set with `with_error_budget`.
+    pub fn error_budget(&self) -> f64 {
+        self.error_budget
+    }
+
+    /// Returns the projection of a zone in one hemisphere.
+    ///
+    /// Building a projection computes its series coefficients, so keep it around when
+    /// converting many points in the same zone.
+    pub fn projection(&self, zone_num: u8, northern: bool) -> TransverseMercator {
+        TransverseMercator::new(
+            self.ellipsoid,
+            0.,
+            f64::from(zone_num) * 6. - 183.,
+            self.scale_factor,
+            self.false_easting,
+            self.false_northing(northern),
+        )
+    }
+
+    /// Converts a latitude and longitude in decimal degrees to a coordinate in its own zone
+    /// and band, including the Norway and Svalbard exceptions.
+    ///
+    /// Returns `None` if the latitude is not between 80°S and 84°N or the longitude is not
+    /// between -180° and 180°.
+    pub fn to_utm(&self, latitude: f64, longitude: f64) -> Option<UtmCoordinate> {
+        if !(-180. ..=180.).contains(&longitude) {
+            return None;
+        }
+        let zone_letter = lat_to_zone_letter(latitude)?;
+        let zone_num = lat_lon_to_zone_number(latitude, longitude);
+        let (northing, easting, _) = self
+            .projection(zone_num, zone_letter >= 'N')
+            .forward(latitude, longitude);
+        Some(UtmCoordinate::new(easting, northing, zone_num, zone_letter))
+    }
+
+    #[cfg(feature = "inverse")]
+    /// Converts a coordinate to a latitude and longitude in decimal degrees.
+    ///
+    /// Only the zone number and letter are checked, since the range of valid eastings and
+    /// northings depends on the grid constants.
+    pub fn to_lat_lon(&self, coordinate: &UtmCoordinate) -> Result<(f64, f64), WSG84ToLatLonError> {
+        if !(1..=60).contains(&coordinate.zone_num) {
+            return Err(WSG84ToLatLonError::ZoneNumOutOfRange);
+        }
+        if !ZONE_LETTERS.contains(coordinate.zone_letter) {
+            return Err(WSG84ToLatLonError::ZoneLetterOutOfRange);
+        }
+        Ok(self
+            .projection(coordinate.zone_num, coordinate.zone_letter >= 'N')
+            .inverse(coordinate.easting, coordinate.northing))
+    }
+
+    /// Converts a latitude and longitude like `to_utm` and checks the estimated error against
+    /// the error budget.
+    ///
+    /// Krüger's series is within `budget::KRUGER_ERROR` of the exact projection everywhere a
+    /// point can be in its own zone, so only budgets below that are exceeded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::converter::Converter;
+    /// let converter = Converter::new().with_error_budget(0.01);
+    /// assert!(converter.to_utm_budgeted(-33.92487, 18.42406).unwrap().is_within());
+    /// ```
+    pub fn to_utm_budgeted(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> Option<Budgeted<UtmCoordinate>> {
+        let coordinate = self.to_utm(latitude, longitude)?;
+        Some(Budgeted::new(coordinate, KRUGER_ERROR, self.error_budget))
+    }
+
+    #[cfg(feature = "inverse")]
+    /// Converts a coordinate like `to_lat_lon` and checks the estimated error against the error
+    /// budget.
+    ///
+    /// The estimate adds the size of the last Newton step of the inverse, as a distance on the
+    /// ground, to `budget::KRUGER_ERROR`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::converter::Converter;
+    /// use utm::UtmCoordinate;
+    /// let converter = Converter::new().with_error_budget(1e-6);
+    /// let coordinate = UtmCoordinate::new(261878., 6243186., 34, 'H');
+    /// assert!(converter.to_lat_lon_budgeted(&coordinate).unwrap().is_within());
+    /// ```
+    pub fn to_lat_lon_budgeted(
+        &self,
+        coordinate: &UtmCoordinate,
+    ) -> Result<Budgeted<(f64, f64)>, WSG84ToLatLonError> {
+        self.to_lat_lon(coordinate)?;
+        let (latitude, longitude, diagnostics) = self
+            .projection(coordinate.zone_num, coordinate.zone_letter >= 'N')
+            .inverse_with_diagnostics(coordinate.easting, coordinate.northing);
+        let step = if diagnostics.converged {
+            0.
+        } else {
+            diagnostics.last_step * self.ellipsoid.semi_major_axis()
+        };
+        Ok(Budgeted::new(
+            (latitude, longitude),
+            KRUGER_ERROR + step,
+            self.error_budget,
+        ))
+    }
+}
+
+impl Default for Converter {
+    fn default() -> Converter {
+        Converter::new()
+    }
+}
+
+/// The process-wide default converter, with its projections built up front.
+#[cfg(not(feature = "no_std"))]
+static GLOBAL: OnceLock<Workspace> = OnceLock::new();
+
+#[cfg(not(feature = "no_std"))]
+/// Makes a converter the process-wide default for the free functions, such as `to_utm_wgs84`
+/// and `wsg84_utm_to_lat_lon`.
+///
+/// The default can only be set once. Set it at startup, before any conversions, since
+/// conversions made before then use the classic series on WGS84. Returns the converter back
+/// if a default was already set. `to_utm_wgs84` and its siblings only use it with the `forward`
+/// feature, and `wsg84_utm_to_lat_lon` only with the `inverse` feature.
+///
+/// `wsg84_utm_to_lat_lon` then only checks the zone number and letter, like
+/// `Converter::to_lat_lon`, so that it accepts whatever `to_utm_wgs84` returns with the
+/// default's false easting and northings.
+///
+/// # Examples
+///
+/// ```
+/// use utm::converter::{self, Converter};
+/// use utm::datum::INTERNATIONAL_1924;
+/// let global = Converter::new()
+///     .with_ellipsoid(INTERNATIONAL_1924)
+///     .with_false_easting(1000000.);
+/// # #[cfg(feature = "forward")] {
+/// let (series, _, _) = utm::to_utm_wgs84(48.8, 2.3, 31);
+/// converter::set_global(global).unwrap();
+/// let (kruger, _, _) = utm::to_utm_wgs84(48.8, 2.3, 31);
+/// assert!((kruger - series).abs() > 1.);
+/// # #[cfg(feature = "inverse")] {
+/// // Eastings beyond the standard range still convert back
+/// let (northing, easting, _) = utm::to_utm_wgs84(48.8, 4.5, 31);
+/// assert!(easting > 1000000.);
+/// let (latitude, longitude) = utm::wsg84_utm_to_lat_lon(easting, northing, 31, 'U').unwrap();
+/// assert!((latitude - 48.8).abs() < 1e-9 && (longitude - 4.5).abs() < 1e-9);
+/// # }
+/// # }
+/// # converter::set_global(global).ok();
+/// assert_eq!(converter::global().unwrap().ellipsoid(), INTERNATIONAL_1924);
+/// assert!(converter::set_global(Converter::new()).is_err());
+/// ```
+pub fn set_global(converter: Converter) -> Result<(), Converter> {
+    let mut converter = Some(converter);
+    GLOBAL.get_or_init(|| Workspace::with_converter(converter.take().unwrap()));
+    match converter {
+        Some(converter) => Err(converter),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+/// Returns the process-wide default converter, or `None` if `set_global` hasn't been called.
+pub fn global() -> Option<Converter> {
+    GLOBAL.get().map(Workspace::converter)
+}
+
+/// Returns the workspace of the process-wide default converter, if one was set.
+#[cfg(all(any(feature = "forward", feature = "inverse"), not(feature = "no_std")))]
+pub(crate) fn global_workspace() -> Option<&'static Workspace> {
+    GLOBAL.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "forward")]
+    use to_utm_wgs84;
+
+    #[cfg(feature = "forward")]
+    #[test]
+    fn default_is_utm() {
+        for &(latitude, longitude) in &[(-33.92487, 18.42406), (60.5, 5.), (0.1, -177.)] {
+            let coordinate = Converter::new().to_utm(latitude, longitude).unwrap();
+            let zone = lat_lon_to_zone_number(latitude, longitude);
+            let (northing, easting, _) = to_utm_wgs84(latitude, longitude, zone);
+            assert_eq!(coordinate.zone_num, zone);
+            assert!((coordinate.easting - easting).abs() < 0.01);
+            assert!((coordinate.northing - northing).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn overrides() {
+        let standard = Converter::new().to_utm(-33.92487, 18.42406).unwrap();
+        let shifted = Converter::new()
+            .with_false_easting(0.)
+            .with_false_northing(0., 20000000.)
+            .to_utm(-33.92487, 18.42406)
+            .unwrap();
+        assert!((shifted.easting - (standard.easting - 500000.)).abs() < 1e-6);
+        assert!((shifted.northing - (standard.northing + 10000000.)).abs() < 1e-6);
+
+        let unscaled = Converter::new().with_scale_factor(1.);
+        let coordinate = unscaled.to_utm(45., 9.).unwrap();
+        assert!((coordinate.easting - 500000.).abs() < 1e-6);
+        assert_eq!(unscaled.false_northing(true), 0.);
+        #[cfg(feature = "inverse")]
+        assert_eq!(
+            unscaled.to_lat_lon(&UtmCoordinate::new(500000., 0., 61, 'N')),
+            Err(WSG84ToLatLonError::ZoneNumOutOfRange)
+        );
+        assert_eq!(Converter::new().to_utm(85., 0.), None);
+    }
+}
@@ -0,0 +1,511 @@
+//! Convert latitude and longitude columns of CSV files in parallel, behind the `csv` feature.
+//!
+//! `convert_file` memory-maps the input, finds the latitude and longitude columns from the
+//! header, splits the rows into chunks on line boundaries, and converts the chunks on the
+//! rayon thread pool with `batch::to_utm`. Rows are never copied into owned strings: fields are
+//! parsed straight out of the mapped bytes. Each chunk is formatted in parallel and written in
+//! order, a bounded number of chunks at a time, so memory use stays flat however large the file
+//! is.
+//!
+//! Every row is written back unchanged with three columns appended: the zone and band, e.g.
+//! `34H`, the easting, and the northing, brought onto the options' `precision::Precision`. Rows
+//! keep their line endings, `\n` or `\r\n`. Rows whose coordinates can't be parsed or converted
+//! get empty columns and are counted in `Summary::failed`.
+//!
+//! `convert_with` and `convert_file_with` take a `batch::Control` for progress reporting and
+//! cancellation, like the batch conversions.
+//!
+//! Fields are split on the delimiter without any quoting rules, so the latitude and longitude
+//! columns, and any columns before them, must not contain quoted delimiters.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use memmap2::Mmap;
+use rayon::prelude::*;
+
+use super::batch::{self, Control};
+use super::precision::Precision;
+use super::UtmCoordinate;
+
+/// The names recognized as latitude columns, compared without regard to case.
+const LATITUDE_NAMES: [&str; 3] = ["lat", "latitude", "y"];
+
+/// The names recognized as longitude columns, compared without regard to case.
+const LONGITUDE_NAMES: [&str; 5] = ["lon", "lng", "long", "longitude", "x"];
+
+/// Errors that can occur when converting a file.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing failed.
+    Io(io::Error),
+    /// No columns were given and none of the header's columns look like a latitude and a
+    /// longitude.
+    Columns,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "I/O error: {}", err),
+            Error::Columns => write!(f, "Could not find latitude and longitude columns"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::Columns => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+/// The zero-based indices of the latitude and longitude columns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Columns {
+    /// The index of the latitude column.
+    pub latitude: usize,
+    /// The index of the longitude column.
+    pub longitude: usize,
+}
+
+/// How to read and write a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    delimiter: u8,
+    header: bool,
+    columns: Option<Columns>,
+    chunk_size: usize,
+    precision: Precision,
+}
+
+impl Options {
+    /// Creates options for a comma separated file with a header, finding the columns from the
+    /// header, with 4 MiB chunks and millimeter output.
+    pub fn new() -> Options {
+        Options {
+            delimiter: b',',
+            header: true,
+            columns: None,
+            chunk_size: 4 << 20,
+            precision: Precision::MILLIMETER,
+        }
+    }
+
+    /// Returns these options with another delimiter.
+    pub fn with_delimiter(mut self, delimiter: u8) -> Options {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Returns these options with given columns instead of finding them from the header.
+    pub fn with_columns(mut self, columns: Columns) -> Options {
+        self.columns = Some(columns);
+        self
+    }
+
+    /// Returns these options for a file without a header, which needs `with_columns`.
+    pub fn without_header(mut self) -> Options {
+        self.header = false;
+        self
+    }
+
+    /// Returns these options with chunks of about `chunk_size` bytes of input.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Options {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Returns these options with eastings and northings written with another precision.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::csv::{convert, Options};
+    /// use utm::precision::{Precision, Rounding};
+    /// let options = Options::new().with_precision(Precision::new(-1, Rounding::Floor));
+    /// let mut output = Vec::new();
+    /// convert(b"lat,lon\n-33.92487,18.42406\n", &mut output, &options).unwrap();
+    /// assert!(output.ends_with(b",34H,261870,6243180\n"));
+    /// ```
+    pub fn with_precision(mut self, precision: Precision) -> Options {
+        self.precision = precision;
+        self
+    }
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options::new()
+    }
+}
+
+/// The number of rows converted and how many of them failed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Summary {
+    /// The number of rows, not counting the header.
+    pub rows: usize,
+    /// The number of rows written with empty UTM columns.
+    pub failed: usize,
+}
+
+/// Finds the latitude and longitude columns of a header row.
+///
+/// Columns named `lat`, `latitude`, or `y`, and `lon`, `lng`, `long`, `longitude`, or `x` are
+/// recognized, in any case and with surrounding whitespace or quotes.
+///
+/// # Examples
+///
+/// ```
+/// use utm::csv::{find_columns, Columns};
+/// let columns = find_columns(b"id,Longitude,Latitude", b',').unwrap();
+/// assert_eq!(columns, Columns { latitude: 2, longitude: 1 });
+/// ```
+pub fn find_columns(header: &[u8], delimiter: u8) -> Option<Columns> {
+    let mut latitude = None;
+    let mut longitude = None;
+    for (index, name) in header.split(|&byte| byte == delimiter).enumerate() {
+        let name = match std::str::from_utf8(name) {
+            Ok(name) => name.trim().trim_matches('"'),
+            Err(_) => continue,
+        };
+        let is = |names: &[&str]| names.iter().any(|n| n.eq_ignore_ascii_case(name));
+        if latitude.is_none() && is(&LATITUDE_NAMES) {
+            latitude = Some(index);
+        } else if longitude.is_none() && is(&LONGITUDE_NAMES) {
+            longitude = Some(index);
+        }
+    }
+    Some(Columns {
+        latitude: latitude?,
+        longitude: longitude?,
+    })
+}
+
+/// Converts the rows of CSV data in memory and writes them to `output`.
+///
+/// # Examples
+///
+/// ```
+/// use utm::csv::{convert, Options, Summary};
+/// let input = b"name,lat,lon\ncape town,-33.92487,18.42406\nnowhere,91,0\n";
+/// let mut output = Vec::new();
+/// let summary = convert(input, &mut output, &Options::new()).unwrap();
+/// assert_eq!(summary, Summary { rows: 2, failed: 1 });
+/// let output = String::from_utf8(output).unwrap();
+/// let mut lines = output.lines();
+/// assert_eq!(lines.next(), Some("name,lat,lon,utm_zone,utm_easting,utm_northing"));
+/// assert!(lines.next().unwrap().starts_with("cape town,-33.92487,18.42406,34H,"));
+/// assert_eq!(lines.next(), Some("nowhere,91,0,,,"));
+/// ```
+pub fn convert<W: Write>(
+    input: &[u8],
+    output: &mut W,
+    options: &Options,
+) -> Result<Summary, Error> {
+    convert_with(input, output, options, &mut Control::new())
+}
+
+/// Converts the rows of CSV data in memory and writes them to `output`, with progress
+/// reporting and cancellation.
+///
+/// Progress is counted in bytes of input after the header, and is reported after every group
+/// of chunks converted in parallel rather than every `interval` rows. A cancelled conversion
+/// stops after the group it was cancelled in and returns the rows written so far.
+///
+/// # Examples
+///
+/// ```
+/// use utm::batch::{Cancellation, Control};
+/// use utm::csv::{convert_with, Options};
+/// let mut input = b"lat,lon\n".to_vec();
+/// for _ in 0..1000 {
+///     input.extend_from_slice(b"-33.92487,18.42406\n");
+/// }
+/// let cancellation = Cancellation::new();
+/// let mut progress = |done: usize, total: usize| {
+///     assert!(done <= total);
+///     cancellation.cancel();
+/// };
+/// let mut control = Control::new()
+///     .with_progress(&mut progress)
+///     .with_cancellation(&cancellation);
+/// let options = Options::new().with_chunk_size(64);
+/// let mut output = Vec::new();
+/// let summary = convert_with(&input, &mut output, &options, &mut control).unwrap();
+/// assert!(summary.rows > 0 && summary.rows < 1000);
+/// ```
+pub fn convert_with<W: Write>(
+    input: &[u8],
+    output: &mut W,
+    options: &Options,
+    control: &mut Control,
+) -> Result<Summary, Error> {
+    let (header, body) = if options.header {
+        match input.iter().position(|&byte| byte == b'\n') {
+            Some(end) => (&input[..end + 1], &input[end + 1..]),
+            None => (input, &input[input.len()..]),
+        }
+    } else {
+        (&input[..0], input)
+    };
+    let (header, ending) = split_line_ending(header);
+    let columns = match options.columns {
+        Some(columns) => columns,
+        None => find_columns(header, options.delimiter).ok_or(Error::Columns)?,
+    };
+    if options.header && !input.is_empty() {
+        let delimiter = char::from(options.delimiter);
+        output.write_all(header)?;
+        write!(
+            output,
+            "{}utm_zone{}utm_easting{}utm_northing",
+            delimiter, delimiter, delimiter
+        )?;
+        output.write_all(ending)?;
+    }
+    let chunks = split(body, options.chunk_size);
+    let mut summary = Summary::default();
+    let mut done = 0;
+    for group in chunks.chunks(4 * rayon::current_num_threads()) {
+        let converted: Vec<(Vec<u8>, Summary)> = group
+            .par_iter()
+            .map(|chunk| convert_chunk(chunk, columns, options))
+            .collect();
+        for (bytes, chunk_summary) in converted {
+            output.write_all(&bytes)?;
+            summary.rows += chunk_summary.rows;
+            summary.failed += chunk_summary.failed;
+        }
+        done += group.iter().map(|chunk| chunk.len()).sum::<usize>();
+        if !control.report(done, body.len()) {
+            break;
+        }
+    }
+    Ok(summary)
+}
+
+/// Memory-maps a CSV file, converts its rows, and writes them to another file.
+///
+/// The input must not be modified while it is being converted, or the mapped bytes can change
+/// under the parser.
+pub fn convert_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    input: P,
+    output: Q,
+    options: &Options,
+) -> Result<Summary, Error> {
+    convert_file_with(input, output, options, &mut Control::new())
+}
+
+/// Memory-maps a CSV file, converts its rows, and writes them to another file, with progress
+/// reporting and cancellation like `convert_with`.
+///
+/// A cancelled conversion leaves the rows written so far in the output file.
+pub fn convert_file_with<P: AsRef<Path>, Q: AsRef<Path>>(
+    input: P,
+    output: Q,
+    options: &Options,
+    control: &mut Control,
+) -> Result<Summary, Error> {
+    let file = File::open(input)?;
+    let mut output = BufWriter::new(File::create(output)?);
+    let summary = if file.metadata()?.len() == 0 {
+        convert_with(&[], &mut output, options, control)?
+    } else {
+        // Safety: the map is only read, and the caller keeps the file unchanged while it is.
+        let map = unsafe { Mmap::map(&file)? };
+        convert_with(&map, &mut output, options, control)?
+    };
+    output.flush()?;
+    Ok(summary)
+}
+
+/// Splits rows into chunks of about `size` bytes, each ending at a line boundary.
+fn split(mut body: &[u8], size: usize) -> Vec<&[u8]> {
+    let mut chunks = Vec::new();
+    while !body.is_empty() {
+        let end = if body.len() <= size {
+            body.len()
+        } else {
+            body[size..]
+                .iter()
+                .position(|&byte| byte == b'\n')
+                .map_or(body.len(), |offset| size + offset + 1)
+        };
+        chunks.push(&body[..end]);
+        body = &body[end..];
+    }
+    chunks
+}
+
+/// Converts and formats the rows of one chunk.
+fn convert_chunk(chunk: &[u8], columns: Columns, options: &Options) -> (Vec<u8>, Summary) {
+    let lines: Vec<(&[u8], &[u8])> = chunk
+        .split_inclusive(|&byte| byte == b'\n')
+        .map(split_line_ending)
+        .filter(|(line, _)| !line.is_empty())
+        .collect();
+    let lat_lons: Vec<(f64, f64)> = lines
+        .iter()
+        .map(|(line, _)| {
+            (
+                field(line, columns.latitude, options.delimiter),
+                field(line, columns.longitude, options.delimiter),
+            )
+        })
+        .collect();
+    let mut coordinates = vec![UtmCoordinate::new(0., 0., 1, 'C'); lines.len()];
+    let mut failed = vec![false; lines.len()];
+    let mut start = 0;
+    // The batch conversion stops at the first row it can't convert, so pick up after it.
+    while let Err(error) = batch::to_utm(&lat_lons[start..], &mut coordinates[start..]) {
+        failed[start + error.index] = true;
+        start += error.index + 1;
+    }
+    let mut bytes = Vec::with_capacity(chunk.len() + lines.len() * 32);
+    let mut summary = Summary {
+        rows: lines.len(),
+        failed: 0,
+    };
+    let delimiter = char::from(options.delimiter);
+    let precision = options.precision;
+    let decimals = precision.decimals().max(0) as usize;
+    for (((line, ending), coordinate), &failed) in lines.iter().zip(&coordinates).zip(&failed) {
+        bytes.extend_from_slice(line);
+        // Writing to a Vec can't fail.
+        let _ = if failed {
+            summary.failed += 1;
+            write!(bytes, "{}{}{}", delimiter, delimiter, delimiter)
+        } else {
+            write!(
+                bytes,
+                "{}{}{}{}{:.*}{}{:.*}",
+                delimiter,
+                coordinate.zone_num,
+                coordinate.zone_letter,
+                delimiter,
+                decimals,
+                precision.quantize(coordinate.easting),
+                delimiter,
+                decimals,
+                precision.quantize(coordinate.northing)
+            )
+        };
+        bytes.extend_from_slice(ending);
+    }
+    (bytes, summary)
+}
+
+/// Parses a field as a number, or returns NaN, which no conversion accepts.
+fn field(line: &[u8], index: usize, delimiter: u8) -> f64 {
+    line.split(|&byte| byte == delimiter)
+        .nth(index)
+        .and_then(|field| std::str::from_utf8(field).ok())
+        .and_then(|field| field.trim().trim_matches('"').parse().ok())
+        .unwrap_or(f64::NAN)
+}
+
+/// Strips a trailing carriage return.
+fn trim_line(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Splits a line into its contents and its line ending, `\n`, `\r\n`, or nothing.
+fn split_line_ending(line: &[u8]) -> (&[u8], &[u8]) {
+    let contents = line.strip_suffix(b"\n").map_or(line, trim_line);
+    line.split_at(contents.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(rows: usize) -> Vec<u8> {
+        let mut input = b"id;x;y\r\n".to_vec();
+        for i in 0..rows {
+            let latitude = -79. + (i % 160) as f64;
+            let longitude = -179.5 + (i % 359) as f64;
+            write!(input, "{};{};{}\r\n", i, longitude, latitude).unwrap();
+        }
+        input
+    }
+
+    #[test]
+    fn chunks_match_one_pass() {
+        let input = input(1000);
+        let options = Options::new().with_delimiter(b';');
+        let mut whole = Vec::new();
+        let summary = convert(&input, &mut whole, &options).unwrap();
+        assert_eq!(
+            summary,
+            Summary {
+                rows: 1000,
+                failed: 0
+            }
+        );
+        let mut chunked = Vec::new();
+        let options = options.with_chunk_size(100);
+        assert_eq!(convert(&input, &mut chunked, &options).unwrap(), summary);
+        assert_eq!(whole, chunked);
+        let text = String::from_utf8(whole).unwrap();
+        assert_eq!(text.lines().count(), 1001);
+        assert_eq!(text.matches("\r\n").count(), 1001);
+        assert!(text.lines().nth(1).unwrap().starts_with("0;-179.5;-79;1C;"));
+    }
+
+    #[test]
+    fn columns() {
+        assert_eq!(find_columns(b"a,b", b','), None);
+        let mut output = Vec::new();
+        assert!(matches!(
+            convert(b"a,b\n1,2\n", &mut output, &Options::new()),
+            Err(Error::Columns)
+        ));
+        let options = Options::new().without_header().with_columns(Columns {
+            latitude: 1,
+            longitude: 0,
+        });
+        let summary = convert(b"18.42406,-33.92487\nbad", &mut output, &options).unwrap();
+        assert_eq!(summary, Summary { rows: 2, failed: 1 });
+        assert!(output.starts_with(b"18.42406,-33.92487,34H,"));
+        assert!(output.ends_with(b"\nbad,,,"));
+    }
+
+    #[test]
+    fn files() {
+        let directory = std::env::temp_dir();
+        let input_path = directory.join(format!("utm-csv-{}-in.csv", std::process::id()));
+        let output_path = directory.join(format!("utm-csv-{}-out.csv", std::process::id()));
+        std::fs::write(&input_path, input(10)).unwrap();
+        let options = Options::new()
+            .with_delimiter(b';')
+            .with_precision(Precision::METER);
+        let summary = convert_file(&input_path, &output_path, &options).unwrap();
+        assert_eq!(summary.rows, 10);
+        let output = std::fs::read_to_string(&output_path).unwrap();
+        assert!(output.starts_with("id;x;y;utm_zone;utm_easting;utm_northing\r\n"));
+        std::fs::write(&input_path, b"").unwrap();
+        let summary = convert_file(
+            &input_path,
+            &output_path,
+            &options.with_columns(Columns {
+                latitude: 0,
+                longitude: 1,
+            }),
+        )
+        .unwrap();
+        assert_eq!(summary, Summary::default());
+        std::fs::remove_file(&input_path).unwrap();
+        std::fs::remove_file(&output_path).unwrap();
+    }
+}
@@ -0,0 +1,235 @@
+//! Space-filling curve keys for sorting UTM coordinates by location.
+//!
+//! Databases and file formats read faster when points that are close on the ground are close
+//! in storage. Sorting by easting, or by northing, only keeps neighbours together along one
+//! axis. A space-filling curve visits every cell of a grid in an order that keeps nearby cells
+//! mostly nearby, so sorting by its position, the key, keeps points together in both.
+//!
+//! Coordinates are first brought onto a grid of cells with a `precision::Precision`, e.g. one
+//! cell per centimeter, with `quantize`. `morton` and `hilbert` then turn a cell into a 64 bit
+//! key. Morton (Z-order) keys just interleave the bits of the cell's column and row, so they
+//! are cheap and easy to compute in SQL. Hilbert keys cost a little more, but the curve never
+//! jumps, so consecutive keys are always adjacent cells and ranges of keys are more compact.
+//!
+//! Keys from different zones, or from different hemispheres of one zone, are on different
+//! grids, so sort by zone number and hemisphere first.
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use super::precision::Precision;
+use super::UtmCoordinate;
+
+/// Returns the column and row of the cell a coordinate's easting and northing fall in, on a
+/// grid with a precision's step and rounding.
+///
+/// Returns `None` if either is negative or too large for 32 bits of cells. At a centimeter,
+/// that is about 43,000 km, which covers every zone; at a millimeter, northings over about
+/// 4,295 km don't fit.
+///
+/// # Examples
+///
+/// ```
+/// use utm::curve::quantize;
+/// use utm::precision::Precision;
+/// use utm::UtmCoordinate;
+/// let coordinate = UtmCoordinate::new(261878.126, 6243186.654, 34, 'H');
+/// assert_eq!(
+///     quantize(&coordinate, Precision::CENTIMETER),
+///     Some((26187813, 624318665))
+/// );
+/// assert_eq!(quantize(&coordinate, Precision::MILLIMETER), None);
+/// ```
+pub fn quantize(coordinate: &UtmCoordinate, precision: Precision) -> Option<(u32, u32)> {
+    let cell = |value: f64| {
+        let cell = (precision.quantize(value) / precision.step()).round();
+        if (0. ..=f64::from(u32::MAX)).contains(&cell) {
+            Some(cell as u32)
+        } else {
+            None
+        }
+    };
+    Some((cell(coordinate.easting)?, cell(coordinate.northing)?))
+}
+
+/// Returns the Morton key of a cell, with the bits of the column in the even positions and
+/// the bits of the row in the odd ones.
+///
+/// # Examples
+///
+/// ```
+/// use utm::curve::{morton, morton_cell};
+/// assert_eq!(morton(0b11, 0b00), 0b0101);
+/// assert_eq!(morton(0b00, 0b11), 0b1010);
+/// assert_eq!(morton_cell(morton(26187813, 624318665)), (26187813, 624318665));
+/// ```
+pub fn morton(column: u32, row: u32) -> u64 {
+    spread(column) | spread(row) << 1
+}
+
+/// Returns the column and row of the cell with a Morton key.
+pub fn morton_cell(key: u64) -> (u32, u32) {
+    (compact(key), compact(key >> 1))
+}
+
+/// Returns the Hilbert key of a cell, its position along a Hilbert curve through all
+/// 2^32 by 2^32 cells that starts at the origin.
+///
+/// # Examples
+///
+/// ```
+/// use utm::curve::{hilbert, hilbert_cell};
+/// // Consecutive keys are always side by side
+/// let (column, row) = hilbert_cell(1234567);
+/// let (next_column, next_row) = hilbert_cell(1234568);
+/// assert_eq!(column.abs_diff(next_column) + row.abs_diff(next_row), 1);
+/// assert_eq!(hilbert(column, row), 1234567);
+/// ```
+pub fn hilbert(column: u32, row: u32) -> u64 {
+    let (mut x, mut y) = (column, row);
+    let mut key = 0;
+    let mut s = 1u32 << 31;
+    while s > 0 {
+        let rx = u64::from(x & s != 0);
+        let ry = u64::from(y & s != 0);
+        key += u64::from(s) * u64::from(s) * ((3 * rx) ^ ry);
+        // Rotate the quadrant so the curve through it starts and ends in the right corners
+        if ry == 0 {
+            if rx == 1 {
+                x = !x;
+                y = !y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s >>= 1;
+    }
+    key
+}
+
+/// Returns the column and row of the cell with a Hilbert key.
+pub fn hilbert_cell(key: u64) -> (u32, u32) {
+    let (mut x, mut y) = (0u32, 0u32);
+    let mut t = key;
+    let mut s = 1u32;
+    loop {
+        let rx = (t >> 1) as u32 & 1;
+        let ry = (t as u32 ^ rx) & 1;
+        if ry == 0 {
+            if rx == 1 {
+                x = s - 1 - x;
+                y = s - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        x += s * rx;
+        y += s * ry;
+        t >>= 2;
+        if s == 1 << 31 {
+            return (x, y);
+        }
+        s <<= 1;
+    }
+}
+
+/// Returns the Morton key of a coordinate on a grid with a precision, see `quantize`.
+pub fn morton_key(coordinate: &UtmCoordinate, precision: Precision) -> Option<u64> {
+    let (column, row) = quantize(coordinate, precision)?;
+    Some(morton(column, row))
+}
+
+/// Returns the Hilbert key of a coordinate on a grid with a precision, see `quantize`.
+///
+/// # Examples
+///
+/// ```
+/// use utm::curve::hilbert_key;
+/// use utm::precision::Precision;
+/// use utm::UtmCoordinate;
+/// let mut coordinates = vec![
+///     UtmCoordinate::new(500010., 4000000., 33, 'S'),
+///     UtmCoordinate::new(900000., 4000000., 33, 'S'),
+///     UtmCoordinate::new(500000., 4000010., 33, 'S'),
+/// ];
+/// coordinates.sort_by_key(|c| hilbert_key(c, Precision::METER));
+/// assert_eq!(coordinates[2].easting, 900000.);
+/// ```
+pub fn hilbert_key(coordinate: &UtmCoordinate, precision: Precision) -> Option<u64> {
+    let (column, row) = quantize(coordinate, precision)?;
+    Some(hilbert(column, row))
+}
+
+/// Moves the bits of a value to the even bit positions.
+fn spread(value: u32) -> u64 {
+    let mut value = u64::from(value);
+    value = (value | value << 16) & 0x0000_ffff_0000_ffff;
+    value = (value | value << 8) & 0x00ff_00ff_00ff_00ff;
+    value = (value | value << 4) & 0x0f0f_0f0f_0f0f_0f0f;
+    value = (value | value << 2) & 0x3333_3333_3333_3333;
+    (value | value << 1) & 0x5555_5555_5555_5555
+}
+
+/// Gathers the even bits of a value, undoing `spread`.
+fn compact(value: u64) -> u32 {
+    let mut value = value & 0x5555_5555_5555_5555;
+    value = (value | value >> 1) & 0x3333_3333_3333_3333;
+    value = (value | value >> 2) & 0x0f0f_0f0f_0f0f_0f0f;
+    value = (value | value >> 4) & 0x00ff_00ff_00ff_00ff;
+    value = (value | value >> 8) & 0x0000_ffff_0000_ffff;
+    ((value | value >> 16) & 0xffff_ffff) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_round_trip() {
+        for &(column, row) in &[
+            (0, 0),
+            (1, 0),
+            (0, 1),
+            (u32::MAX, u32::MAX),
+            (u32::MAX, 0),
+            (26187813, 624318665),
+        ] {
+            assert_eq!(morton_cell(morton(column, row)), (column, row));
+            assert_eq!(hilbert_cell(hilbert(column, row)), (column, row));
+        }
+        assert_eq!(hilbert(0, 0), 0);
+        assert_eq!(hilbert_cell(u64::MAX), (u32::MAX, 0));
+    }
+
+    #[test]
+    fn hilbert_keys_step_to_neighbours() {
+        let mut previous = hilbert_cell(0);
+        for key in 1..4096 {
+            let cell = hilbert_cell(key);
+            assert_eq!(
+                previous.0.abs_diff(cell.0) + previous.1.abs_diff(cell.1),
+                1,
+                "{}",
+                key
+            );
+            previous = cell;
+        }
+        // The first 4^k keys fill a 2^k square
+        assert!((0..1024).all(|key| {
+            let (column, row) = hilbert_cell(key);
+            column < 32 && row < 32
+        }));
+    }
+
+    #[test]
+    fn quantize_rejects_what_doesnt_fit() {
+        let coordinate = UtmCoordinate::new(-1., 1000., 31, 'N');
+        assert_eq!(quantize(&coordinate, Precision::METER), None);
+        let coordinate = UtmCoordinate::new(f64::NAN, 1000., 31, 'N');
+        assert_eq!(quantize(&coordinate, Precision::METER), None);
+        let coordinate = UtmCoordinate::new(999999.6, 9999999.4, 31, 'N');
+        assert_eq!(
+            quantize(&coordinate, Precision::METER),
+            Some((1000000, 9999999))
+        );
+    }
+}
@@ -0,0 +1,310 @@
+//! Geodetic coordinates tagged with their datum.
+//!
+//! The projection functions in this crate assume WGS84 latitudes and longitudes. Legacy data
+//! is often on an older datum, such as NAD27, and projecting it as if it were WGS84 silently
+//! shifts every point by tens or hundreds of meters. `Geodetic` carries the datum as a type
+//! parameter, so only WGS84 coordinates can be projected and everything else has to go through
+//! an explicit `transform_to`.
+//!
+//! ```compile_fail
+//! use utm::datum::{Geodetic, Nad27};
+//! let point = Geodetic::<Nad27>::new(39.224079, -98.541807);
+//! let _ = point.to_utm_no_zone();
+//! ```
+
+use std::marker::PhantomData;
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+#[cfg(feature = "forward")]
+use super::{lat_lon_to_zone_number, series_to_utm_wgs84};
+use super::{Ellipsoid, WGS84};
+
+/// The GRS80 ellipsoid, used by NAD83, ETRS89, and most modern national datums.
+pub const GRS80: Ellipsoid = Ellipsoid::new(6378137.0, 1.0 / 298.257222101);
+
+/// The Clarke 1866 ellipsoid, used by NAD27.
+pub const CLARKE_1866: Ellipsoid = Ellipsoid::new(6378206.4, 1.0 / 294.978698214);
+
+/// The International 1924 (Hayford) ellipsoid, used by ED50.
+pub const INTERNATIONAL_1924: Ellipsoid = Ellipsoid::new(6378388.0, 1.0 / 297.0);
+
+/// The Clarke 1880 ellipsoid, used by several African datums.
+pub const CLARKE_1880: Ellipsoid = Ellipsoid::new(6378249.145, 1.0 / 293.465);
+
+/// The Bessel 1841 ellipsoid, used by Tokyo Datum and several central European datums.
+pub const BESSEL_1841: Ellipsoid = Ellipsoid::new(6377397.155, 1.0 / 299.1528128);
+
+/// A geodetic datum, used as a type-level tag.
+pub trait Datum {
+    /// The reference ellipsoid of this datum.
+    const ELLIPSOID: Ellipsoid;
+
+    /// The geocentric translation (dx, dy, dz) in meters from this datum to WGS84.
+    const TO_WGS84: (f64, f64, f64);
+}
+
+/// World Geodetic System 1984.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Wgs84;
+
+/// North American Datum of 1983, treated as coincident with WGS84.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Nad83;
+
+/// North American Datum of 1927, using the mean shift for the conterminous United States.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Nad27;
+
+/// European Datum 1950, using the western European mean shift.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ed50;
+
+impl Datum for Wgs84 {
+    const ELLIPSOID: Ellipsoid = WGS84;
+    const TO_WGS84: (f64, f64, f64) = (0., 0., 0.);
+}
+
+impl Datum for Nad83 {
+    const ELLIPSOID: Ellipsoid = GRS80;
+    const TO_WGS84: (f64, f64, f64) = (0., 0., 0.);
+}
+
+impl Datum for Nad27 {
+    const ELLIPSOID: Ellipsoid = CLARKE_1866;
+    const TO_WGS84: (f64, f64, f64) = (-8., 160., 176.);
+}
+
+impl Datum for Ed50 {
+    const ELLIPSOID: Ellipsoid = INTERNATIONAL_1924;
+    const TO_WGS84: (f64, f64, f64) = (-87., -98., -121.);
+}
+
+/// A datum chosen at runtime, for data that mixes datums point by point.
+///
+/// Each tag has the same ellipsoid and shift as the type of the same name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DatumTag {
+    /// World Geodetic System 1984, see `Wgs84`.
+    Wgs84,
+    /// North American Datum of 1983, see `Nad83`.
+    Nad83,
+    /// North American Datum of 1927, see `Nad27`.
+    Nad27,
+    /// European Datum 1950, see `Ed50`.
+    Ed50,
+}
+
+impl DatumTag {
+    /// Returns the reference ellipsoid of this datum.
+    pub fn ellipsoid(self) -> Ellipsoid {
+        match self {
+            DatumTag::Wgs84 => Wgs84::ELLIPSOID,
+            DatumTag::Nad83 => Nad83::ELLIPSOID,
+            DatumTag::Nad27 => Nad27::ELLIPSOID,
+            DatumTag::Ed50 => Ed50::ELLIPSOID,
+        }
+    }
+
+    /// Returns the geocentric translation (dx, dy, dz) in meters from this datum to WGS84.
+    pub fn to_wgs84_translation(self) -> (f64, f64, f64) {
+        match self {
+            DatumTag::Wgs84 => Wgs84::TO_WGS84,
+            DatumTag::Nad83 => Nad83::TO_WGS84,
+            DatumTag::Nad27 => Nad27::TO_WGS84,
+            DatumTag::Ed50 => Ed50::TO_WGS84,
+        }
+    }
+
+    /// Transforms a latitude and longitude in decimal degrees on this datum to WGS84, like
+    /// `Geodetic::transform_to`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::datum::{DatumTag, Geodetic, Nad27, Wgs84};
+    /// let (latitude, longitude) = DatumTag::Nad27.to_wgs84(39.224079, -98.541807);
+    /// let typed = Geodetic::<Nad27>::new(39.224079, -98.541807).transform_to::<Wgs84>();
+    /// assert!((latitude - typed.latitude()).abs() < 1e-12);
+    /// assert!((longitude - typed.longitude()).abs() < 1e-12);
+    /// ```
+    pub fn to_wgs84(self, latitude: f64, longitude: f64) -> (f64, f64) {
+        if self == DatumTag::Wgs84 {
+            return (latitude, longitude);
+        }
+        molodensky(
+            latitude,
+            longitude,
+            self.ellipsoid(),
+            WGS84,
+            self.to_wgs84_translation(),
+        )
+    }
+}
+
+/// A latitude and longitude in decimal degrees on the datum `D`.
+pub struct Geodetic<D> {
+    latitude: f64,
+    longitude: f64,
+    datum: PhantomData<D>,
+}
+
+impl<D: Datum> Geodetic<D> {
+    /// Creates a new geodetic coordinate from a latitude and longitude in decimal degrees.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::datum::{Geodetic, Nad27};
+    /// let meades_ranch = Geodetic::<Nad27>::new(39.224079, -98.541807);
+    /// ```
+    pub fn new(latitude: f64, longitude: f64) -> Geodetic<D> {
+        Geodetic {
+            latitude,
+            longitude,
+            datum: PhantomData,
+        }
+    }
+
+    /// Returns the latitude in decimal degrees.
+    pub fn latitude(&self) -> f64 {
+        self.latitude
+    }
+
+    /// Returns the longitude in decimal degrees.
+    pub fn longitude(&self) -> f64 {
+        self.longitude
+    }
+
+    /// Transforms this coordinate to another datum.
+    ///
+    /// Uses the standard Molodensky transformation through WGS84 with the datum's mean
+    /// translation, assuming a point on the ellipsoid. Expect errors of a few meters, which is
+    /// the accuracy of the published mean shifts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::datum::{Geodetic, Nad27, Wgs84};
+    /// let nad27 = Geodetic::<Nad27>::new(39.224079, -98.541807);
+    /// let wgs84 = nad27.transform_to::<Wgs84>();
+    /// # #[cfg(feature = "forward")] {
+    /// let (northing, easting, _) = wgs84.to_utm_no_zone();
+    /// # }
+    /// ```
+    pub fn transform_to<T: Datum>(&self) -> Geodetic<T> {
+        let (dx, dy, dz) = D::TO_WGS84;
+        let (latitude, longitude) = molodensky(
+            self.latitude,
+            self.longitude,
+            D::ELLIPSOID,
+            WGS84,
+            (dx, dy, dz),
+        );
+        let (dx, dy, dz) = T::TO_WGS84;
+        let (latitude, longitude) =
+            molodensky(latitude, longitude, WGS84, T::ELLIPSOID, (-dx, -dy, -dz));
+        Geodetic::new(latitude, longitude)
+    }
+}
+
+#[cfg(feature = "forward")]
+impl Geodetic<Wgs84> {
+    /// Converts this coordinate to UTM coordinates in the given zone.
+    ///
+    /// Returns the northing, easting, and meridian convergence, like `to_utm_wgs84`. The
+    /// coordinate is on WGS84, so this always uses the classic series on WGS84, even if a global
+    /// converter on another ellipsoid was set with `converter::set_global`.
+    pub fn to_utm(&self, zone: u8) -> (f64, f64, f64) {
+        series_to_utm_wgs84(self.latitude, self.longitude, zone)
+    }
+
+    /// Converts this coordinate to UTM coordinates in its own zone.
+    ///
+    /// Returns the northing, easting, and meridian convergence, like `to_utm_wgs84_no_zone`,
+    /// and like `to_utm` regardless of a global converter.
+    pub fn to_utm_no_zone(&self) -> (f64, f64, f64) {
+        self.to_utm(lat_lon_to_zone_number(self.latitude, self.longitude))
+    }
+}
+
+impl<D> Clone for Geodetic<D> {
+    fn clone(&self) -> Geodetic<D> {
+        *self
+    }
+}
+
+impl<D> Copy for Geodetic<D> {}
+
+impl<D> std::fmt::Debug for Geodetic<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Geodetic")
+            .field("latitude", &self.latitude)
+            .field("longitude", &self.longitude)
+            .finish()
+    }
+}
+
+impl<D> PartialEq for Geodetic<D> {
+    fn eq(&self, other: &Geodetic<D>) -> bool {
+        self.latitude == other.latitude && self.longitude == other.longitude
+    }
+}
+
+/// Shifts a latitude and longitude in decimal degrees between two ellipsoids with the standard
+/// Molodensky formulas, for a point at zero height.
+fn molodensky(
+    latitude: f64,
+    longitude: f64,
+    from: Ellipsoid,
+    to: Ellipsoid,
+    (dx, dy, dz): (f64, f64, f64),
+) -> (f64, f64) {
+    let phi = latitude.to_radians();
+    let lambda = longitude.to_radians();
+    let a = from.a;
+    let f = from.f;
+    let da = to.a - from.a;
+    let df = to.f - from.f;
+    let b = a * (1. - f);
+    let e2 = 2. * f - f * f;
+
+    let (sin_phi, cos_phi) = (phi.sin(), phi.cos());
+    let (sin_lambda, cos_lambda) = (lambda.sin(), lambda.cos());
+    let w2 = 1. - e2 * sin_phi * sin_phi;
+    let rn = a / w2.sqrt();
+    let rm = a * (1. - e2) / (w2 * w2.sqrt());
+
+    let dphi = (-dx * sin_phi * cos_lambda - dy * sin_phi * sin_lambda
+        + dz * cos_phi
+        + da * rn * e2 * sin_phi * cos_phi / a
+        + df * (rm * a / b + rn * b / a) * sin_phi * cos_phi)
+        / rm;
+    let dlambda = (-dx * sin_lambda + dy * cos_lambda) / (rn * cos_phi);
+
+    ((phi + dphi).to_degrees(), (lambda + dlambda).to_degrees())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn meades_ranch() {
+        // NAD27 and NAD83 positions of the NAD27 datum origin.
+        let nad27 = Geodetic::<Nad27>::new(39.224079444, -98.541807222);
+        let nad83 = nad27.transform_to::<Nad83>();
+        assert!((nad83.latitude() - 39.224086722).abs() * 111000. < 10.);
+        assert!((nad83.longitude() - -98.542151500).abs() * 86000. < 10.);
+    }
+
+    #[test]
+    fn round_trip() {
+        let ed50 = Geodetic::<Ed50>::new(48.8566, 2.3522);
+        let back = ed50.transform_to::<Wgs84>().transform_to::<Ed50>();
+        assert!((ed50.latitude() - back.latitude()).abs() < 1e-6);
+        assert!((ed50.longitude() - back.longitude()).abs() < 1e-6);
+    }
+}
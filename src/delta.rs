@@ -0,0 +1,289 @@
+//! Compact delta encoding of UTM fix streams, for bandwidth-limited telemetry links.
+//!
+//! Consecutive fixes from a tracker are close together, so after quantizing eastings and
+//! northings to a `Precision` the differences between fixes are small integers. Each fix is
+//! written as a zigzag varint pair: the first in full, every later one as the change from its
+//! predecessor. A walker sampled once a second at centimeter precision costs about four bytes a
+//! fix.
+//!
+//! The zone is not part of the stream, so every fix has to be in the same zone. Nothing here
+//! allocates, and both ends work on caller-provided buffers.
+
+use std::fmt;
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use super::precision::Precision;
+
+/// The most bytes a single fix can take, two ten byte varints.
+pub const MAX_FIX_LEN: usize = 20;
+
+/// Errors that can occur when encoding or decoding a stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The output buffer is too small for the fix. Nothing was written.
+    BufferTooSmall,
+    /// The input ends in the middle of a fix.
+    Truncated,
+    /// A value doesn't fit in 64 bits, either when quantizing a coordinate or when decoding.
+    Overflow,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::BufferTooSmall => write!(f, "Output buffer too small for the fix"),
+            Error::Truncated => write!(f, "Input ends in the middle of a fix"),
+            Error::Overflow => write!(f, "Value does not fit in 64 bits"),
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for Error {}
+
+/// Writes eastings and northings as a delta encoded stream.
+///
+/// # Examples
+///
+/// ```
+/// use utm::delta::{Decoder, Encoder};
+/// use utm::precision::Precision;
+/// let track = [(261878.12, 6243186.34), (261879.03, 6243187.11), (261880.2, 6243187.9)];
+/// let mut encoder = Encoder::new(Precision::CENTIMETER);
+/// let mut buffer = [0u8; 64];
+/// let mut len = 0;
+/// for &(easting, northing) in &track {
+///     len += encoder.encode(easting, northing, &mut buffer[len..]).unwrap();
+/// }
+/// // Nine bytes for the first fix, then four for each delta
+/// assert_eq!(len, 17);
+///
+/// let mut decoder = Decoder::new(Precision::CENTIMETER);
+/// let mut offset = 0;
+/// for &(easting, northing) in &track {
+///     let ((e, n), read) = decoder.decode(&buffer[offset..len]).unwrap();
+///     assert!((e - easting).abs() < 0.005 && (n - northing).abs() < 0.005);
+///     offset += read;
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Encoder {
+    precision: Precision,
+    previous: Option<(i64, i64)>,
+}
+
+impl Encoder {
+    /// Creates an encoder that quantizes to `precision`.
+    pub fn new(precision: Precision) -> Encoder {
+        Encoder {
+            precision,
+            previous: None,
+        }
+    }
+
+    /// Writes the next fix to the start of `output` and returns the number of bytes written.
+    ///
+    /// At most `MAX_FIX_LEN` bytes are written. If `output` is too small the encoder is left as
+    /// it was, so the fix can be retried with a fresh buffer.
+    pub fn encode(
+        &mut self,
+        easting: f64,
+        northing: f64,
+        output: &mut [u8],
+    ) -> Result<usize, Error> {
+        let current = (
+            to_steps(self.precision, easting)?,
+            to_steps(self.precision, northing)?,
+        );
+        let (easting_delta, northing_delta) = match self.previous {
+            Some(previous) => (
+                current.0.wrapping_sub(previous.0),
+                current.1.wrapping_sub(previous.1),
+            ),
+            None => current,
+        };
+        let mut scratch = [0u8; MAX_FIX_LEN];
+        let mut len = write_varint(zigzag(easting_delta), &mut scratch);
+        len += write_varint(zigzag(northing_delta), &mut scratch[len..]);
+        if output.len() < len {
+            return Err(Error::BufferTooSmall);
+        }
+        output[..len].copy_from_slice(&scratch[..len]);
+        self.previous = Some(current);
+        Ok(len)
+    }
+
+    /// Makes the next fix a full one instead of a delta, e.g. so a receiver can join mid-stream.
+    ///
+    /// The decoder has to be reset at the same point.
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+}
+
+/// Reads eastings and northings back from a delta encoded stream.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Decoder {
+    precision: Precision,
+    previous: Option<(i64, i64)>,
+}
+
+impl Decoder {
+    /// Creates a decoder for a stream quantized to `precision`.
+    pub fn new(precision: Precision) -> Decoder {
+        Decoder {
+            precision,
+            previous: None,
+        }
+    }
+
+    /// Reads the next fix from the start of `input`.
+    ///
+    /// Returns the easting and northing, and the number of bytes read. If the fix is incomplete
+    /// the decoder is left as it was, so decoding can resume once more input has arrived.
+    pub fn decode(&mut self, input: &[u8]) -> Result<((f64, f64), usize), Error> {
+        let (easting, easting_len) = read_varint(input)?;
+        let (northing, northing_len) = read_varint(&input[easting_len..])?;
+        let (easting, northing) = (unzigzag(easting), unzigzag(northing));
+        let current = match self.previous {
+            Some(previous) => (
+                previous.0.wrapping_add(easting),
+                previous.1.wrapping_add(northing),
+            ),
+            None => (easting, northing),
+        };
+        self.previous = Some(current);
+        Ok((
+            (
+                from_steps(self.precision, current.0),
+                from_steps(self.precision, current.1),
+            ),
+            easting_len + northing_len,
+        ))
+    }
+
+    /// Expects the next fix to be a full one, matching `Encoder::reset`.
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+}
+
+fn to_steps(precision: Precision, value: f64) -> Result<i64, Error> {
+    let steps = (precision.quantize(value) / precision.step()).round();
+    // i64::MAX isn't representable, so compare against 2^63.
+    if steps.is_finite() && steps.abs() < 9223372036854775808. {
+        Ok(steps as i64)
+    } else {
+        Err(Error::Overflow)
+    }
+}
+
+fn from_steps(precision: Precision, steps: i64) -> f64 {
+    let decimals = i32::from(precision.decimals());
+    if decimals >= 0 {
+        steps as f64 / 10f64.powi(decimals)
+    } else {
+        steps as f64 * 10f64.powi(-decimals)
+    }
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+/// Writes a LEB128 varint and returns its length. `output` must have room for ten bytes.
+fn write_varint(mut value: u64, output: &mut [u8]) -> usize {
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            output[len] = byte;
+            return len + 1;
+        }
+        output[len] = byte | 0x80;
+        len += 1;
+    }
+}
+
+fn read_varint(input: &[u8]) -> Result<(u64, usize), Error> {
+    let mut value = 0u64;
+    for (index, &byte) in input.iter().enumerate() {
+        let bits = u64::from(byte & 0x7f);
+        if index == 9 && bits > 1 || index > 9 {
+            return Err(Error::Overflow);
+        }
+        value |= bits << (7 * index);
+        if byte & 0x80 == 0 {
+            return Ok((value, index + 1));
+        }
+    }
+    Err(Error::Truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use precision::Rounding;
+
+    #[test]
+    fn varints() {
+        for &value in &[0, 1, -1, 63, -64, 64, i64::MAX, i64::MIN] {
+            let mut buffer = [0u8; 10];
+            let len = write_varint(zigzag(value), &mut buffer);
+            assert_eq!(read_varint(&buffer[..len]), Ok((zigzag(value), len)));
+            assert_eq!(unzigzag(zigzag(value)), value);
+        }
+        assert_eq!(read_varint(&[0xff; 11]), Err(Error::Overflow));
+    }
+
+    #[test]
+    fn round_trip_with_reset() {
+        let precision = Precision::new(-1, Rounding::Floor);
+        let mut encoder = Encoder::new(precision);
+        let mut decoder = Decoder::new(precision);
+        let mut buffer = [0u8; MAX_FIX_LEN];
+        for (i, &(easting, northing)) in [(500019., 4000001.), (499871., 4000123.), (500000., 0.)]
+            .iter()
+            .enumerate()
+        {
+            if i == 2 {
+                encoder.reset();
+                decoder.reset();
+            }
+            let len = encoder.encode(easting, northing, &mut buffer).unwrap();
+            let ((e, n), read) = decoder.decode(&buffer[..len]).unwrap();
+            assert_eq!(read, len);
+            assert_eq!(e, (easting / 10.).floor() * 10.);
+            assert_eq!(n, (northing / 10.).floor() * 10.);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let mut encoder = Encoder::new(Precision::MILLIMETER);
+        let mut small = [0u8; 4];
+        assert_eq!(
+            encoder.encode(261878., 6243186., &mut small),
+            Err(Error::BufferTooSmall)
+        );
+        assert_eq!(encoder, Encoder::new(Precision::MILLIMETER));
+        assert_eq!(
+            encoder.encode(f64::NAN, 6243186., &mut [0; MAX_FIX_LEN]),
+            Err(Error::Overflow)
+        );
+
+        let mut buffer = [0u8; MAX_FIX_LEN];
+        let len = encoder.encode(261878., 6243186., &mut buffer).unwrap();
+        let mut decoder = Decoder::new(Precision::MILLIMETER);
+        assert_eq!(decoder.decode(&buffer[..len - 1]), Err(Error::Truncated));
+        assert_eq!(decoder, Decoder::new(Precision::MILLIMETER));
+    }
+}
@@ -0,0 +1,165 @@
+//! The range of valid eastings in a zone, which narrows away from the equator.
+//!
+//! `wsg84_utm_to_lat_lon` accepts any easting from 100,000 m up to 1,000,000 m, but a zone
+//! six degrees wide only spans about 166,000 m to 834,000 m at the equator, and less the
+//! further north or south it goes: at 60° it is about 333,000 m to 667,000 m. An easting outside
+//! of that range is outside of its zone, and if it is far outside, most likely corrupted. The
+//! inverse converts it anyway, to a longitude in the wrong zone. `to_lat_lon_strict` checks
+//! the easting against the range at its northing first. Both work on standard UTM, with the
+//! classic series, even if a global converter was set with `converter::set_global`.
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use super::converter::Converter;
+#[cfg(feature = "inverse")]
+use super::series_wsg84_utm_to_lat_lon;
+use super::{is_standard_zone, WSG84ToLatLonError, ZONE_EXCEPTIONS, ZONE_LETTERS};
+
+/// Returns the range of eastings inside a zone at a northing, as a half-open range from the
+/// easting of the zone's western boundary up to that of its eastern boundary.
+///
+/// The zone letter selects the hemisphere and any Norway and Svalbard exception. Zones that
+/// don't exist in a band, such as 32X, have an empty range. Northings beyond the pole get the
+/// range at the pole, which is empty.
+///
+/// # Examples
+///
+/// ```
+/// use utm::easting::easting_range;
+/// let (min, max) = easting_range(0., 31, 'N').unwrap();
+/// assert!((min - 166021.4).abs() < 1. && (max - 833978.6).abs() < 1.);
+/// let (min, max) = easting_range(6651000., 32, 'V').unwrap();
+/// // Zone 32V reaches west to 3°E
+/// assert!(min < 170000. && max < 670000.);
+/// ```
+pub fn easting_range(
+    northing: f64,
+    zone_num: u8,
+    zone_letter: char,
+) -> Result<(f64, f64), WSG84ToLatLonError> {
+    if !(0. ..=10000000.).contains(&northing) {
+        return Err(WSG84ToLatLonError::NorthingOutOfRange);
+    }
+    if !(1..=60).contains(&zone_num) {
+        return Err(WSG84ToLatLonError::ZoneNumOutOfRange);
+    }
+    if !ZONE_LETTERS.contains(zone_letter) {
+        return Err(WSG84ToLatLonError::ZoneLetterOutOfRange);
+    }
+    let central_meridian = f64::from(zone_num) * 6. - 183.;
+    let (west, east) = match ZONE_EXCEPTIONS
+        .iter()
+        .find(|exception| exception.zone_num == zone_num && exception.zone_letter == zone_letter)
+    {
+        Some(exception) => (exception.min_longitude, exception.max_longitude),
+        None if is_standard_zone(zone_num, zone_letter) => {
+            (central_meridian - 3., central_meridian + 3.)
+        }
+        None => (central_meridian, central_meridian),
+    };
+    let northern = zone_letter >= 'N';
+    Ok((
+        boundary_easting(northing, zone_num, northern, west),
+        boundary_easting(northing, zone_num, northern, east),
+    ))
+}
+
+/// Returns the easting where a meridian crosses a northing in a zone.
+fn boundary_easting(northing: f64, zone_num: u8, northern: bool, longitude: f64) -> f64 {
+    let projection = Converter::new().projection(zone_num, northern);
+    // Northings increase with latitude along a meridian, so bisect on the latitude.
+    let (mut south, mut north) = if northern { (0., 90.) } else { (-90., 0.) };
+    for _ in 0..64 {
+        let latitude = (south + north) / 2.;
+        let (candidate, _, _) = projection.forward(latitude, longitude);
+        if candidate < northing {
+            south = latitude;
+        } else {
+            north = latitude;
+        }
+    }
+    let (_, easting, _) = projection.forward((south + north) / 2., longitude);
+    easting
+}
+
+#[cfg(feature = "inverse")]
+/// Converts a UTM coordinate to a latitude and longitude, like `wsg84_utm_to_lat_lon`, but
+/// rejects eastings that are more than `margin` meters outside of `easting_range`.
+///
+/// A margin of zero accepts only coordinates inside their zone. Neighboring zones customarily
+/// overlap by a few kilometers, so a margin of that size keeps data from the overlap.
+///
+/// # Examples
+///
+/// ```
+/// use utm::easting::to_lat_lon_strict;
+/// use utm::{wsg84_utm_to_lat_lon, WSG84ToLatLonError};
+/// assert!(to_lat_lon_strict(261878., 6243186., 34, 'H', 0.).is_ok());
+/// // At 70°N, an easting of 150 km is two zones away
+/// assert!(wsg84_utm_to_lat_lon(150000., 7770000., 33, 'W').is_ok());
+/// assert_eq!(
+///     to_lat_lon_strict(150000., 7770000., 33, 'W', 5000.),
+///     Err(WSG84ToLatLonError::EastingOutOfRange)
+/// );
+/// ```
+pub fn to_lat_lon_strict(
+    easting: f64,
+    northing: f64,
+    zone_num: u8,
+    zone_letter: char,
+    margin: f64,
+) -> Result<(f64, f64), WSG84ToLatLonError> {
+    let lat_lon = series_wsg84_utm_to_lat_lon(easting, northing, zone_num, zone_letter)?;
+    let (min, max) = easting_range(northing, zone_num, zone_letter)?;
+    if easting < min - margin || easting >= max + margin {
+        return Err(WSG84ToLatLonError::EastingOutOfRange);
+    }
+    Ok(lat_lon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symmetric_and_narrowing() {
+        let mut previous = 0.;
+        for &northing in &[0., 2000000., 5000000., 8000000., 9300000.] {
+            let (min, max) = easting_range(northing, 33, 'N').unwrap();
+            assert!((min + max - 1000000.).abs() < 1e-6);
+            assert!(min > previous);
+            previous = min;
+            let (south_min, _) = easting_range(10000000. - northing, 33, 'M').unwrap();
+            assert!((south_min - min).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn exceptions() {
+        let (min, max) = easting_range(8600000., 32, 'X').unwrap();
+        assert_eq!(min, max);
+        let (min, max) = easting_range(8600000., 33, 'X').unwrap();
+        assert!(min < 400000. && max > 600000.);
+        assert_eq!(
+            easting_range(1., 0, 'N'),
+            Err(WSG84ToLatLonError::ZoneNumOutOfRange)
+        );
+        for &letter in &['I', 'O'] {
+            assert_eq!(
+                easting_range(1., 33, letter),
+                Err(WSG84ToLatLonError::ZoneLetterOutOfRange)
+            );
+        }
+    }
+
+    #[cfg(feature = "inverse")]
+    #[test]
+    fn margin() {
+        let (min, _) = easting_range(5000000., 32, 'T').unwrap();
+        assert!(to_lat_lon_strict(min, 5000000., 32, 'T', 0.).is_ok());
+        assert!(to_lat_lon_strict(min - 1., 5000000., 32, 'T', 0.).is_err());
+        assert!(to_lat_lon_strict(min - 1., 5000000., 32, 'T', 2.).is_ok());
+    }
+}
@@ -0,0 +1,222 @@
+//! UTM coordinate reference systems by EPSG code.
+//!
+//! GeoPackages, shapefiles, and most GIS metadata store the coordinate reference system of UTM
+//! data as a single EPSG code, e.g. 32633 for WGS84 zone 33N or 25832 for ETRS89 zone 32N. The
+//! zone, hemisphere, and datum are all packed into that number, and unpacking it by hand is easy
+//! to get subtly wrong. `Crs::from_code` does it once, and `utm_to_lat_lon_epsg` converts
+//! straight from an easting, northing, and code.
+//!
+//! Supported codes are the UTM systems of:
+//!
+//! - WGS84: 32601 to 32660 in the north and 32701 to 32760 in the south.
+//! - ETRS89: 25828 to 25838.
+//! - NAD83: 26901 to 26923.
+//! - NAD27: 26701 to 26722.
+//! - ED50: 23028 to 23038.
+//!
+//! Latitudes and longitudes are on the code's own datum; no datum shift is applied.
+
+use std::fmt;
+
+use super::converter::Converter;
+use super::datum::{DatumTag, GRS80};
+use super::tm::TransverseMercator;
+use super::Ellipsoid;
+
+/// The datum of a UTM coordinate reference system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Datum {
+    /// World Geodetic System 1984.
+    Wgs84,
+    /// European Terrestrial Reference System 1989, on GRS80.
+    Etrs89,
+    /// North American Datum of 1983.
+    Nad83,
+    /// North American Datum of 1927.
+    Nad27,
+    /// European Datum 1950.
+    Ed50,
+}
+
+impl Datum {
+    /// Returns the reference ellipsoid of this datum.
+    pub fn ellipsoid(self) -> Ellipsoid {
+        match self {
+            Datum::Wgs84 => DatumTag::Wgs84.ellipsoid(),
+            Datum::Etrs89 => GRS80,
+            Datum::Nad83 => DatumTag::Nad83.ellipsoid(),
+            Datum::Nad27 => DatumTag::Nad27.ellipsoid(),
+            Datum::Ed50 => DatumTag::Ed50.ellipsoid(),
+        }
+    }
+
+    /// Returns the matching `datum::DatumTag`, or `None` for ETRS89, which has none.
+    pub fn tag(self) -> Option<DatumTag> {
+        match self {
+            Datum::Wgs84 => Some(DatumTag::Wgs84),
+            Datum::Etrs89 => None,
+            Datum::Nad83 => Some(DatumTag::Nad83),
+            Datum::Nad27 => Some(DatumTag::Nad27),
+            Datum::Ed50 => Some(DatumTag::Ed50),
+        }
+    }
+}
+
+/// Errors that can occur with EPSG codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The code is not one of the supported UTM systems.
+    UnknownCode(u32),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UnknownCode(code) => write!(f, "Unknown UTM EPSG code {}", code),
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for Error {}
+
+/// The first code, datum, hemisphere, and zone range of each supported family.
+const FAMILIES: [(u32, Datum, bool, u8, u8); 6] = [
+    (32600, Datum::Wgs84, true, 1, 60),
+    (32700, Datum::Wgs84, false, 1, 60),
+    (25800, Datum::Etrs89, true, 28, 38),
+    (26900, Datum::Nad83, true, 1, 23),
+    (26700, Datum::Nad27, true, 1, 22),
+    (23000, Datum::Ed50, true, 28, 38),
+];
+
+/// A UTM coordinate reference system: a zone, a hemisphere, and a datum.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Crs {
+    /// The zone number, from 1 to 60.
+    pub zone_num: u8,
+    /// True for the northern hemisphere, false for the southern.
+    pub northern: bool,
+    /// The datum.
+    pub datum: Datum,
+}
+
+impl Crs {
+    /// Returns the coordinate reference system of an EPSG code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::epsg::{Crs, Datum};
+    /// let crs = Crs::from_code(32734).unwrap();
+    /// assert_eq!((crs.zone_num, crs.northern, crs.datum), (34, false, Datum::Wgs84));
+    /// assert_eq!(Crs::from_code(25832).unwrap().datum, Datum::Etrs89);
+    /// assert!(Crs::from_code(32661).is_err());
+    /// ```
+    pub fn from_code(code: u32) -> Result<Crs, Error> {
+        FAMILIES
+            .iter()
+            .find(|&&(base, _, _, first, last)| {
+                code > base && code - base >= u32::from(first) && code - base <= u32::from(last)
+            })
+            .map(|&(base, datum, northern, _, _)| Crs {
+                zone_num: (code - base) as u8,
+                northern,
+                datum,
+            })
+            .ok_or(Error::UnknownCode(code))
+    }
+
+    /// Returns the EPSG code of this coordinate reference system, or `None` if EPSG doesn't
+    /// define one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::epsg::{Crs, Datum};
+    /// let crs = Crs { zone_num: 33, northern: true, datum: Datum::Wgs84 };
+    /// assert_eq!(crs.code(), Some(32633));
+    /// let crs = Crs { zone_num: 33, northern: false, datum: Datum::Etrs89 };
+    /// assert_eq!(crs.code(), None);
+    /// ```
+    pub fn code(&self) -> Option<u32> {
+        FAMILIES
+            .iter()
+            .find(|&&(_, datum, northern, first, last)| {
+                datum == self.datum
+                    && northern == self.northern
+                    && (first..=last).contains(&self.zone_num)
+            })
+            .map(|&(base, _, _, _, _)| base + u32::from(self.zone_num))
+    }
+
+    /// Returns the transverse Mercator projection of this coordinate reference system.
+    pub fn projection(&self) -> TransverseMercator {
+        Converter::new()
+            .with_ellipsoid(self.datum.ellipsoid())
+            .projection(self.zone_num, self.northern)
+    }
+}
+
+#[cfg(feature = "inverse")]
+/// Converts an easting and northing in meters in the UTM system of an EPSG code to a latitude
+/// and longitude in decimal degrees on the same datum.
+///
+/// # Examples
+///
+/// ```
+/// use utm::epsg::utm_to_lat_lon_epsg;
+/// // Capetown, South Africa
+/// let (latitude, longitude) = utm_to_lat_lon_epsg(261878., 6243186., 32734).unwrap();
+/// assert!((latitude + 33.92487).abs() < 1e-5 && (longitude - 18.42406).abs() < 1e-5);
+/// assert!(utm_to_lat_lon_epsg(261878., 6243186., 4326).is_err());
+/// ```
+pub fn utm_to_lat_lon_epsg(easting: f64, northing: f64, code: u32) -> Result<(f64, f64), Error> {
+    Ok(Crs::from_code(code)?
+        .projection()
+        .inverse(easting, northing))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_round_trip() {
+        for &(base, first, last) in &[
+            (32600, 1, 60),
+            (32700, 1, 60),
+            (25800, 28, 38),
+            (26900, 1, 23),
+            (26700, 1, 22),
+            (23000, 28, 38),
+        ] {
+            assert!(Crs::from_code(base + first - 1).is_err());
+            assert!(Crs::from_code(base + last + 1).is_err());
+            for code in base + first..=base + last {
+                assert_eq!(Crs::from_code(code).unwrap().code(), Some(code));
+            }
+        }
+        assert_eq!(Crs::from_code(0), Err(Error::UnknownCode(0)));
+        assert_eq!(Crs::from_code(u32::MAX), Err(Error::UnknownCode(u32::MAX)));
+    }
+
+    #[cfg(feature = "inverse")]
+    #[test]
+    fn datums() {
+        // The same point on NAD83 and NAD27 projects to different coordinates
+        let (nad83, _, _) = Crs::from_code(26914)
+            .unwrap()
+            .projection()
+            .forward(39.224079, -98.541807);
+        let (nad27, easting, _) = Crs::from_code(26714)
+            .unwrap()
+            .projection()
+            .forward(39.224079, -98.541807);
+        assert!((nad83 - nad27).abs() > 100.);
+        let (latitude, longitude) = utm_to_lat_lon_epsg(easting, nad27, 26714).unwrap();
+        assert!((latitude - 39.224079).abs() < 1e-9 && (longitude + 98.541807).abs() < 1e-9);
+        assert_eq!(Datum::Etrs89.ellipsoid(), Datum::Nad83.ellipsoid());
+        assert_eq!(Datum::Etrs89.tag(), None);
+    }
+}
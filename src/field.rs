@@ -0,0 +1,284 @@
+//! Fixed-width coordinate fields, like those in avionics and legacy survey records.
+//!
+//! Fixed-width formats pad every number with zeros to the same number of digits, so fields line
+//! up by column and can be sliced by offset. A `FieldFormat` describes one such layout, and
+//! `FieldFormat::format` checks a coordinate against it before anything is written, since a
+//! value that doesn't fit would shift every field after it.
+//!
+//! ```
+//! use utm::field::{FieldFormat, ZoneField};
+//! use utm::precision::Precision;
+//! use utm::UtmCoordinate;
+//! let coordinate = UtmCoordinate::new(85273.018, 6761077.204, 6, 'V');
+//! let fields = FieldFormat::STANDARD.format(&coordinate).unwrap();
+//! assert_eq!(fields.to_string(), "06V 085273 6761077");
+//! let format = FieldFormat::new(7, 8)
+//!     .with_zone(ZoneField::Hemisphere)
+//!     .with_precision(Precision::CENTIMETER)
+//!     .with_separator(None);
+//! assert_eq!(format.format(&coordinate).unwrap().to_string(), "06N0085273.0206761077.20");
+//! ```
+
+use std::fmt;
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use super::precision::Precision;
+use super::UtmCoordinate;
+
+/// How the zone is written at the start of a record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum ZoneField {
+    /// The two digit zone number and the band letter, e.g. `06V`.
+    #[default]
+    Band,
+    /// The two digit zone number and `N` or `S` for the hemisphere, e.g. `06N`.
+    Hemisphere,
+    /// No zone field.
+    Omitted,
+}
+
+/// The layout of a fixed-width record of a zone, easting, and northing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FieldFormat {
+    zone: ZoneField,
+    easting_digits: u8,
+    northing_digits: u8,
+    precision: Precision,
+    separator: Option<char>,
+}
+
+/// Errors that can occur when formatting fixed-width fields.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// An easting or northing is negative, which has no zero-padded form.
+    Negative,
+    /// An easting or northing has more whole digits than its field.
+    TooWide,
+    /// An easting or northing is not a finite number.
+    NotFinite,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Negative => write!(f, "Value is negative"),
+            Error::TooWide => write!(f, "Value has more digits than its field"),
+            Error::NotFinite => write!(f, "Value is not finite"),
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for Error {}
+
+impl FieldFormat {
+    /// Six digit eastings and seven digit northings in whole meters, after the zone and band,
+    /// separated by spaces, e.g. `06V 085273 6761077`.
+    pub const STANDARD: FieldFormat = FieldFormat::new(6, 7);
+
+    /// Creates a format with the given number of whole digits for eastings and northings.
+    ///
+    /// It starts with the zone and band, whole meters, and spaces between fields.
+    pub const fn new(easting_digits: u8, northing_digits: u8) -> FieldFormat {
+        FieldFormat {
+            zone: ZoneField::Band,
+            easting_digits,
+            northing_digits,
+            precision: Precision::METER,
+            separator: Some(' '),
+        }
+    }
+
+    /// Returns this format with another zone field.
+    pub const fn with_zone(mut self, zone: ZoneField) -> FieldFormat {
+        self.zone = zone;
+        self
+    }
+
+    /// Returns this format with another precision.
+    ///
+    /// Positive decimals are written after a decimal point, which isn't counted in the digits.
+    pub const fn with_precision(mut self, precision: Precision) -> FieldFormat {
+        self.precision = precision;
+        self
+    }
+
+    /// Returns this format with another separator between fields, or none.
+    pub const fn with_separator(mut self, separator: Option<char>) -> FieldFormat {
+        self.separator = separator;
+        self
+    }
+
+    /// Returns the width of a record in characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::field::FieldFormat;
+    /// assert_eq!(FieldFormat::STANDARD.width(), 18);
+    /// ```
+    pub fn width(&self) -> usize {
+        let zone = if self.zone == ZoneField::Omitted {
+            0
+        } else {
+            3
+        };
+        let decimals = match self.decimals() {
+            0 => 0,
+            decimals => decimals + 1,
+        };
+        let separators = match (self.separator, self.zone) {
+            (None, _) => 0,
+            (Some(_), ZoneField::Omitted) => 1,
+            (Some(_), _) => 2,
+        };
+        zone + usize::from(self.easting_digits)
+            + usize::from(self.northing_digits)
+            + 2 * decimals
+            + separators
+    }
+
+    /// Checks a coordinate against this format and returns its fields, which are written with
+    /// `Display`.
+    pub fn format(&self, coordinate: &UtmCoordinate) -> Result<Fields, Error> {
+        Ok(Fields {
+            format: *self,
+            zone_num: coordinate.zone_num,
+            zone_letter: coordinate.zone_letter,
+            easting: self.quantize(coordinate.easting, self.easting_digits)?,
+            northing: self.quantize(coordinate.northing, self.northing_digits)?,
+        })
+    }
+
+    fn decimals(&self) -> usize {
+        self.precision.decimals().max(0) as usize
+    }
+
+    fn quantize(&self, value: f64, digits: u8) -> Result<f64, Error> {
+        if !value.is_finite() {
+            return Err(Error::NotFinite);
+        }
+        let value = self.precision.quantize(value);
+        if value < 0. {
+            Err(Error::Negative)
+        } else if value >= 10f64.powi(i32::from(digits)) {
+            Err(Error::TooWide)
+        } else {
+            // Negative zero would be written with a sign.
+            Ok(value.abs())
+        }
+    }
+}
+
+impl Default for FieldFormat {
+    fn default() -> FieldFormat {
+        FieldFormat::STANDARD
+    }
+}
+
+/// A coordinate checked against a `FieldFormat`, returned by `FieldFormat::format`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fields {
+    format: FieldFormat,
+    zone_num: u8,
+    zone_letter: char,
+    easting: f64,
+    northing: f64,
+}
+
+impl fmt::Display for Fields {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let format = &self.format;
+        let zone_char = match format.zone {
+            ZoneField::Band => Some(self.zone_letter),
+            ZoneField::Hemisphere if self.zone_letter >= 'N' => Some('N'),
+            ZoneField::Hemisphere => Some('S'),
+            ZoneField::Omitted => None,
+        };
+        if let Some(zone_char) = zone_char {
+            write!(f, "{:02}{}", self.zone_num, zone_char)?;
+            if let Some(separator) = format.separator {
+                write!(f, "{}", separator)?;
+            }
+        }
+        let decimals = format.decimals();
+        let width = |digits: u8| match decimals {
+            0 => usize::from(digits),
+            decimals => usize::from(digits) + decimals + 1,
+        };
+        write!(
+            f,
+            "{:0width$.decimals$}",
+            self.easting,
+            width = width(format.easting_digits),
+            decimals = decimals
+        )?;
+        if let Some(separator) = format.separator {
+            write!(f, "{}", separator)?;
+        }
+        write!(
+            f,
+            "{:0width$.decimals$}",
+            self.northing,
+            width = width(format.northing_digits),
+            decimals = decimals
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use precision::Rounding;
+
+    #[test]
+    fn widths() {
+        let coordinate = UtmCoordinate::new(0., 0., 31, 'N');
+        for format in &[
+            FieldFormat::STANDARD,
+            FieldFormat::new(7, 8).with_zone(ZoneField::Omitted),
+            FieldFormat::new(7, 8)
+                .with_separator(Some(','))
+                .with_precision(Precision::MILLIMETER),
+            FieldFormat::new(6, 7)
+                .with_zone(ZoneField::Omitted)
+                .with_separator(None),
+        ] {
+            let text = format.format(&coordinate).unwrap().to_string();
+            assert_eq!(text.len(), format.width(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let format = FieldFormat::STANDARD;
+        let coordinate = UtmCoordinate::new(999999.5, 0., 31, 'N');
+        assert_eq!(format.format(&coordinate), Err(Error::TooWide));
+        let floor = format.with_precision(Precision::METER.with_rounding(Rounding::Floor));
+        assert!(floor.format(&coordinate).is_ok());
+        let coordinate = UtmCoordinate::new(500000., -0.6, 31, 'N');
+        assert_eq!(format.format(&coordinate), Err(Error::Negative));
+        let coordinate = UtmCoordinate::new(500000., -0.4, 31, 'N');
+        assert_eq!(
+            format.format(&coordinate).unwrap().to_string(),
+            "31N 500000 0000000"
+        );
+        let coordinate = UtmCoordinate::new(f64::NAN, 0., 31, 'N');
+        assert_eq!(format.format(&coordinate), Err(Error::NotFinite));
+    }
+
+    #[test]
+    fn coarse_precision() {
+        let format = FieldFormat::new(6, 7)
+            .with_zone(ZoneField::Hemisphere)
+            .with_precision(Precision::new(-1, Rounding::Nearest));
+        let coordinate = UtmCoordinate::new(261878.5, 6243186., 34, 'H');
+        assert_eq!(
+            format.format(&coordinate).unwrap().to_string(),
+            "34S 261880 6243190"
+        );
+    }
+}
@@ -0,0 +1,385 @@
+//! Rotate vectors between the true north and grid north frames.
+//!
+//! Away from the central meridian grid north and true north differ by the meridian convergence,
+//! up to a few degrees inside a zone. Navigation filters that keep their velocity in UTM have to
+//! rotate every velocity or heading vector by the local convergence when moving between a
+//! geodetic east/north frame and the grid easting/northing frame.
+//!
+//! Vectors are `(east, north)` pairs in any unit. Convergences are in radians, using the same
+//! sign as the third value returned by `to_utm_wgs84` and `tm::TransverseMercator::forward`:
+//! positive when true north lies clockwise of grid north.
+//!
+//! Poses are on standard UTM: positions use the classic series and convergences the standard
+//! projection, even if a global converter was set with `converter::set_global`.
+//!
+//! `apply_convergence` and `remove_convergence` do the same for bearings. They take angles
+//! tagged with their unit, `Degrees` or `Radians`, so a convergence in radians can't be added
+//! to a bearing in degrees by mistake, and wrap the result into a single turn.
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+#[cfg(feature = "forward")]
+use super::converter::Converter;
+#[cfg(all(feature = "forward", feature = "inverse"))]
+use super::series_wsg84_utm_to_lat_lon;
+use super::UtmCoordinate;
+#[cfg(all(feature = "forward", feature = "inverse"))]
+use super::WSG84ToLatLonError;
+#[cfg(feature = "forward")]
+use super::{lat_to_zone_letter, series_to_utm_wgs84};
+
+/// A latitude and longitude in decimal degrees with a heading from true north.
+///
+/// Headings are in radians, clockwise from north, between 0 and 2π.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeodeticPose {
+    /// The latitude in decimal degrees.
+    pub latitude: f64,
+    /// The longitude in decimal degrees.
+    pub longitude: f64,
+    /// The heading in radians clockwise from true north.
+    pub heading: f64,
+}
+
+/// A UTM coordinate with a heading from grid north.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GridPose {
+    /// The position.
+    pub coordinate: UtmCoordinate,
+    /// The heading in radians clockwise from grid north.
+    pub heading: f64,
+}
+
+impl GeodeticPose {
+    #[cfg(feature = "forward")]
+    /// Projects the position into a zone and turns the heading to grid north.
+    ///
+    /// Returns `None` if the latitude is outside of the UTM bands.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::frame::GeodeticPose;
+    /// // Heading due north, west of zone 10's central meridian
+    /// let pose = GeodeticPose { latitude: 40.62, longitude: -123.45, heading: 0. };
+    /// let grid = pose.to_grid(10).unwrap();
+    /// assert_eq!(grid.coordinate.zone_letter, 'T');
+    /// // Grid north is west of true north here, so the grid heading is east of north.
+    /// assert!(grid.heading > 0. && grid.heading < 0.01);
+    /// ```
+    pub fn to_grid(&self, zone: u8) -> Option<GridPose> {
+        let zone_letter = lat_to_zone_letter(self.latitude)?;
+        let (northing, easting, _) = series_to_utm_wgs84(self.latitude, self.longitude, zone);
+        Some(GridPose {
+            coordinate: UtmCoordinate::new(easting, northing, zone, zone_letter),
+            heading: normalize(self.heading + convergence(self.latitude, self.longitude, zone)),
+        })
+    }
+}
+
+impl GridPose {
+    #[cfg(all(feature = "forward", feature = "inverse"))]
+    /// Unprojects the position and turns the heading to true north.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::frame::GeodeticPose;
+    /// let pose = GeodeticPose { latitude: -33.92487, longitude: 18.42406, heading: 1.2 };
+    /// let back = pose.to_grid(34).unwrap().to_geodetic().unwrap();
+    /// assert!((back.heading - 1.2).abs() < 1e-6);
+    /// ```
+    pub fn to_geodetic(&self) -> Result<GeodeticPose, WSG84ToLatLonError> {
+        let coordinate = &self.coordinate;
+        let (latitude, longitude) = series_wsg84_utm_to_lat_lon(
+            coordinate.easting,
+            coordinate.northing,
+            coordinate.zone_num,
+            coordinate.zone_letter,
+        )?;
+        let convergence = convergence(latitude, longitude, coordinate.zone_num);
+        Ok(GeodeticPose {
+            latitude,
+            longitude,
+            heading: normalize(self.heading - convergence),
+        })
+    }
+}
+
+/// Returns the meridian convergence in radians at a latitude and longitude in decimal degrees,
+/// in the given zone.
+///
+/// # Examples
+///
+/// ```
+/// use utm::frame::convergence;
+/// // West of the central meridian in the northern hemisphere, true north is east of grid north
+/// assert!(convergence(40.62, -123.45, 10) > 0.);
+/// assert_eq!(convergence(40.62, -123., 10), 0.);
+/// // And west of it in the southern hemisphere, true north is west of grid north
+/// assert!((convergence(-33.92487, 18.42406, 34) + 0.0251).abs() < 1e-4);
+/// ```
+#[cfg(feature = "forward")]
+pub fn convergence(latitude: f64, longitude: f64, zone: u8) -> f64 {
+    Converter::new()
+        .projection(zone, latitude >= 0.)
+        .forward(latitude, longitude)
+        .2
+}
+
+/// Rotates an `(east, north)` vector in the true north frame into the grid frame.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "forward")] {
+/// use utm::frame::{convergence, true_to_grid};
+/// // Heading due north at 10 m/s
+/// let (east, north) = true_to_grid((0., 10.), convergence(40.62, -123.45, 10));
+/// assert!(east > 0.);
+/// assert!((east.hypot(north) - 10.).abs() < 1e-12);
+/// # }
+/// ```
+pub fn true_to_grid(vector: (f64, f64), convergence: f64) -> (f64, f64) {
+    rotate(vector, convergence)
+}
+
+/// Rotates an `(easting, northing)` vector in the grid frame into the true north frame.
+///
+/// # Examples
+///
+/// ```
+/// use utm::frame::{grid_to_true, true_to_grid};
+/// let (east, north) = grid_to_true(true_to_grid((3., 4.), 0.02), 0.02);
+/// assert!((east - 3.).abs() < 1e-12);
+/// assert!((north - 4.).abs() < 1e-12);
+/// ```
+pub fn grid_to_true(vector: (f64, f64), convergence: f64) -> (f64, f64) {
+    rotate(vector, -convergence)
+}
+
+/// An angle tagged with its unit.
+pub trait Angle: Copy {
+    /// The size of a full turn in this unit.
+    const TURN: f64;
+
+    /// Creates an angle from a value in this unit.
+    fn from_value(value: f64) -> Self;
+
+    /// Returns the value in this unit.
+    fn value(self) -> f64;
+}
+
+/// An angle in degrees.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Degrees(pub f64);
+
+/// An angle in radians.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Radians(pub f64);
+
+impl Angle for Degrees {
+    const TURN: f64 = 360.;
+
+    fn from_value(value: f64) -> Degrees {
+        Degrees(value)
+    }
+
+    fn value(self) -> f64 {
+        self.0
+    }
+}
+
+impl Angle for Radians {
+    const TURN: f64 = 2. * std::f64::consts::PI;
+
+    fn from_value(value: f64) -> Radians {
+        Radians(value)
+    }
+
+    fn value(self) -> f64 {
+        self.0
+    }
+}
+
+impl From<Radians> for Degrees {
+    fn from(radians: Radians) -> Degrees {
+        Degrees(radians.0.to_degrees())
+    }
+}
+
+impl From<Degrees> for Radians {
+    fn from(degrees: Degrees) -> Radians {
+        Radians(degrees.0.to_radians())
+    }
+}
+
+/// Turns a bearing from true north into a bearing from grid north, in the bearing's unit and
+/// between zero and a full turn.
+///
+/// # Examples
+///
+/// ```
+/// use utm::frame::{apply_convergence, Degrees, Radians};
+/// // The convergence as returned by `to_utm_wgs84`, in radians
+/// let grid = apply_convergence(Degrees(359.5), Radians(1f64.to_radians()));
+/// assert!((grid.0 - 0.5).abs() < 1e-12);
+/// ```
+pub fn apply_convergence<B: Angle, C: Angle>(true_bearing: B, convergence: C) -> B {
+    B::from_value(wrap_turn::<B>(
+        true_bearing.value() + convergence.value() * B::TURN / C::TURN,
+    ))
+}
+
+/// Turns a bearing from grid north into a bearing from true north, in the bearing's unit and
+/// between zero and a full turn.
+///
+/// # Examples
+///
+/// ```
+/// use utm::frame::{remove_convergence, Degrees};
+/// let true_bearing = remove_convergence(Degrees(0.5), Degrees(1.));
+/// assert!((true_bearing.0 - 359.5).abs() < 1e-12);
+/// ```
+pub fn remove_convergence<B: Angle, C: Angle>(grid_bearing: B, convergence: C) -> B {
+    B::from_value(wrap_turn::<B>(
+        grid_bearing.value() - convergence.value() * B::TURN / C::TURN,
+    ))
+}
+
+/// Wraps a value into [0, a full turn).
+fn wrap_turn<A: Angle>(value: f64) -> f64 {
+    let value = value % A::TURN;
+    let value = if value < 0. { value + A::TURN } else { value };
+    // Adding a full turn to a tiny negative value rounds to a full turn.
+    if value >= A::TURN {
+        0.
+    } else {
+        value
+    }
+}
+
+/// Returns a heading between 0 and 2π.
+#[cfg(any(feature = "forward", feature = "inverse"))]
+pub(crate) fn normalize(heading: f64) -> f64 {
+    wrap_turn::<Radians>(heading)
+}
+
+/// Rotates a vector clockwise by `angle` radians.
+fn rotate((east, north): (f64, f64), angle: f64) -> (f64, f64) {
+    let (sin, cos) = angle.sin_cos();
+    (east * cos + north * sin, north * cos - east * sin)
+}
+
+#[cfg(all(test, feature = "forward", feature = "inverse"))]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+    use {to_utm_wgs84, wsg84_utm_to_lat_lon};
+
+    /// Points in both hemispheres, on both sides of their zone's central meridian.
+    const POINTS: [(f64, f64, u8, char); 4] = [
+        (47.5, -119.2, 11, 'T'),
+        (-33.92487, 18.42406, 34, 'H'),
+        (-0.5, -119.2, 11, 'M'),
+        (-60.3, 100.7, 47, 'E'),
+    ];
+
+    #[test]
+    fn grid_north_matches_the_grid_line() {
+        for &(latitude, longitude, zone, letter) in &POINTS {
+            let (northing, easting, _) = to_utm_wgs84(latitude, longitude, zone);
+            let convergence = convergence(latitude, longitude, zone);
+            // Step 1 m along grid north and measure the true direction of the step.
+            let (lat2, lon2) = wsg84_utm_to_lat_lon(easting, northing + 1., zone, letter).unwrap();
+            let (lat1, lon1) = wsg84_utm_to_lat_lon(easting, northing, zone, letter).unwrap();
+            // The ratio of the meridional to the prime vertical radius of curvature on WGS84.
+            let e2 = 0.00669438;
+            let m_over_n = (1. - e2) / (1. - e2 * lat1.to_radians().sin().powi(2));
+            let east = (lon2 - lon1).to_radians() * lat1.to_radians().cos();
+            let north = (lat2 - lat1).to_radians() * m_over_n;
+            let norm = east.hypot(north);
+            let (expected_east, expected_north) = grid_to_true((0., 1.), convergence);
+            assert!((east / norm - expected_east).abs() < 1e-5, "{}", latitude);
+            assert!((north / norm - expected_north).abs() < 1e-5, "{}", latitude);
+        }
+    }
+
+    #[test]
+    fn pose_heading_matches_rotated_vector() {
+        for &(latitude, longitude, zone, _) in &POINTS {
+            let pose = GeodeticPose {
+                latitude,
+                longitude,
+                heading: 6.27,
+            };
+            let grid = pose.to_grid(zone).unwrap();
+            let (east, north) = true_to_grid(
+                (pose.heading.sin(), pose.heading.cos()),
+                convergence(latitude, longitude, zone),
+            );
+            let expected = normalize(east.atan2(north));
+            assert!((expected - grid.heading).abs() < 1e-12, "{}", latitude);
+            let back = grid.to_geodetic().unwrap();
+            assert!((back.heading - 6.27).abs() < 1e-6);
+            assert!((back.latitude - latitude).abs() < 1e-6);
+        }
+        // Wrapped past north
+        let pose = GeodeticPose {
+            latitude: 47.5,
+            longitude: -119.2,
+            heading: 6.27,
+        };
+        assert!(pose.to_grid(11).unwrap().heading < 0.1);
+    }
+
+    #[test]
+    fn pose_heading_follows_a_northward_step() {
+        // A vehicle heading due north at Cape Town, west of zone 34's central meridian
+        let pose = GeodeticPose {
+            latitude: -33.92487,
+            longitude: 18.42406,
+            heading: 0.,
+        };
+        let grid = pose.to_grid(34).unwrap();
+        let (northing, easting, _) = to_utm_wgs84(pose.latitude + 1e-5, pose.longitude, 34);
+        let step = normalize(
+            (easting - grid.coordinate.easting).atan2(northing - grid.coordinate.northing),
+        );
+        // True north is west of grid north here, so the grid heading is west of north
+        assert!(step > PI && grid.heading > PI);
+        assert!(
+            (step - grid.heading).abs() < 1e-6,
+            "{} {}",
+            step,
+            grid.heading
+        );
+        let back = grid.to_geodetic().unwrap();
+        assert!(back.heading < 1e-6 || 2. * PI - back.heading < 1e-6);
+        assert!((back.longitude - pose.longitude).abs() < 1e-5);
+    }
+
+    #[test]
+    fn bearings_wrap() {
+        let grid = apply_convergence(Radians(6.2), Radians(0.1));
+        assert!((grid.0 - (6.3 - 2. * PI)).abs() < 1e-12);
+        assert!((remove_convergence(grid, Radians(0.1)).0 - 6.2).abs() < 1e-12);
+        let grid = apply_convergence(Degrees(10.), Degrees::from(Radians(-0.1)));
+        let expected = apply_convergence(Degrees(10.), Radians(-0.1));
+        assert!((grid.0 - expected.0).abs() < 1e-12);
+        assert_eq!(apply_convergence(Degrees(0.), Degrees(-1e-17)).0, 0.);
+        assert_eq!(apply_convergence(Degrees(720.), Degrees(0.)).0, 0.);
+        let (_, _, convergence) = to_utm_wgs84(47.5, -119.2, 11);
+        let heading = normalize(6.27 + convergence);
+        assert!((apply_convergence(Radians(6.27), Radians(convergence)).0 - heading).abs() < 1e-12);
+    }
+
+    #[test]
+    fn headings_wrap() {
+        assert_eq!(normalize(-1e-17), 0.);
+        assert_eq!(normalize(2. * PI), 0.);
+        assert!((normalize(-PI / 2.) - 1.5 * PI).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,439 @@
+//! UTM conversions generic over the scalar type, behind the `generic` feature.
+//!
+//! These are the same series as `to_utm_wgs84` and `wsg84_utm_to_lat_lon`, written for any
+//! `Scalar` instead of `f64`. Every `num::Float` is one, such as `f32`. Automatic differentiation
+//! types, such as the dual numbers of the `num-dual` crate, can be wrapped in a newtype that
+//! implements `Scalar`, so optimization and bundle adjustment code can get exact derivatives of
+//! the projection instead of using finite differences.
+//!
+//! Zone numbers, letters, and the ellipsoid stay plain values: only the coordinates are generic.
+
+#[cfg(any(feature = "forward", feature = "inverse"))]
+use std::f64::consts::PI;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use num::traits::Float;
+
+#[cfg(feature = "forward")]
+use super::Ellipsoid;
+#[cfg(any(feature = "forward", feature = "inverse"))]
+use super::WGS84;
+#[cfg(feature = "inverse")]
+use super::{WSG84ToLatLonError, E, K0, ZONE_LETTERS};
+
+/// The operations the series need from a scalar.
+///
+/// This is implemented for every `Float`, and by the crate's own scalar types such as
+/// `interval::Interval`. Types from other crates that aren't a `Float`, such as `num-dual`'s
+/// `Dual64`, can't implement it themselves, but a newtype around them can.
+pub trait Scalar:
+    Copy
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    /// Converts a constant to this scalar.
+    fn from_f64(value: f64) -> Self;
+    /// Returns the sine of an angle in radians.
+    fn sin(self) -> Self;
+    /// Returns the cosine of an angle in radians.
+    fn cos(self) -> Self;
+    /// Returns the tangent of an angle in radians.
+    fn tan(self) -> Self;
+    /// Returns the square root.
+    fn sqrt(self) -> Self;
+    /// Raises this scalar to an integer power.
+    fn powi(self, n: i32) -> Self;
+}
+
+impl<T: Float> Scalar for T {
+    fn from_f64(value: f64) -> T {
+        T::from(value).unwrap()
+    }
+
+    fn sin(self) -> T {
+        Float::sin(self)
+    }
+
+    fn cos(self) -> T {
+        Float::cos(self)
+    }
+
+    fn tan(self) -> T {
+        Float::tan(self)
+    }
+
+    fn sqrt(self) -> T {
+        Float::sqrt(self)
+    }
+
+    fn powi(self, n: i32) -> T {
+        Float::powi(self, n)
+    }
+}
+
+/// Converts a plain constant to the scalar type.
+fn c<T: Scalar>(value: f64) -> T {
+    T::from_f64(value)
+}
+
+#[cfg(feature = "forward")]
+/// Converts a latitude and longitude in decimal degrees to UTM coordinates in the given zone,
+/// like `to_utm_wgs84`.
+///
+/// Returns the northing, easting, and meridian convergence.
+///
+/// # Examples
+///
+/// ```
+/// use utm::generic::to_utm;
+/// let (northing, easting, _) = to_utm(40.62f32, -123.45f32, 10);
+/// let (expected_northing, expected_easting, _) = utm::to_utm_wgs84(40.62, -123.45, 10);
+/// assert!((f64::from(easting) - expected_easting).abs() < 1.);
+/// assert!((f64::from(northing) - expected_northing).abs() < 1.);
+/// ```
+pub fn to_utm<T: Scalar + PartialOrd>(latitude: T, longitude: T, zone: u8) -> (T, T, T) {
+    let latitude = latitude * c(PI / 180.);
+    // The equator is in band N, so it is northern
+    let northern = latitude >= c(0.);
+    forward(latitude, longitude * c(PI / 180.), zone, northern)
+}
+
+/// Converts a latitude and longitude in radians to UTM coordinates, with the false northing of
+/// the given hemisphere.
+#[cfg(feature = "forward")]
+pub(crate) fn forward<T: Scalar>(latitude: T, longitude: T, zone: u8, northern: bool) -> (T, T, T) {
+    let ellipsoid = WGS84;
+    let long_origin = (f64::from(zone) * 6.0 - 183.0).to_radians();
+    let e2 = 2.0 * ellipsoid.f - ellipsoid.f * ellipsoid.f;
+    let ep2 = e2 / (1.0 - e2);
+
+    let one = c::<T>(1.0);
+    let n = c::<T>(ellipsoid.a) / (one - c::<T>(e2) * latitude.sin().powi(2)).sqrt();
+    let t = latitude.tan().powi(2);
+    let cc = c::<T>(ep2) * latitude.cos().powi(2);
+    let a = latitude.cos() * (longitude - c(long_origin));
+
+    let m = meridian_arc(ellipsoid, latitude);
+
+    let x1 = (one - t + cc) * a.powi(3) / c(6.0);
+    let x2 = (c::<T>(5.0) - c::<T>(18.0) * t + t.powi(2) + c::<T>(72.0) * cc - c(58.0 * ep2))
+        * a.powi(5)
+        / c(120.0);
+    let x = c::<T>(0.9996) * n * (a + x1 + x2);
+
+    let y1 = (c::<T>(5.0) - t + c::<T>(9.0) * cc + c::<T>(4.0) * cc.powi(2)) * a.powi(4) / c(24.0);
+    let y2 = (c::<T>(61.0) - c::<T>(58.0) * t + t.powi(2) + c::<T>(600.0) * cc - c(330.0 * ep2))
+        * a.powi(6)
+        / c(720.0);
+    let y3 = a.powi(2) / c(2.0) + y1 + y2;
+    let y = c::<T>(0.9996) * (m + n * latitude.tan() * y3);
+
+    // Southern hemisphere coords add a factor
+    let northing_mod = if northern { 0.0 } else { 10000000.0 };
+
+    let northing = y + c(northing_mod);
+    let easting = x + c(500000.0);
+
+    // The footpoint is found from the distance to the equator, before the false northing.
+    let convergence = meridian_convergence(ellipsoid, y, easting);
+    (northing, easting, convergence)
+}
+
+#[cfg(feature = "forward")]
+fn meridian_arc<T: Scalar>(ellipsoid: Ellipsoid, latitude: T) -> T {
+    let e2 = 2.0 * ellipsoid.f - ellipsoid.f * ellipsoid.f;
+    let term1 = 1.0 - e2 / 4.0 - (3.0 * e2 * e2) / 64.0 - (5.0 * e2 * e2 * e2) / 256.0;
+    let term2 = (3.0 * e2) / 8.0 + (3.0 * e2 * e2) / 32.0 + (45.0 * e2 * e2 * e2) / 1024.0;
+    let term3 = (15.0 * e2 * e2) / 256.0 + (45.0 * e2 * e2 * e2) / 1024.0;
+    let term4 = (35.0 * e2 * e2 * e2) / 3072.0;
+
+    c::<T>(ellipsoid.a)
+        * (c::<T>(term1) * latitude - c::<T>(term2) * (c::<T>(2.0) * latitude).sin()
+            + c::<T>(term3) * (c::<T>(4.0) * latitude).sin()
+            - c::<T>(term4) * (c::<T>(6.0) * latitude).sin())
+}
+
+#[cfg(feature = "forward")]
+fn meridian_convergence<T: Scalar>(ellipsoid: Ellipsoid, northing: T, easting: T) -> T {
+    let e2 = 2.0 * ellipsoid.f - ellipsoid.f * ellipsoid.f;
+    let e1 = (1.0 - Float::sqrt(1.0 - e2)) / (1.0 + Float::sqrt(1.0 - e2));
+    let mu_const =
+        ellipsoid.a * (1.0 - e2 / 4.0 - 3.0 * e2 * e2 / 64.0 - 5.0 * e2 * e2 * e2 / 256.0);
+
+    let mu = northing / c(0.9996 * mu_const);
+    let term1 = 3.0 * e1 / 2.0 - 27.0 * e1 * e1 * e1 / 32.0;
+    let term2 = 21.0 * e1 * e1 / 16.0 - 55.0 * e1 * e1 * e1 * e1 / 32.0;
+    let term3 = 151.0 * e1 * e1 * e1 / 96.0;
+    let term4 = 1097.0 * e1 * e1 * e1 * e1 / 512.0;
+    let foot_lat = mu
+        + c::<T>(term1) * (c::<T>(2.0) * mu).sin()
+        + c::<T>(term2) * (c::<T>(4.0) * mu).sin()
+        + c::<T>(term3) * (c::<T>(6.0) * mu).sin()
+        + c::<T>(term4) * (c::<T>(8.0) * mu).sin();
+
+    let one = c::<T>(1.0);
+    let sin2 = foot_lat.sin().powi(2);
+    let ep = (easting - c(500000.0)) / c(0.9996);
+    let n = c::<T>(ellipsoid.a) / (one - c::<T>(e2) * sin2).sqrt();
+    let w = one - c::<T>(e2) * sin2;
+    let m = c::<T>(ellipsoid.a * (1.0 - e2)) / (w * w.sqrt());
+
+    let conv1 = -(ep / n) * foot_lat.tan();
+    let h30 = (ep / n).powi(3);
+    let k28 = n / m;
+    let k29 = k28.powi(2);
+    let j29 = foot_lat.tan().powi(2);
+    let conv2 = (foot_lat.tan() * h30 / c(3.0)) * (c::<T>(-2.0) * k29 + c::<T>(3.0) * k28 + j29);
+    conv1 + conv2
+}
+
+#[cfg(feature = "inverse")]
+/// Converts a UTM coordinate to a latitude and longitude in decimal degrees, like
+/// `wsg84_utm_to_lat_lon`.
+///
+/// # Examples
+///
+/// ```
+/// use utm::generic::to_lat_lon;
+/// let (latitude, longitude) = to_lat_lon(261878f32, 6243186f32, 34, 'H').unwrap();
+/// assert!((latitude - -33.92487).abs() < 1e-3);
+/// assert!((longitude - 18.42406).abs() < 1e-3);
+/// ```
+pub fn to_lat_lon<T: Scalar + PartialOrd>(
+    easting: T,
+    northing: T,
+    zone_num: u8,
+    zone_letter: char,
+) -> Result<(T, T), WSG84ToLatLonError> {
+    if !(c(100000.)..c(1000000.)).contains(&easting) {
+        return Err(WSG84ToLatLonError::EastingOutOfRange);
+    }
+    if !(c(0.)..=c(10000000.)).contains(&northing) {
+        return Err(WSG84ToLatLonError::NorthingOutOfRange);
+    }
+    if !(1..=60).contains(&zone_num) {
+        return Err(WSG84ToLatLonError::ZoneNumOutOfRange);
+    }
+    if !ZONE_LETTERS.contains(zone_letter) {
+        return Err(WSG84ToLatLonError::ZoneLetterOutOfRange);
+    }
+    let (latitude, longitude) = inverse(easting, northing, zone_letter >= 'N');
+    let central_meridian = (f64::from(zone_num) - 1.) * 6. - 180. + 3.;
+    Ok((
+        latitude * c(180. / PI),
+        longitude * c(180. / PI) + c(central_meridian),
+    ))
+}
+
+/// Converts an easting and northing to a latitude and a longitude relative to the central
+/// meridian, both in radians.
+#[cfg(feature = "inverse")]
+pub(crate) fn inverse<T: Scalar>(easting: T, northing: T, northern: bool) -> (T, T) {
+    let e2 = Float::powi(E, 2);
+    let e3 = Float::powi(E, 3);
+    let e_p2 = E / (1. - E);
+
+    let sqrt_e = Float::sqrt(1. - E);
+    let _e = (1. - sqrt_e) / (1. + sqrt_e);
+    let _e2 = Float::powi(_e, 2);
+    let _e3 = Float::powi(_e, 3);
+    let _e4 = Float::powi(_e, 4);
+    let _e5 = Float::powi(_e, 5);
+
+    let m1 = 1. - E / 4. - 3. * e2 / 64. - 5. * e3 / 256.;
+
+    let p2 = 3. / 2. * _e - 27. / 32. * _e3 + 269. / 512. * _e5;
+    let p3 = 21. / 16. * _e2 - 55. / 32. * _e4;
+    let p4 = 151. / 96. * _e3 - 417. / 128. * _e5;
+    let p5 = 1097. / 512. * _e4;
+
+    let x = easting - c(500000.);
+    let y = if northern {
+        northing
+    } else {
+        northing - c(10000000.)
+    };
+
+    let mu = y / c(K0 * WGS84.a * m1);
+
+    let p_rad = mu
+        + c::<T>(p2) * (c::<T>(2.) * mu).sin()
+        + c::<T>(p3) * (c::<T>(4.) * mu).sin()
+        + c::<T>(p4) * (c::<T>(6.) * mu).sin()
+        + c::<T>(p5) * (c::<T>(8.) * mu).sin();
+
+    let one = c::<T>(1.);
+    let p_sin2 = p_rad.sin().powi(2);
+    let p_cos = p_rad.cos();
+    let p_tan = p_rad.tan();
+    let p_tan2 = p_tan.powi(2);
+    let p_tan4 = p_tan.powi(4);
+
+    let ep_sin = one - c::<T>(E) * p_sin2;
+    let n = c::<T>(WGS84.a) / ep_sin.sqrt();
+    let r = c::<T>(1. - E) / ep_sin;
+
+    let cc = c::<T>(_e) * p_cos.powi(2);
+    let cc2 = cc.powi(2);
+
+    let d = x / (n * c(K0));
+    let d2 = d.powi(2);
+    let d3 = d.powi(3);
+    let d4 = d.powi(4);
+    let d5 = d.powi(5);
+    let d6 = d.powi(6);
+
+    let latitude = p_rad
+        - (p_tan / r)
+            * (d2 / c(2.)
+                - d4 / c(24.)
+                    * (c::<T>(5.) + c::<T>(3.) * p_tan2 + c::<T>(10.) * cc
+                        - c::<T>(4.) * cc2
+                        - c(9. * e_p2)))
+        + d6 / c(720.)
+            * (c::<T>(61.) + c::<T>(90.) * p_tan2 + c::<T>(298.) * cc + c::<T>(45.) * p_tan4
+                - c(252. * e_p2)
+                - c::<T>(3.) * cc2);
+
+    let longitude = (d - d3 / c(6.) * (one + c::<T>(2.) * p_tan2 + cc)
+        + d5 / c(120.)
+            * (c::<T>(5.) - c::<T>(2.) * cc + c::<T>(28.) * p_tan2 - c::<T>(3.) * cc2
+                + c(8. * e_p2)
+                + c::<T>(24.) * p_tan4))
+        / p_cos;
+
+    (latitude, longitude)
+}
+
+#[cfg(all(test, feature = "forward", feature = "inverse"))]
+mod tests {
+    use super::*;
+    use num_dual::{Dual64, DualNum};
+    use {to_utm_wgs84, wsg84_utm_to_lat_lon};
+
+    #[test]
+    fn matches_f64() {
+        for &(latitude, longitude, zone) in &[(40.62, -123.45, 10), (-33.92487, 18.42406, 34)] {
+            let (n1, e1, c1) = to_utm(latitude, longitude, zone);
+            let (n2, e2, c2) = to_utm_wgs84(latitude, longitude, zone);
+            assert!((n1 - n2).abs() < 1e-6);
+            assert!((e1 - e2).abs() < 1e-6);
+            assert!((c1 - c2).abs() < 1e-12);
+        }
+        let (lat1, lon1) = to_lat_lon(261878., 6243186., 34, 'H').unwrap();
+        let (lat2, lon2) = wsg84_utm_to_lat_lon(261878., 6243186., 34, 'H').unwrap();
+        assert!((lat1 - lat2).abs() < 1e-10);
+        assert!((lon1 - lon2).abs() < 1e-10);
+    }
+
+    #[test]
+    fn equator_is_northern() {
+        let (northing, easting, _) = to_utm(0f64, 3.5, 31);
+        let (expected_northing, expected_easting, _) = to_utm_wgs84(0., 3.5, 31);
+        assert_eq!(northing, 0.);
+        assert_eq!(expected_northing, 0.);
+        assert!((easting - expected_easting).abs() < 1e-6);
+        assert_eq!(to_utm(0f32, 3.5, 31).0, 0.);
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            to_lat_lon(50f64, 6243186., 34, 'H').unwrap_err(),
+            WSG84ToLatLonError::EastingOutOfRange
+        );
+        for &letter in &['I', 'O'] {
+            assert_eq!(
+                to_lat_lon(261878f64, 6243186., 34, letter).unwrap_err(),
+                WSG84ToLatLonError::ZoneLetterOutOfRange
+            );
+        }
+    }
+
+    /// A dual number, wrapped so it can implement `Scalar`.
+    #[derive(Clone, Copy, PartialEq, PartialOrd)]
+    struct Dual(Dual64);
+
+    macro_rules! dual_op {
+        ($trait:ident, $method:ident) => {
+            impl $trait for Dual {
+                type Output = Dual;
+                fn $method(self, other: Dual) -> Dual {
+                    Dual(self.0.$method(other.0))
+                }
+            }
+        };
+    }
+
+    dual_op!(Add, add);
+    dual_op!(Sub, sub);
+    dual_op!(Mul, mul);
+    dual_op!(Div, div);
+
+    impl Neg for Dual {
+        type Output = Dual;
+        fn neg(self) -> Dual {
+            Dual(-self.0)
+        }
+    }
+
+    impl Scalar for Dual {
+        fn from_f64(value: f64) -> Dual {
+            Dual(Dual64::from_re(value))
+        }
+
+        fn sin(self) -> Dual {
+            Dual(self.0.sin())
+        }
+
+        fn cos(self) -> Dual {
+            Dual(self.0.cos())
+        }
+
+        fn tan(self) -> Dual {
+            Dual(self.0.tan())
+        }
+
+        fn sqrt(self) -> Dual {
+            Dual(self.0.sqrt())
+        }
+
+        fn powi(self, n: i32) -> Dual {
+            Dual(self.0.powi(n))
+        }
+    }
+
+    #[test]
+    fn dual_derivatives_match_finite_differences() {
+        let (latitude, longitude, zone) = (-33.92487, 18.42406, 34);
+        let h = 1e-6;
+        let seeds = [(1., 0.), (0., 1.)];
+        for &(dlat, dlon) in &seeds {
+            let (northing, easting, _) = to_utm(
+                Dual(Dual64::new(latitude, dlat)),
+                Dual(Dual64::new(longitude, dlon)),
+                zone,
+            );
+            let (n1, e1, _) = to_utm(latitude - h * dlat, longitude - h * dlon, zone);
+            let (n2, e2, _) = to_utm(latitude + h * dlat, longitude + h * dlon, zone);
+            let (dn, de) = ((n2 - n1) / (2. * h), (e2 - e1) / (2. * h));
+            assert!((northing.0.eps - dn).abs() < 1e-6 * dn.abs());
+            assert!((easting.0.eps - de).abs() < 1e-6 * de.abs());
+        }
+
+        let (latitude, longitude) = to_lat_lon(
+            Dual(Dual64::new(261878., 1.)),
+            Dual(Dual64::from_re(6243186.)),
+            zone,
+            'H',
+        )
+        .unwrap();
+        let (lat1, lon1) = to_lat_lon(261878. - 1., 6243186., zone, 'H').unwrap();
+        let (lat2, lon2) = to_lat_lon(261878. + 1., 6243186., zone, 'H').unwrap();
+        assert!((latitude.0.eps - (lat2 - lat1) / 2.).abs() < 1e-10);
+        assert!((longitude.0.eps - (lon2 - lon1) / 2.).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,441 @@
+//! Geodesics between two points, and waypoints along them projected into one zone.
+//!
+//! A long flight leg drawn on a UTM grid by interpolating latitude and longitude, or easting and
+//! northing, between a few waypoints has a visible kink at each one, because neither is a
+//! straight line on the ground. `densify` instead puts points along the geodesic, the shortest
+//! path on the ellipsoid, at a fixed spacing, and projects all of them into one chosen zone so
+//! the leg is a single smooth curve on one grid. Projections use Krüger's series from `tm`,
+//! which stays accurate thousands of kilometers outside of the zone's nominal width.
+//!
+//! Geodesics are solved with Vincenty's formulae, which are good to well under a millimeter but
+//! don't converge for nearly antipodal points.
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use std::f64::consts::PI;
+use std::fmt;
+
+use super::tm::TransverseMercator;
+use super::Ellipsoid;
+
+/// The change in longitude, in radians on the auxiliary sphere, below which Vincenty's inverse
+/// iteration stops.
+const TOLERANCE: f64 = 1e-12;
+
+/// The maximum number of steps of Vincenty's iterations.
+const MAX_ITERATIONS: u32 = 200;
+
+/// Errors that can occur when solving a geodesic or densifying it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// A latitude was not between -90 and 90 degrees, or a coordinate was not finite.
+    Coordinate,
+    /// The geodesic could not be solved, which happens for nearly antipodal points.
+    NotConverged,
+    /// The spacing between waypoints was not a positive, finite number of meters.
+    Spacing,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Coordinate => write!(f, "Coordinate out of range"),
+            Error::NotConverged => write!(f, "Geodesic did not converge"),
+            Error::Spacing => write!(f, "Spacing must be positive and finite"),
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for Error {}
+
+/// The geodesic from one point to another on an ellipsoid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Geodesic {
+    start: (f64, f64),
+    end: (f64, f64),
+    distance: f64,
+    azimuth: f64,
+    flattening: f64,
+    semi_minor_axis: f64,
+    sin_u1: f64,
+    cos_u1: f64,
+    sin_alpha: f64,
+    cos2_alpha: f64,
+    sigma1: f64,
+    a: f64,
+    b: f64,
+}
+
+impl Geodesic {
+    /// Solves the geodesic between two latitudes and longitudes in decimal degrees.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::datum::GRS80;
+    /// use utm::geodesic::Geodesic;
+    /// // Flinders Peak to Buninyong, from Vincenty (1975)
+    /// let geodesic = Geodesic::between(
+    ///     GRS80,
+    ///     (-37.95103341666667, 144.42486788888888),
+    ///     (-37.65282113888889, 143.92649552777777),
+    /// )
+    /// .unwrap();
+    /// assert!((geodesic.distance() - 54972.271).abs() < 1e-3);
+    /// assert!((geodesic.initial_azimuth() - 306.8681583).abs() < 1e-6);
+    /// ```
+    pub fn between(
+        ellipsoid: Ellipsoid,
+        start: (f64, f64),
+        end: (f64, f64),
+    ) -> Result<Geodesic, Error> {
+        for &(latitude, longitude) in &[start, end] {
+            if !(-90. ..=90.).contains(&latitude) || !longitude.is_finite() {
+                return Err(Error::Coordinate);
+            }
+        }
+        let f = ellipsoid.flattening();
+        let (sin_u1, cos_u1) = reduced_latitude(f, start.0);
+        let (sin_u2, cos_u2) = reduced_latitude(f, end.0);
+        let l = normalize(end.1 - start.1).to_radians();
+        let mut lambda = l;
+        let mut iterations = 0;
+        let (lambda, sigma, sin_alpha, cos2_alpha, cos_2sm) = loop {
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+            let sin_sigma =
+                (cos_u2 * sin_lambda).hypot(cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda);
+            let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+            let sigma = sin_sigma.atan2(cos_sigma);
+            let sin_alpha = if sin_sigma == 0. {
+                0.
+            } else {
+                cos_u1 * cos_u2 * sin_lambda / sin_sigma
+            };
+            let cos2_alpha = 1. - sin_alpha * sin_alpha;
+            // Zero on the equator, where the formula is 0 / 0
+            let cos_2sm = if cos2_alpha == 0. {
+                0.
+            } else {
+                cos_sigma - 2. * sin_u1 * sin_u2 / cos2_alpha
+            };
+            let next = l + longitude_correction(
+                f, sin_alpha, cos2_alpha, sigma, sin_sigma, cos_sigma, cos_2sm,
+            );
+            iterations += 1;
+            if next.abs() > PI || iterations > MAX_ITERATIONS {
+                return Err(Error::NotConverged);
+            }
+            let step = (next - lambda).abs();
+            lambda = next;
+            if step < TOLERANCE {
+                break (lambda, sigma, sin_alpha, cos2_alpha, cos_2sm);
+            }
+        };
+        let (sin_lambda, cos_lambda) = lambda.sin_cos();
+        let (sin_sigma, cos_sigma) = sigma.sin_cos();
+        let b = ellipsoid.semi_major_axis() * (1. - f);
+        let (a_coefficient, b_coefficient) = coefficients(ellipsoid, cos2_alpha);
+        let delta_sigma = delta_sigma(b_coefficient, sin_sigma, cos_sigma, cos_2sm);
+        let azimuth = (cos_u2 * sin_lambda).atan2(cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda);
+        Ok(Geodesic {
+            start,
+            end,
+            distance: b * a_coefficient * (sigma - delta_sigma),
+            azimuth,
+            flattening: f,
+            semi_minor_axis: b,
+            sin_u1,
+            cos_u1,
+            sin_alpha,
+            cos2_alpha,
+            sigma1: (sin_u1 / cos_u1).atan2(azimuth.cos()),
+            a: a_coefficient,
+            b: b_coefficient,
+        })
+    }
+
+    /// Returns the length of the geodesic in meters.
+    pub fn distance(&self) -> f64 {
+        self.distance
+    }
+
+    /// Returns the azimuth at the start of the geodesic in decimal degrees clockwise from true
+    /// north, between 0 and 360.
+    pub fn initial_azimuth(&self) -> f64 {
+        let azimuth = self.azimuth.to_degrees();
+        if azimuth < 0. {
+            azimuth + 360.
+        } else {
+            azimuth
+        }
+    }
+
+    /// Returns the latitude and longitude in decimal degrees of the point a distance in meters
+    /// along the geodesic from its start.
+    ///
+    /// Distances beyond the end continue along the same geodesic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::datum::GRS80;
+    /// use utm::geodesic::Geodesic;
+    /// let geodesic = Geodesic::between(GRS80, (0., 0.), (0., 10.)).unwrap();
+    /// let (latitude, longitude) = geodesic.point_at(geodesic.distance() / 2.);
+    /// assert!(latitude.abs() < 1e-12 && (longitude - 5.).abs() < 1e-9);
+    /// ```
+    pub fn point_at(&self, distance: f64) -> (f64, f64) {
+        let f = self.flattening;
+        let (sin_alpha1, cos_alpha1) = self.azimuth.sin_cos();
+        let sigma0 = distance / (self.semi_minor_axis * self.a);
+        let mut sigma = sigma0;
+        let mut iterations = 0;
+        let (sin_sigma, cos_sigma, cos_2sm) = loop {
+            let cos_2sm = (2. * self.sigma1 + sigma).cos();
+            let (sin_sigma, cos_sigma) = sigma.sin_cos();
+            let next = sigma0 + delta_sigma(self.b, sin_sigma, cos_sigma, cos_2sm);
+            iterations += 1;
+            let step = (next - sigma).abs();
+            sigma = next;
+            if step < TOLERANCE || iterations > MAX_ITERATIONS {
+                let (sin_sigma, cos_sigma) = sigma.sin_cos();
+                break (sin_sigma, cos_sigma, (2. * self.sigma1 + sigma).cos());
+            }
+        };
+        let x = self.sin_u1 * sin_sigma - self.cos_u1 * cos_sigma * cos_alpha1;
+        let latitude = (self.sin_u1 * cos_sigma + self.cos_u1 * sin_sigma * cos_alpha1)
+            .atan2((1. - f) * self.sin_alpha.hypot(x));
+        let lambda = (sin_sigma * sin_alpha1)
+            .atan2(self.cos_u1 * cos_sigma - self.sin_u1 * sin_sigma * cos_alpha1);
+        let l = lambda
+            - longitude_correction(
+                f,
+                self.sin_alpha,
+                self.cos2_alpha,
+                sigma,
+                sin_sigma,
+                cos_sigma,
+                cos_2sm,
+            );
+        (
+            latitude.to_degrees(),
+            normalize(self.start.1 + l.to_degrees()),
+        )
+    }
+}
+
+/// Returns waypoints along the geodesic between two latitudes and longitudes in decimal degrees,
+/// no more than a spacing in meters apart, as eastings and northings in one projection.
+///
+/// The waypoints are evenly spaced along the geodesic and include both ends. The geodesic is
+/// solved on the projection's ellipsoid.
+///
+/// # Examples
+///
+/// ```
+/// use utm::converter::Converter;
+/// use utm::geodesic::densify;
+/// // Frankfurt to Moscow, all in zone 32
+/// let projection = Converter::new().projection(32, true);
+/// let waypoints: Vec<_> = densify(&projection, (50.03, 8.57), (55.97, 37.41), 10000.)
+///     .unwrap()
+///     .collect();
+/// assert_eq!(waypoints.len(), 204);
+/// let (easting, northing) = waypoints[203];
+/// let (end_northing, end_easting, _) = projection.forward(55.97, 37.41);
+/// assert_eq!((easting, northing), (end_easting, end_northing));
+/// ```
+pub fn densify(
+    projection: &TransverseMercator,
+    start: (f64, f64),
+    end: (f64, f64),
+    spacing: f64,
+) -> Result<Waypoints, Error> {
+    if !(spacing > 0. && spacing.is_finite()) {
+        return Err(Error::Spacing);
+    }
+    let geodesic = Geodesic::between(projection.ellipsoid(), start, end)?;
+    let segments = (geodesic.distance() / spacing).ceil().max(1.) as usize;
+    Ok(Waypoints {
+        geodesic,
+        projection: *projection,
+        segments,
+        index: 0,
+    })
+}
+
+/// An iterator over the eastings and northings of waypoints along a geodesic, created by
+/// `densify`.
+#[derive(Clone, Debug)]
+pub struct Waypoints {
+    geodesic: Geodesic,
+    projection: TransverseMercator,
+    segments: usize,
+    index: usize,
+}
+
+impl Waypoints {
+    /// Returns the geodesic the waypoints are on.
+    pub fn geodesic(&self) -> &Geodesic {
+        &self.geodesic
+    }
+}
+
+impl Iterator for Waypoints {
+    type Item = (f64, f64);
+
+    fn next(&mut self) -> Option<(f64, f64)> {
+        if self.index > self.segments {
+            return None;
+        }
+        // The ends are exact, rather than whatever the direct solution lands on
+        let (latitude, longitude) = if self.index == 0 {
+            self.geodesic.start
+        } else if self.index == self.segments {
+            self.geodesic.end
+        } else {
+            self.geodesic
+                .point_at(self.geodesic.distance * self.index as f64 / self.segments as f64)
+        };
+        self.index += 1;
+        let (northing, easting, _) = self.projection.forward(latitude, longitude);
+        Some((easting, northing))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.segments + 1 - self.index.min(self.segments + 1);
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Waypoints {}
+
+/// Returns the sine and cosine of the reduced latitude of a latitude in decimal degrees.
+fn reduced_latitude(flattening: f64, latitude: f64) -> (f64, f64) {
+    let (sin, cos) = latitude.to_radians().sin_cos();
+    let u = ((1. - flattening) * sin).atan2(cos);
+    u.sin_cos()
+}
+
+/// Returns Vincenty's A and B coefficients for the squared cosine of a geodesic's azimuth at
+/// the equator.
+fn coefficients(ellipsoid: Ellipsoid, cos2_alpha: f64) -> (f64, f64) {
+    let a = ellipsoid.semi_major_axis();
+    let b = a * (1. - ellipsoid.flattening());
+    let u2 = cos2_alpha * (a * a - b * b) / (b * b);
+    (
+        1. + u2 / 16384. * (4096. + u2 * (-768. + u2 * (320. - 175. * u2))),
+        u2 / 1024. * (256. + u2 * (-128. + u2 * (74. - 47. * u2))),
+    )
+}
+
+/// Returns the difference between an arc length on the auxiliary sphere and on the ellipsoid.
+fn delta_sigma(b: f64, sin_sigma: f64, cos_sigma: f64, cos_2sm: f64) -> f64 {
+    let cos2_2sm = cos_2sm * cos_2sm;
+    b * sin_sigma
+        * (cos_2sm
+            + b / 4.
+                * (cos_sigma * (-1. + 2. * cos2_2sm)
+                    - b / 6.
+                        * cos_2sm
+                        * (-3. + 4. * sin_sigma * sin_sigma)
+                        * (-3. + 4. * cos2_2sm)))
+}
+
+/// Returns the difference between a longitude on the auxiliary sphere and on the ellipsoid.
+fn longitude_correction(
+    f: f64,
+    sin_alpha: f64,
+    cos2_alpha: f64,
+    sigma: f64,
+    sin_sigma: f64,
+    cos_sigma: f64,
+    cos_2sm: f64,
+) -> f64 {
+    let c = f / 16. * cos2_alpha * (4. + f * (4. - 3. * cos2_alpha));
+    (1. - c)
+        * f
+        * sin_alpha
+        * (sigma + c * sin_sigma * (cos_2sm + c * cos_sigma * (-1. + 2. * cos_2sm * cos_2sm)))
+}
+
+/// Wraps a longitude in decimal degrees into [-180, 180).
+fn normalize(longitude: f64) -> f64 {
+    let wrapped = (longitude + 180.) % 360.;
+    if wrapped < 0. {
+        wrapped + 180.
+    } else {
+        wrapped - 180.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use converter::Converter;
+    use WGS84;
+
+    #[test]
+    fn round_trips() {
+        let geodesic = Geodesic::between(WGS84, (40.64, -73.78), (51.47, -0.45)).unwrap();
+        assert!((geodesic.distance() - 5_555_065.).abs() < 1000.);
+        let (latitude, longitude) = geodesic.point_at(geodesic.distance());
+        assert!((latitude - 51.47).abs() < 1e-9 && (longitude + 0.45).abs() < 1e-9);
+        // Across the antimeridian, and back again
+        let geodesic = Geodesic::between(WGS84, (-33.9, 151.2), (-36.85, -185.2)).unwrap();
+        let (latitude, longitude) = geodesic.point_at(geodesic.distance());
+        assert!((latitude + 36.85).abs() < 1e-9 && (longitude - 174.8).abs() < 1e-9);
+        assert_eq!(
+            Geodesic::between(WGS84, (0., 0.), (0.5, 179.7)),
+            Err(Error::NotConverged)
+        );
+        assert_eq!(
+            Geodesic::between(WGS84, (91., 0.), (0., 0.)),
+            Err(Error::Coordinate)
+        );
+        let geodesic = Geodesic::between(WGS84, (10., 10.), (10., 10.)).unwrap();
+        assert_eq!(geodesic.distance(), 0.);
+    }
+
+    #[test]
+    fn waypoints_bow_away_from_the_chord() {
+        let projection = Converter::new().projection(31, true);
+        let waypoints = densify(&projection, (50., -5.), (50., 7.), 1000.).unwrap();
+        assert_eq!(waypoints.len(), 861);
+        let geodesic = *waypoints.geodesic();
+        let spacing = geodesic.distance() / 860.;
+        let points: Vec<_> = waypoints.collect();
+        assert_eq!(points.len(), 861);
+        // The geodesic between two points on a parallel runs poleward of it, and isn't quite
+        // straight on the grid either
+        let (latitude, _) = geodesic.point_at(geodesic.distance() / 2.);
+        assert!(latitude > 50.1);
+        let chord = (
+            (points[0].0 + points[860].0) / 2.,
+            (points[0].1 + points[860].1) / 2.,
+        );
+        let (easting, northing) = points[430];
+        assert!((easting - chord.0).hypot(northing - chord.1) > 300.);
+        // Interpolating latitude and longitude is off by much more
+        let (naive_northing, naive_easting, _) = projection.forward(50., 1.);
+        assert!((easting - naive_easting).hypot(northing - naive_northing) > 10000.);
+        for pair in points.windows(2) {
+            let step = (pair[1].0 - pair[0].0).hypot(pair[1].1 - pair[0].1);
+            // Grid distances are scaled by at most 0.9996 to 1.0035 this far out
+            assert!(step > spacing * 0.999 && step < spacing * 1.004, "{}", step);
+        }
+        assert_eq!(
+            densify(&projection, (50., -5.), (50., 7.), 0.).unwrap_err(),
+            Error::Spacing
+        );
+        assert_eq!(
+            densify(&projection, (50., 5.), (50., 5.), 10.)
+                .unwrap()
+                .count(),
+            2
+        );
+    }
+}
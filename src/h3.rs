@@ -0,0 +1,143 @@
+//! Conversions to and from H3 cell indices, behind the `h3` feature.
+//!
+//! H3 is a hierarchical hexagonal index of the globe. These functions let a pipeline key its
+//! data on H3 cells while using this crate for its UTM coordinates, going through WGS84
+//! latitude and longitude in both directions.
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use h3o::{LatLng, Resolution};
+
+pub use h3o::CellIndex;
+
+#[cfg(feature = "inverse")]
+use super::wsg84_utm_to_lat_lon;
+use super::WSG84ToLatLonError;
+#[cfg(feature = "forward")]
+use super::{to_utm_wgs84, to_utm_wgs84_no_zone};
+
+/// Errors that can occur when converting to an H3 cell.
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The latitude or longitude is not a finite number.
+    InvalidLatLon,
+    /// The resolution is not between 0 and 15.
+    InvalidResolution,
+    /// The UTM coordinate could not be converted to a latitude and longitude.
+    Utm(WSG84ToLatLonError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidLatLon => write!(f, "Latitude and longitude must be finite"),
+            Error::InvalidResolution => write!(f, "H3 resolution must be between 0 and 15"),
+            Error::Utm(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<WSG84ToLatLonError> for Error {
+    fn from(err: WSG84ToLatLonError) -> Error {
+        Error::Utm(err)
+    }
+}
+
+/// Returns the H3 cell containing a WGS84 latitude and longitude in decimal degrees.
+///
+/// # Examples
+///
+/// ```
+/// use utm::h3::lat_lon_to_cell;
+/// let cell = lat_lon_to_cell(48.8566, 2.3522, 9).unwrap();
+/// assert_eq!(cell.to_string(), "891fb466257ffff");
+/// ```
+pub fn lat_lon_to_cell(latitude: f64, longitude: f64, resolution: u8) -> Result<CellIndex, Error> {
+    let resolution = Resolution::try_from(resolution).map_err(|_| Error::InvalidResolution)?;
+    let lat_lng = LatLng::new(latitude, longitude).map_err(|_| Error::InvalidLatLon)?;
+    Ok(lat_lng.to_cell(resolution))
+}
+
+/// Returns the latitude and longitude of the center of an H3 cell, in decimal degrees.
+///
+/// # Examples
+///
+/// ```
+/// use utm::h3::{cell_to_lat_lon, lat_lon_to_cell};
+/// let cell = lat_lon_to_cell(48.8566, 2.3522, 9).unwrap();
+/// let (latitude, longitude) = cell_to_lat_lon(cell);
+/// assert!((latitude - 48.8566).abs() < 1e-2);
+/// assert!((longitude - 2.3522).abs() < 1e-2);
+/// ```
+pub fn cell_to_lat_lon(cell: CellIndex) -> (f64, f64) {
+    let lat_lng = LatLng::from(cell);
+    (lat_lng.lat(), lat_lng.lng())
+}
+
+#[cfg(feature = "inverse")]
+/// Returns the H3 cell containing a UTM coordinate.
+///
+/// # Examples
+///
+/// ```
+/// use utm::h3::utm_to_cell;
+/// // Capetown, South Africa
+/// let cell = utm_to_cell(261878., 6243186., 34, 'H', 7).unwrap();
+/// ```
+pub fn utm_to_cell(
+    easting: f64,
+    northing: f64,
+    zone_num: u8,
+    zone_letter: char,
+    resolution: u8,
+) -> Result<CellIndex, Error> {
+    let (latitude, longitude) = wsg84_utm_to_lat_lon(easting, northing, zone_num, zone_letter)?;
+    lat_lon_to_cell(latitude, longitude, resolution)
+}
+
+#[cfg(feature = "forward")]
+/// Returns the UTM coordinates of the center of an H3 cell in the cell's own zone.
+///
+/// Returns the northing, easting, and meridian convergence, like `to_utm_wgs84_no_zone`.
+pub fn cell_to_utm(cell: CellIndex) -> (f64, f64, f64) {
+    let (latitude, longitude) = cell_to_lat_lon(cell);
+    to_utm_wgs84_no_zone(latitude, longitude)
+}
+
+#[cfg(feature = "forward")]
+/// Returns the UTM coordinates of the center of an H3 cell in the given zone.
+///
+/// Returns the northing, easting, and meridian convergence, like `to_utm_wgs84`.
+pub fn cell_to_utm_in_zone(cell: CellIndex, zone: u8) -> (f64, f64, f64) {
+    let (latitude, longitude) = cell_to_lat_lon(cell);
+    to_utm_wgs84(latitude, longitude, zone)
+}
+
+#[cfg(all(test, feature = "forward", feature = "inverse"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utm_round_trip() {
+        let cell = utm_to_cell(313784., 5427057., 60, 'G', 12).unwrap();
+        let (northing, easting, _) = cell_to_utm_in_zone(cell, 60);
+        // Resolution 12 cells are about 10 meters across.
+        assert!((easting - 313784.).abs() < 15.);
+        assert!((northing - 5427057.).abs() < 15.);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            lat_lon_to_cell(0., 0., 16).unwrap_err(),
+            Error::InvalidResolution
+        );
+        assert_eq!(
+            lat_lon_to_cell(f64::NAN, 0., 5).unwrap_err(),
+            Error::InvalidLatLon
+        );
+    }
+}
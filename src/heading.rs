@@ -0,0 +1,263 @@
+//! True and grid headings along a route.
+//!
+//! A line that is straight on the grid crosses the meridians at a changing angle, because the
+//! meridian convergence changes along it. Flying it means steering a true heading that drifts
+//! along the way, by degrees over a long east-west leg. Conversely, the grid heading of a
+//! constant true heading drifts. These functions sample either kind of line and report both
+//! headings at every sample, with the convergence evaluated at each one rather than once for
+//! the whole leg.
+//!
+//! Headings are in radians clockwise from north, between 0 and 2π. Projections use Krüger's
+//! series through `converter::Converter`.
+
+use std::f64::consts::PI;
+use std::fmt;
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use super::converter::Converter;
+use super::frame::normalize;
+use super::{lat_to_zone_letter, UtmCoordinate, WSG84ToLatLonError};
+
+/// The mean radius of the earth in meters, used for rhumb lines.
+const EARTH_RADIUS: f64 = 6371008.8;
+
+/// A point along a route, with its true and grid headings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sample {
+    /// The latitude in decimal degrees.
+    pub latitude: f64,
+    /// The longitude in decimal degrees.
+    pub longitude: f64,
+    /// The position in the route's zone.
+    pub coordinate: UtmCoordinate,
+    /// The heading in radians clockwise from true north.
+    pub true_heading: f64,
+    /// The heading in radians clockwise from grid north.
+    pub grid_heading: f64,
+}
+
+/// Errors that can occur when sampling a route.
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The start and end of a grid line are in different zones or hemispheres.
+    ZoneMismatch,
+    /// A rhumb line leaves the UTM latitude bands.
+    OutOfBands,
+    /// A coordinate could not be converted to a latitude and longitude.
+    Utm(WSG84ToLatLonError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::ZoneMismatch => write!(f, "Start and end are in different zones"),
+            Error::OutOfBands => write!(f, "Route leaves the UTM latitude bands"),
+            Error::Utm(err) => err.fmt(f),
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for Error {}
+
+impl From<WSG84ToLatLonError> for Error {
+    fn from(err: WSG84ToLatLonError) -> Error {
+        Error::Utm(err)
+    }
+}
+
+/// Samples a line that is straight on the grid, from `start` to `end`, and returns how many
+/// samples were written.
+///
+/// The samples are evenly spaced and include both ends, so there is one sample per slot of
+/// `samples`. The grid heading is the same at every sample and the true heading varies.
+///
+/// # Examples
+///
+/// ```
+/// use utm::heading::{along_grid_line, Sample};
+/// use utm::UtmCoordinate;
+/// // 300 km due grid east across zone 32
+/// let start = UtmCoordinate::new(350000., 5300000., 32, 'T');
+/// let end = UtmCoordinate::new(650000., 5300000., 32, 'T');
+/// let mut samples = [Sample {
+///     latitude: 0.,
+///     longitude: 0.,
+///     coordinate: start,
+///     true_heading: 0.,
+///     grid_heading: 0.,
+/// }; 5];
+/// assert_eq!(along_grid_line(&start, &end, &mut samples), Ok(5));
+/// let drift = samples[4].true_heading - samples[0].true_heading;
+/// // Over two degrees of true heading between the ends
+/// assert!(drift.to_degrees() > 2.);
+/// ```
+pub fn along_grid_line(
+    start: &UtmCoordinate,
+    end: &UtmCoordinate,
+    samples: &mut [Sample],
+) -> Result<usize, Error> {
+    if start.zone_num != end.zone_num || (start.zone_letter >= 'N') != (end.zone_letter >= 'N') {
+        return Err(Error::ZoneMismatch);
+    }
+    let converter = Converter::new();
+    let northern = start.zone_letter >= 'N';
+    let projection = converter.projection(start.zone_num, northern);
+    let (de, dn) = (end.easting - start.easting, end.northing - start.northing);
+    let grid_heading = normalize(de.atan2(dn));
+    let len = samples.len();
+    for (index, sample) in samples.iter_mut().enumerate() {
+        let fraction = fraction(index, len);
+        let mut coordinate = UtmCoordinate::new(
+            start.easting + fraction * de,
+            start.northing + fraction * dn,
+            start.zone_num,
+            start.zone_letter,
+        );
+        let (latitude, longitude) = converter.to_lat_lon(&coordinate)?;
+        coordinate.zone_letter = lat_to_zone_letter(latitude).unwrap_or(start.zone_letter);
+        let (_, _, convergence) = projection.forward(latitude, longitude);
+        *sample = Sample {
+            latitude,
+            longitude,
+            coordinate,
+            true_heading: normalize(grid_heading - convergence),
+            grid_heading,
+        };
+    }
+    Ok(len)
+}
+
+/// Samples a rhumb line, which keeps a constant true heading, starting at a latitude and
+/// longitude in decimal degrees and running `distance` meters, and returns how many samples
+/// were written.
+///
+/// The samples are evenly spaced and include both ends and are projected into `zone`. The true
+/// heading is the same at every sample and the grid heading varies. The rhumb line is computed
+/// on a sphere, which places the far end within a fraction of a percent of the distance.
+///
+/// # Examples
+///
+/// ```
+/// use utm::heading::{along_rhumb_line, Sample};
+/// use utm::UtmCoordinate;
+/// let mut samples = [Sample {
+///     latitude: 0.,
+///     longitude: 0.,
+///     coordinate: UtmCoordinate::new(0., 0., 1, 'C'),
+///     true_heading: 0.,
+///     grid_heading: 0.,
+/// }; 3];
+/// // Due east for 300 km, starting 150 km west of zone 32's central meridian
+/// along_rhumb_line(47.8, 7., 90f64.to_radians(), 300000., 32, &mut samples).unwrap();
+/// assert!(samples[0].grid_heading > samples[2].grid_heading);
+/// ```
+pub fn along_rhumb_line(
+    latitude: f64,
+    longitude: f64,
+    true_heading: f64,
+    distance: f64,
+    zone: u8,
+    samples: &mut [Sample],
+) -> Result<usize, Error> {
+    let converter = Converter::new();
+    let phi0 = latitude.to_radians();
+    let len = samples.len();
+    for (index, sample) in samples.iter_mut().enumerate() {
+        let delta = fraction(index, len) * distance / EARTH_RADIUS;
+        let phi = phi0 + delta * true_heading.cos();
+        if phi.abs() > PI / 2. {
+            return Err(Error::OutOfBands);
+        }
+        let dpsi = ((PI / 4. + phi / 2.).tan() / (PI / 4. + phi0 / 2.).tan()).ln();
+        // East-west legs have no change in isometric latitude, so use the limit.
+        let q = if dpsi.abs() > 1e-12 {
+            (phi - phi0) / dpsi
+        } else {
+            phi0.cos()
+        };
+        let lat = phi.to_degrees();
+        let lon = longitude + (delta * true_heading.sin() / q).to_degrees();
+        let lon = (lon + 540.) % 360. - 180.;
+        let zone_letter = lat_to_zone_letter(lat).ok_or(Error::OutOfBands)?;
+        let (northing, easting, convergence) = converter
+            .projection(zone, zone_letter >= 'N')
+            .forward(lat, lon);
+        *sample = Sample {
+            latitude: lat,
+            longitude: lon,
+            coordinate: UtmCoordinate::new(easting, northing, zone, zone_letter),
+            true_heading: normalize(true_heading),
+            grid_heading: normalize(true_heading + convergence),
+        };
+    }
+    Ok(len)
+}
+
+/// Returns how far along the route sample `index` of `len` is, from 0 to 1.
+fn fraction(index: usize, len: usize) -> f64 {
+    if len > 1 {
+        index as f64 / (len - 1) as f64
+    } else {
+        0.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty() -> Sample {
+        Sample {
+            latitude: 0.,
+            longitude: 0.,
+            coordinate: UtmCoordinate::new(0., 0., 1, 'C'),
+            true_heading: 0.,
+            grid_heading: 0.,
+        }
+    }
+
+    #[test]
+    fn grid_north_on_the_central_meridian() {
+        let start = UtmCoordinate::new(500000., 6000000., 33, 'H');
+        let end = UtmCoordinate::new(500000., 6100000., 33, 'H');
+        let mut samples = [empty(); 3];
+        along_grid_line(&start, &end, &mut samples).unwrap();
+        for sample in &samples {
+            assert_eq!(sample.grid_heading, 0.);
+            assert!(sample.true_heading.min(2. * PI - sample.true_heading) < 1e-9);
+            assert!((sample.longitude - 15.).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn rhumb_line_ends() {
+        let mut samples = [empty(); 4];
+        along_rhumb_line(-40., 170., PI / 4., 100000., 59, &mut samples).unwrap();
+        assert_eq!(samples[0].latitude, -40.);
+        assert_eq!(samples[0].longitude, 170.);
+        // Headed north east, so the grid heading stays close to the true heading.
+        for sample in &samples {
+            assert_eq!(sample.true_heading, PI / 4.);
+            assert!((sample.grid_heading - PI / 4.).abs() < 0.05);
+        }
+        assert!(samples[3].latitude > -40. && samples[3].longitude > 170.);
+    }
+
+    #[test]
+    fn errors() {
+        let start = UtmCoordinate::new(500000., 6000000., 33, 'H');
+        let end = UtmCoordinate::new(500000., 6000000., 34, 'H');
+        assert_eq!(
+            along_grid_line(&start, &end, &mut [empty(); 2]),
+            Err(Error::ZoneMismatch)
+        );
+        assert_eq!(
+            along_rhumb_line(83., 0., 0., 200000., 31, &mut [empty(); 2]),
+            Err(Error::OutOfBands)
+        );
+    }
+}
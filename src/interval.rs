@@ -0,0 +1,460 @@
+//! Conversions of intervals, behind the `interval` feature.
+//!
+//! An `Interval` is a closed range of numbers. Converting intervals gives a rigorous enclosure
+//! of every result the conversion could produce for inputs in the given ranges, including the
+//! floating point error of every intermediate step. That bounds the combined effect of input
+//! uncertainty and rounding in one go.
+//!
+//! The enclosure is of the same series `to_utm_wgs84` and `wsg84_utm_to_lat_lon` evaluate,
+//! with its constants taken as exact. It does not include how far those series are from the
+//! exact transverse Mercator projection. Rigor relies on the platform `sin`, `cos`, and `tan`
+//! being accurate to within an ulp, which holds for common math libraries.
+
+use std::f64::consts::PI;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use super::generic;
+use super::{next_down, next_up};
+#[cfg(feature = "inverse")]
+use super::{WSG84ToLatLonError, ZONE_LETTERS};
+
+/// A closed interval of numbers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Interval {
+    lo: f64,
+    hi: f64,
+}
+
+impl Interval {
+    /// Creates an interval from its bounds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lo` is greater than `hi`, or either is NaN.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::interval::Interval;
+    /// let interval = Interval::new(1., 2.);
+    /// assert!(interval.contains(1.5));
+    /// ```
+    pub fn new(lo: f64, hi: f64) -> Interval {
+        assert!(lo <= hi, "invalid interval [{}, {}]", lo, hi);
+        Interval { lo, hi }
+    }
+
+    /// Creates an interval containing just one number.
+    pub fn point(value: f64) -> Interval {
+        Interval::new(value, value)
+    }
+
+    /// Creates the interval of numbers within `radius` of `center`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::interval::Interval;
+    /// let interval = Interval::with_radius(10., 0.5);
+    /// assert!(interval.contains(9.5) && interval.contains(10.5));
+    /// ```
+    pub fn with_radius(center: f64, radius: f64) -> Interval {
+        widen(center - radius, center + radius, 1)
+    }
+
+    /// Returns the lower bound.
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+
+    /// Returns the upper bound.
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    /// Returns the middle of the interval.
+    pub fn midpoint(&self) -> f64 {
+        self.lo + (self.hi - self.lo) / 2.
+    }
+
+    /// Returns the width of the interval.
+    pub fn width(&self) -> f64 {
+        self.hi - self.lo
+    }
+
+    /// Returns true if the interval contains a number.
+    pub fn contains(&self, value: f64) -> bool {
+        (self.lo..=self.hi).contains(&value)
+    }
+
+    fn contains_zero(&self) -> bool {
+        self.contains(0.)
+    }
+
+    /// Returns true if `lo + period * k` is in the interval for some integer `k`, erring towards
+    /// true when it is within rounding error of an end.
+    fn contains_periodic(&self, lo: f64, period: f64) -> bool {
+        let slack = 1e-9 * self.lo.abs().max(self.hi.abs()).max(1.);
+        let k = ((self.lo - lo) / period).floor();
+        (0..3).any(|i| {
+            let point = lo + period * (k + f64::from(i));
+            self.lo - slack <= point && point <= self.hi + slack
+        })
+    }
+}
+
+/// Returns `[lo, hi]` widened by `ulps` representable numbers at each end.
+fn widen(mut lo: f64, mut hi: f64, ulps: u32) -> Interval {
+    for _ in 0..ulps {
+        lo = next_down(lo);
+        hi = next_up(hi);
+    }
+    Interval { lo, hi }
+}
+
+/// Returns the smallest interval containing all of `values`, widened by `ulps`.
+fn hull(values: [f64; 4], ulps: u32) -> Interval {
+    let lo = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let hi = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    widen(lo, hi, ulps)
+}
+
+impl Add for Interval {
+    type Output = Interval;
+    fn add(self, other: Interval) -> Interval {
+        widen(self.lo + other.lo, self.hi + other.hi, 1)
+    }
+}
+
+impl Sub for Interval {
+    type Output = Interval;
+    fn sub(self, other: Interval) -> Interval {
+        widen(self.lo - other.hi, self.hi - other.lo, 1)
+    }
+}
+
+impl Mul for Interval {
+    type Output = Interval;
+    fn mul(self, other: Interval) -> Interval {
+        hull(
+            [
+                self.lo * other.lo,
+                self.lo * other.hi,
+                self.hi * other.lo,
+                self.hi * other.hi,
+            ],
+            1,
+        )
+    }
+}
+
+impl Div for Interval {
+    type Output = Interval;
+    fn div(self, other: Interval) -> Interval {
+        if other.contains_zero() {
+            return Interval::new(f64::NEG_INFINITY, f64::INFINITY);
+        }
+        hull(
+            [
+                self.lo / other.lo,
+                self.lo / other.hi,
+                self.hi / other.lo,
+                self.hi / other.hi,
+            ],
+            1,
+        )
+    }
+}
+
+impl Neg for Interval {
+    type Output = Interval;
+    fn neg(self) -> Interval {
+        Interval {
+            lo: -self.hi,
+            hi: -self.lo,
+        }
+    }
+}
+
+impl Interval {
+    /// Returns an enclosure of the sine of every number in the interval.
+    pub fn sin(self) -> Interval {
+        if self.width() >= 2. * PI {
+            return Interval::new(-1., 1.);
+        }
+        let (a, b) = (self.lo.sin(), self.hi.sin());
+        let mut interval = widen(a.min(b), a.max(b), 2);
+        if self.contains_periodic(PI / 2., 2. * PI) {
+            interval.hi = 1.;
+        }
+        if self.contains_periodic(-PI / 2., 2. * PI) {
+            interval.lo = -1.;
+        }
+        Interval::new(interval.lo.max(-1.), interval.hi.min(1.))
+    }
+
+    /// Returns an enclosure of the cosine of every number in the interval.
+    pub fn cos(self) -> Interval {
+        if self.width() >= 2. * PI {
+            return Interval::new(-1., 1.);
+        }
+        let (a, b) = (self.lo.cos(), self.hi.cos());
+        let mut interval = widen(a.min(b), a.max(b), 2);
+        if self.contains_periodic(0., 2. * PI) {
+            interval.hi = 1.;
+        }
+        if self.contains_periodic(PI, 2. * PI) {
+            interval.lo = -1.;
+        }
+        Interval::new(interval.lo.max(-1.), interval.hi.min(1.))
+    }
+
+    /// Returns an enclosure of the tangent of every number in the interval, which is unbounded
+    /// if the interval contains a pole.
+    pub fn tan(self) -> Interval {
+        if self.width() >= PI || self.contains_periodic(PI / 2., PI) {
+            return Interval::new(f64::NEG_INFINITY, f64::INFINITY);
+        }
+        widen(self.lo.tan(), self.hi.tan(), 2)
+    }
+
+    /// Returns an enclosure of the square root of every non-negative number in the interval.
+    pub fn sqrt(self) -> Interval {
+        widen(self.lo.max(0.).sqrt(), self.hi.sqrt(), 1)
+    }
+
+    /// Returns an enclosure of the `n`th power of every number in the interval.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is negative.
+    pub fn powi(self, n: i32) -> Interval {
+        assert!(n >= 0, "negative powers are not supported");
+        let ulps = n as u32 + 1;
+        let (a, b) = (self.lo.powi(n), self.hi.powi(n));
+        if n % 2 == 1 {
+            widen(a, b, ulps)
+        } else if self.contains_zero() {
+            let hi = widen(a.max(b), a.max(b), ulps).hi;
+            Interval::new(0., hi)
+        } else {
+            let interval = widen(a.min(b), a.max(b), ulps);
+            Interval::new(interval.lo.max(0.), interval.hi)
+        }
+    }
+}
+
+impl generic::Scalar for Interval {
+    fn from_f64(value: f64) -> Interval {
+        Interval::point(value)
+    }
+
+    fn sin(self) -> Interval {
+        Interval::sin(self)
+    }
+
+    fn cos(self) -> Interval {
+        Interval::cos(self)
+    }
+
+    fn tan(self) -> Interval {
+        Interval::tan(self)
+    }
+
+    fn sqrt(self) -> Interval {
+        Interval::sqrt(self)
+    }
+
+    fn powi(self, n: i32) -> Interval {
+        Interval::powi(self, n)
+    }
+}
+
+/// Returns an enclosure of `PI / 180`.
+fn degrees_to_radians() -> Interval {
+    widen(PI / 180., PI / 180., 2)
+}
+
+#[cfg(feature = "forward")]
+/// Converts a latitude and longitude interval in decimal degrees to UTM coordinates in the
+/// given zone, like `to_utm_wgs84`.
+///
+/// Returns enclosures of the northing, easting, and meridian convergence, or `None` if the
+/// latitude interval contains points on both sides of the equator, where the northing jumps by
+/// the false northing.
+///
+/// # Examples
+///
+/// ```
+/// use utm::interval::{to_utm, Interval};
+/// // A fix with a 1e-6° uncertainty
+/// let latitude = Interval::with_radius(40.62, 1e-6);
+/// let longitude = Interval::with_radius(-123.45, 1e-6);
+/// let (northing, easting, _) = to_utm(latitude, longitude, 10).unwrap();
+/// let (expected_northing, expected_easting, _) = utm::to_utm_wgs84(40.62, -123.45, 10);
+/// assert!(northing.contains(expected_northing) && easting.contains(expected_easting));
+/// assert!(northing.width() < 0.5);
+/// ```
+pub fn to_utm(
+    latitude: Interval,
+    longitude: Interval,
+    zone: u8,
+) -> Option<(Interval, Interval, Interval)> {
+    // The equator is in band N, so it is northern
+    let northern = if latitude.lo >= 0. {
+        true
+    } else if latitude.hi < 0. {
+        false
+    } else {
+        return None;
+    };
+    let to_radians = degrees_to_radians();
+    Some(generic::forward(
+        latitude * to_radians,
+        longitude * to_radians,
+        zone,
+        northern,
+    ))
+}
+
+#[cfg(feature = "inverse")]
+/// Converts an easting and northing interval to a latitude and longitude in decimal degrees, like
+/// `wsg84_utm_to_lat_lon`.
+///
+/// Every point of the intervals has to be in range.
+///
+/// # Examples
+///
+/// ```
+/// use utm::interval::{to_lat_lon, Interval};
+/// let easting = Interval::with_radius(261878., 0.01);
+/// let northing = Interval::with_radius(6243186., 0.01);
+/// let (latitude, longitude) = to_lat_lon(easting, northing, 34, 'H').unwrap();
+/// let (expected_latitude, expected_longitude) =
+///     utm::wsg84_utm_to_lat_lon(261878., 6243186., 34, 'H').unwrap();
+/// assert!(latitude.contains(expected_latitude) && longitude.contains(expected_longitude));
+/// assert!(latitude.width() < 1e-6);
+/// ```
+pub fn to_lat_lon(
+    easting: Interval,
+    northing: Interval,
+    zone_num: u8,
+    zone_letter: char,
+) -> Result<(Interval, Interval), WSG84ToLatLonError> {
+    if easting.lo < 100000. || easting.hi >= 1000000. {
+        return Err(WSG84ToLatLonError::EastingOutOfRange);
+    }
+    if northing.lo < 0. || northing.hi > 10000000. {
+        return Err(WSG84ToLatLonError::NorthingOutOfRange);
+    }
+    if !(1..=60).contains(&zone_num) {
+        return Err(WSG84ToLatLonError::ZoneNumOutOfRange);
+    }
+    if !ZONE_LETTERS.contains(zone_letter) {
+        return Err(WSG84ToLatLonError::ZoneLetterOutOfRange);
+    }
+    let (latitude, longitude) = generic::inverse(easting, northing, zone_letter >= 'N');
+    let to_degrees = Interval::point(1.) / degrees_to_radians();
+    let central_meridian = Interval::point((f64::from(zone_num) - 1.) * 6. - 180. + 3.);
+    Ok((
+        latitude * to_degrees,
+        longitude * to_degrees + central_meridian,
+    ))
+}
+
+#[cfg(all(test, feature = "forward", feature = "inverse"))]
+mod tests {
+    use super::*;
+    use {to_utm_wgs84, wsg84_utm_to_lat_lon};
+
+    #[test]
+    fn encloses_samples() {
+        for &(latitude, longitude, zone) in
+            &[(40.62, -123.45, 10), (-33.9, 18.4, 34), (70., 24., 35)]
+        {
+            let radius = 1e-4;
+            let (northing, easting, convergence) = to_utm(
+                Interval::with_radius(latitude, radius),
+                Interval::with_radius(longitude, radius),
+                zone,
+            )
+            .unwrap();
+            for i in 0..=10 {
+                for j in 0..=10 {
+                    let lat = latitude - radius + 2. * radius * f64::from(i) / 10.;
+                    let lon = longitude - radius + 2. * radius * f64::from(j) / 10.;
+                    let (n, e, c) = to_utm_wgs84(lat, lon, zone);
+                    assert!(northing.contains(n), "{:?} {}", northing, n);
+                    assert!(easting.contains(e), "{:?} {}", easting, e);
+                    assert!(convergence.contains(c), "{:?} {}", convergence, c);
+                }
+            }
+            assert!(northing.width() < 100. && easting.width() < 100.);
+        }
+    }
+
+    #[test]
+    fn inverse_encloses_samples() {
+        let radius = 5.;
+        let (latitude, longitude) = to_lat_lon(
+            Interval::with_radius(261878., radius),
+            Interval::with_radius(6243186., radius),
+            34,
+            'H',
+        )
+        .unwrap();
+        for i in 0..=10 {
+            let easting = 261878. - radius + 2. * radius * f64::from(i) / 10.;
+            let (lat, lon) = wsg84_utm_to_lat_lon(easting, 6243186. + radius, 34, 'H').unwrap();
+            assert!(latitude.contains(lat) && longitude.contains(lon));
+        }
+        assert!(latitude.width() < 1e-3 && longitude.width() < 1e-3);
+    }
+
+    #[test]
+    fn inverse_rejects_i_and_o() {
+        for &letter in &['I', 'O'] {
+            assert_eq!(
+                to_lat_lon(
+                    Interval::point(261878.),
+                    Interval::point(6243186.),
+                    34,
+                    letter
+                )
+                .unwrap_err(),
+                WSG84ToLatLonError::ZoneLetterOutOfRange
+            );
+        }
+    }
+
+    #[test]
+    fn straddling_the_equator() {
+        assert!(to_utm(Interval::new(-1., 1.), Interval::point(3.), 31).is_none());
+        assert!(to_utm(Interval::new(-1., 0.), Interval::point(3.), 31).is_none());
+        let (northing, _, _) = to_utm(Interval::point(0.), Interval::point(3.), 31).unwrap();
+        assert!(northing.contains(to_utm_wgs84(0., 3., 31).0));
+        assert!(northing.hi() < 1e-6);
+        assert_eq!(
+            to_lat_lon(Interval::new(50000., 200000.), Interval::point(0.), 31, 'N').unwrap_err(),
+            WSG84ToLatLonError::EastingOutOfRange
+        );
+    }
+
+    #[test]
+    fn trigonometry() {
+        let sin = Interval::new(1., 2.).sin();
+        assert_eq!(sin.hi(), 1.);
+        assert!(sin.contains(2f64.sin()) && sin.contains(1f64.sin()));
+        let cos = Interval::new(3., 4.).cos();
+        assert_eq!(cos.lo(), -1.);
+        let tan = Interval::new(1., 2.).tan();
+        assert_eq!(tan.hi(), f64::INFINITY);
+        let square = Interval::new(-2., 1.).powi(2);
+        assert_eq!(square.lo(), 0.);
+        assert!(square.contains(4.));
+    }
+}
@@ -0,0 +1,240 @@
+//! The Japanese plane rectangular coordinate systems on JGD2011.
+//!
+//! Japan divides the country into nineteen zones, numbered I through XIX, each a transverse
+//! Mercator projection on GRS80 with a scale factor of 0.9999 and its own origin. There is no
+//! false easting or northing, so coordinates are negative south and west of the origin.
+//!
+//! By Japanese convention the northing is called X and the easting is called Y. The
+//! projections returned here use the crate's usual order, so `forward` returns `(X, Y,
+//! meridian_convergence)`.
+
+use super::datum::GRS80;
+use super::tm::TransverseMercator;
+
+/// The scale factor on the central meridian of every zone.
+pub const SCALE_FACTOR: f64 = 0.9999;
+
+/// The origin latitude and longitude of zones I through XIX, in decimal degrees.
+const ORIGINS: [(f64, f64); 19] = [
+    (33., 129. + 30. / 60.),
+    (33., 131.),
+    (36., 132. + 10. / 60.),
+    (33., 133. + 30. / 60.),
+    (36., 134. + 20. / 60.),
+    (36., 136.),
+    (36., 137. + 10. / 60.),
+    (36., 138. + 30. / 60.),
+    (36., 139. + 50. / 60.),
+    (40., 140. + 50. / 60.),
+    (44., 140. + 15. / 60.),
+    (44., 142. + 15. / 60.),
+    (44., 144. + 15. / 60.),
+    (26., 142.),
+    (26., 127. + 30. / 60.),
+    (26., 124.),
+    (26., 131.),
+    (20., 136.),
+    (26., 154.),
+];
+
+/// Returns the projection for a zone, numbered 1 through 19.
+///
+/// # Examples
+///
+/// ```
+/// use utm::jgd2011;
+/// // The Geospatial Information Authority of Japan's worked example in Tsukuba
+/// let zone = jgd2011::zone(9).unwrap();
+/// let latitude = 36. + 6. / 60. + 13.58925 / 3600.;
+/// let longitude = 140. + 5. / 60. + 16.27815 / 3600.;
+/// let (x, y, _) = zone.forward(latitude, longitude);
+/// assert!((x - 11543.6883).abs() < 1e-3);
+/// assert!((y - 22916.2436).abs() < 1e-3);
+/// assert!(jgd2011::zone(20).is_none());
+/// ```
+pub fn zone(number: u8) -> Option<TransverseMercator> {
+    if (1..=19).contains(&number) {
+        let (latitude, longitude) = ORIGINS[number as usize - 1];
+        Some(TransverseMercator::new(
+            GRS80,
+            latitude,
+            longitude,
+            SCALE_FACTOR,
+            0.,
+            0.,
+        ))
+    } else {
+        None
+    }
+}
+
+/// A Japanese prefecture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Prefecture {
+    /// Hokkaido, in zones XI to XIII.
+    Hokkaido,
+    /// Aomori, in zone X.
+    Aomori,
+    /// Iwate, in zone X.
+    Iwate,
+    /// Miyagi, in zone X.
+    Miyagi,
+    /// Akita, in zone X.
+    Akita,
+    /// Yamagata, in zone X.
+    Yamagata,
+    /// Fukushima, in zone IX.
+    Fukushima,
+    /// Ibaraki, in zone IX.
+    Ibaraki,
+    /// Tochigi, in zone IX.
+    Tochigi,
+    /// Gunma, in zone IX.
+    Gunma,
+    /// Saitama, in zone IX.
+    Saitama,
+    /// Chiba, in zone IX.
+    Chiba,
+    /// Tokyo, in zone IX, with zones XIV, XVIII, and XIX for its outlying islands.
+    Tokyo,
+    /// Kanagawa, in zone IX.
+    Kanagawa,
+    /// Niigata, in zone VIII.
+    Niigata,
+    /// Toyama, in zone VII.
+    Toyama,
+    /// Ishikawa, in zone VII.
+    Ishikawa,
+    /// Fukui, in zone VI.
+    Fukui,
+    /// Yamanashi, in zone VIII.
+    Yamanashi,
+    /// Nagano, in zone VIII.
+    Nagano,
+    /// Gifu, in zone VII.
+    Gifu,
+    /// Shizuoka, in zone VIII.
+    Shizuoka,
+    /// Aichi, in zone VII.
+    Aichi,
+    /// Mie, in zone VI.
+    Mie,
+    /// Shiga, in zone VI.
+    Shiga,
+    /// Kyoto, in zone VI.
+    Kyoto,
+    /// Osaka, in zone VI.
+    Osaka,
+    /// Hyogo, in zone V.
+    Hyogo,
+    /// Nara, in zone VI.
+    Nara,
+    /// Wakayama, in zone VI.
+    Wakayama,
+    /// Tottori, in zone V.
+    Tottori,
+    /// Shimane, in zone III.
+    Shimane,
+    /// Okayama, in zone V.
+    Okayama,
+    /// Hiroshima, in zone III.
+    Hiroshima,
+    /// Yamaguchi, in zone III.
+    Yamaguchi,
+    /// Tokushima, in zone IV.
+    Tokushima,
+    /// Kagawa, in zone IV.
+    Kagawa,
+    /// Ehime, in zone IV.
+    Ehime,
+    /// Kochi, in zone IV.
+    Kochi,
+    /// Fukuoka, in zone II.
+    Fukuoka,
+    /// Saga, in zone II.
+    Saga,
+    /// Nagasaki, in zone I.
+    Nagasaki,
+    /// Kumamoto, in zone II.
+    Kumamoto,
+    /// Oita, in zone II.
+    Oita,
+    /// Miyazaki, in zone II.
+    Miyazaki,
+    /// Kagoshima, in zone II, with zone I for its islands west of 130°E.
+    Kagoshima,
+    /// Okinawa, in zones XV to XVII.
+    Okinawa,
+}
+
+impl Prefecture {
+    /// Returns the zones used in this prefecture, each with the longitude up to which it
+    /// applies, from west to east.
+    fn zones(self) -> &'static [(u8, f64)] {
+        use self::Prefecture::*;
+        match self {
+            Nagasaki => &[(1, 180.)],
+            Kagoshima => &[(1, 130.), (2, 180.)],
+            Fukuoka | Saga | Kumamoto | Oita | Miyazaki => &[(2, 180.)],
+            Yamaguchi | Shimane | Hiroshima => &[(3, 180.)],
+            Kagawa | Ehime | Tokushima | Kochi => &[(4, 180.)],
+            Hyogo | Tottori | Okayama => &[(5, 180.)],
+            Kyoto | Osaka | Fukui | Shiga | Mie | Nara | Wakayama => &[(6, 180.)],
+            Ishikawa | Toyama | Gifu | Aichi => &[(7, 180.)],
+            Niigata | Nagano | Yamanashi | Shizuoka => &[(8, 180.)],
+            Fukushima | Tochigi | Ibaraki | Saitama | Chiba | Gunma | Kanagawa => &[(9, 180.)],
+            Tokyo => &[(18, 137.), (9, 141.), (14, 150.), (19, 180.)],
+            Aomori | Akita | Yamagata | Iwate | Miyagi => &[(10, 180.)],
+            Hokkaido => &[(11, 141.2), (12, 143.25), (13, 180.)],
+            Okinawa => &[(16, 126.), (15, 130.), (17, 180.)],
+        }
+    }
+
+    /// Returns the zone number for a point in this prefecture.
+    ///
+    /// Most prefectures use a single zone. Hokkaido, Tokyo, Kagoshima, and Okinawa are split
+    /// between zones by municipality or island group, and for those the zone is chosen by
+    /// longitude. The longitude split is exact for the island groups but only approximate for
+    /// the municipal boundaries in Hokkaido, so prefer the official municipal assignment when it
+    /// is available.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::jgd2011::Prefecture;
+    /// assert_eq!(Prefecture::Kyoto.zone(135.7681), 6);
+    /// assert_eq!(Prefecture::Hokkaido.zone(141.3544), 12);
+    /// assert_eq!(Prefecture::Tokyo.zone(142.1918), 14);
+    /// ```
+    pub fn zone(self, longitude: f64) -> u8 {
+        let zones = self.zones();
+        zones
+            .iter()
+            .find(|&&(_, east)| longitude < east)
+            .unwrap_or(&zones[zones.len() - 1])
+            .0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn origins_project_to_zero() {
+        for (i, &(latitude, longitude)) in ORIGINS.iter().enumerate() {
+            let (x, y, _) = zone(i as u8 + 1).unwrap().forward(latitude, longitude);
+            assert!(x.abs() < 1e-6);
+            assert!(y.abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn split_prefectures() {
+        assert_eq!(Prefecture::Okinawa.zone(124.1555), 16);
+        assert_eq!(Prefecture::Okinawa.zone(127.6809), 15);
+        assert_eq!(Prefecture::Okinawa.zone(131.2333), 17);
+        assert_eq!(Prefecture::Kagoshima.zone(130.5571), 2);
+        assert_eq!(Prefecture::Kagoshima.zone(129.4937), 1);
+    }
+}
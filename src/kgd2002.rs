@@ -0,0 +1,113 @@
+//! The Korean transverse Mercator belts on KGD2002.
+//!
+//! South Korea divides the country into four belts two degrees of longitude wide, each a
+//! transverse Mercator projection on GRS80 with a scale factor of 1 and its origin at 38°N. The
+//! origin has a false easting of 200,000 m and a false northing of 600,000 m, as defined in 2010
+//! (EPSG:5185 to EPSG:5188). Cadastral and navigation data uses these belts rather than UTM.
+
+use super::datum::GRS80;
+use super::tm::TransverseMercator;
+
+/// The latitude of origin of every belt, in decimal degrees.
+pub const LATITUDE_OF_ORIGIN: f64 = 38.;
+
+/// The false easting of every belt, in meters.
+pub const FALSE_EASTING: f64 = 200000.;
+
+/// The false northing of every belt, in meters.
+pub const FALSE_NORTHING: f64 = 600000.;
+
+/// A Korean transverse Mercator belt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Belt {
+    /// The west belt, centered on 125°E, EPSG:5185.
+    West,
+    /// The central belt, centered on 127°E, EPSG:5186.
+    Central,
+    /// The east belt, centered on 129°E, EPSG:5187.
+    East,
+    /// The East Sea belt for Ulleungdo and Dokdo, centered on 131°E, EPSG:5188.
+    EastSea,
+}
+
+impl Belt {
+    /// Returns the belt covering a longitude in decimal degrees.
+    ///
+    /// Each belt covers one degree on either side of its central meridian, and points on a
+    /// boundary belong to the belt to their east. Longitudes outside of 124°E to 132°E go to
+    /// the nearest belt.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::kgd2002::Belt;
+    /// // Seoul
+    /// assert_eq!(Belt::for_longitude(126.978), Belt::Central);
+    /// // Busan
+    /// assert_eq!(Belt::for_longitude(129.075), Belt::East);
+    /// ```
+    pub fn for_longitude(longitude: f64) -> Belt {
+        if longitude < 126. {
+            Belt::West
+        } else if longitude < 128. {
+            Belt::Central
+        } else if longitude < 130. {
+            Belt::East
+        } else {
+            Belt::EastSea
+        }
+    }
+
+    /// Returns the central meridian in decimal degrees.
+    pub fn central_meridian(self) -> f64 {
+        match self {
+            Belt::West => 125.,
+            Belt::Central => 127.,
+            Belt::East => 129.,
+            Belt::EastSea => 131.,
+        }
+    }
+
+    /// Returns the projection for this belt.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::kgd2002::Belt;
+    /// let (northing, easting, _) = Belt::Central.projection().forward(38., 127.);
+    /// assert!((northing - 600000.).abs() < 1e-6);
+    /// assert!((easting - 200000.).abs() < 1e-6);
+    /// ```
+    pub fn projection(self) -> TransverseMercator {
+        TransverseMercator::new(
+            GRS80,
+            LATITUDE_OF_ORIGIN,
+            self.central_meridian(),
+            1.,
+            FALSE_EASTING,
+            FALSE_NORTHING,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn belt_boundaries() {
+        assert_eq!(Belt::for_longitude(124.5), Belt::West);
+        assert_eq!(Belt::for_longitude(126.), Belt::Central);
+        assert_eq!(Belt::for_longitude(130.), Belt::EastSea);
+        assert_eq!(Belt::for_longitude(131.8667), Belt::EastSea);
+    }
+
+    #[test]
+    fn seoul() {
+        // Seoul City Hall, about 2 km west of the central belt's meridian and 48 km south of
+        // its origin.
+        let (northing, easting, _) = Belt::Central.projection().forward(37.5663, 126.9779);
+        assert!((easting - 198048.).abs() < 1.);
+        assert!((northing - 551863.).abs() < 1.);
+    }
+}
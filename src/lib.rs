@@ -1,33 +1,456 @@
 //! Micro-library for converting from geodetic to UTM coordinates.
+//!
+//! # Features
+//!
+//! - `forward` (default): conversions from latitude and longitude to UTM.
+//! - `inverse` (default): conversions from UTM to latitude and longitude.
+//! - `mgrs` (default): Military Grid Reference System references.
+//! - `parse` (default): `FromStr` for `UtmCoordinate`.
+//! - `analysis`: accuracy comparisons between conversion algorithms over a region. Needs the
+//!   standard library.
+//! - `conformance`: differential testing between conversion backends, for downstream accuracy
+//!   regression checks. Enables `analysis`. With `generic` too, it can check a single precision
+//!   build against double precision.
+//! - `csv`: parallel conversion of the latitude and longitude columns of memory-mapped CSV
+//!   files, with `rayon` and `memmap2`. Enables `forward`. Needs the standard library.
+//! - `generic`: conversions generic over the scalar type, e.g. for automatic differentiation.
+//! - `interval`: conversions of intervals, returning rigorous enclosures of the result.
+//! - `lut`: a forward conversion with lookup table trigonometry, for a fixed run time on targets
+//!   without a floating point unit. Set `UTM_LUT_SIZE` at build time to trade table size for
+//!   accuracy.
+//! - `no_std`: build without the standard library, using `num` for floating point math. A
+//!   `no_std` build never allocates.
+//! - `alloc`: with `no_std`, bring back the APIs that allocate, such as `recovery`, `prj`, and
+//!   the MGRS arenas. They are always available with the standard library.
+//! - `h3`: conversions to and from H3 cell indices.
+//! - `s2`: conversions to and from S2 cell IDs.
+//! - `serde`: `Serialize` and `Deserialize` for `UtmCoordinate`, and, with `parse`, the
+//!   `serde_utm_string` field helper.
+//!
+//! Builds that only ever need one direction, such as firmware producing UTM from GNSS fixes,
+//! can disable the default features and enable just `forward` or `inverse` to leave out the APIs
+//! built on the other direction, and leave out `parse` and `mgrs` to drop the string handling.
+//! Zone and band lookups and the free conversion functions, such as `to_utm_wgs84` and
+//! `wsg84_utm_to_lat_lon`, are always available; the linker drops the ones that aren't called.
 #![cfg_attr(feature = "no_std", no_std)]
 
+#[cfg(all(feature = "no_std", feature = "alloc"))]
+extern crate alloc;
 #[cfg(feature = "no_std")]
 extern crate core as std;
 
 use std::f64::consts::PI;
 
-#[cfg(feature = "no_std")]
+#[cfg(any(feature = "no_std", feature = "generic"))]
 extern crate num;
+#[cfg(all(test, feature = "generic"))]
+extern crate num_dual;
+
+#[cfg(feature = "h3")]
+extern crate h3o;
+#[cfg(all(feature = "csv", not(feature = "no_std")))]
+extern crate memmap2;
+#[cfg(all(feature = "csv", not(feature = "no_std")))]
+extern crate rayon;
+
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
 
 #[cfg(feature = "no_std")]
 #[allow(unused_imports)]
 // it's not clear why this generates an unused imports, b/c tests fail w/o it
 use num::traits::float::Float;
 
+use precision::{Precision, Rounded};
+
+pub mod accuracy;
+#[cfg(all(
+    feature = "analysis",
+    feature = "forward",
+    feature = "inverse",
+    not(feature = "no_std")
+))]
+pub mod analysis;
+pub mod band;
+pub mod batch;
+pub mod bbox;
+#[cfg(feature = "inverse")]
+pub mod bridge;
+pub mod budget;
+pub mod classify;
+#[cfg(all(
+    feature = "conformance",
+    feature = "forward",
+    feature = "inverse",
+    not(feature = "no_std")
+))]
+pub mod conformance;
+pub mod converter;
+#[cfg(all(feature = "csv", not(feature = "no_std")))]
+pub mod csv;
+pub mod curve;
+pub mod datum;
+pub mod delta;
+pub mod easting;
+pub mod epsg;
+pub mod field;
+pub mod frame;
+#[cfg(all(feature = "generic", any(feature = "forward", feature = "inverse")))]
+pub mod generic;
+pub mod geodesic;
+#[cfg(feature = "h3")]
+pub mod h3;
+#[cfg(feature = "inverse")]
+pub mod heading;
+#[cfg(all(feature = "interval", any(feature = "forward", feature = "inverse")))]
+pub mod interval;
+pub mod jgd2011;
+pub mod kgd2002;
+pub mod local;
+#[cfg(all(feature = "lut", feature = "forward"))]
+pub mod lut;
+#[cfg(feature = "mgrs")]
+pub mod mgrs;
+pub mod mounting;
+#[cfg(feature = "inverse")]
+pub mod nearest;
+#[cfg(not(feature = "no_std"))]
+pub mod nmea;
+pub mod northing;
+pub mod polar;
+#[cfg(any(feature = "alloc", not(feature = "no_std")))]
+pub mod pool;
+pub mod precision;
+#[cfg(any(feature = "alloc", not(feature = "no_std")))]
+pub mod prj;
+pub mod record;
+#[cfg(all(feature = "inverse", any(feature = "alloc", not(feature = "no_std"))))]
+pub mod recovery;
+pub mod report;
+#[cfg(feature = "inverse")]
+pub mod roundtrip;
+#[cfg(feature = "s2")]
+pub mod s2;
+#[cfg(all(feature = "serde", feature = "parse"))]
+pub mod serde_utm_string;
+pub mod system;
+pub mod tm;
+pub mod trajectory;
+pub mod units;
+#[cfg(feature = "forward")]
+pub mod vote;
+pub mod workspace;
+#[cfg(all(feature = "forward", feature = "inverse"))]
+pub mod zoned;
+
+/// A reference ellipsoid, defined by its semi-major axis and flattening.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Ellipsoid {
     a: f64,
     f: f64,
 }
 
+impl Ellipsoid {
+    /// Creates an ellipsoid from its semi-major axis in meters and its flattening.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::Ellipsoid;
+    /// let clarke_1866 = Ellipsoid::new(6378206.4, 1.0 / 294.978698214);
+    /// ```
+    pub const fn new(a: f64, f: f64) -> Ellipsoid {
+        Ellipsoid { a, f }
+    }
+
+    /// Returns the semi-major axis in meters.
+    pub fn semi_major_axis(&self) -> f64 {
+        self.a
+    }
+
+    /// Returns the flattening.
+    pub fn flattening(&self) -> f64 {
+        self.f
+    }
+
+    /// Returns the length in meters of the meridian arc from the equator to a latitude in decimal
+    /// degrees, which is negative in the southern hemisphere.
+    ///
+    /// This is the series used by the UTM conversions, which is accurate to about a millimeter on
+    /// earth-like ellipsoids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::datum::GRS80;
+    /// let quarter_meridian = GRS80.meridian_arc(90.);
+    /// assert!((quarter_meridian - 10001965.729).abs() < 1e-3);
+    /// ```
+    pub fn meridian_arc(&self, latitude: f64) -> f64 {
+        self.meridian_arc_radians(latitude.to_radians())
+    }
+
+    /// Returns the footpoint latitude in decimal degrees, the latitude at which the meridian arc
+    /// from the equator is `arc` meters long.
+    ///
+    /// This is the inverse of `meridian_arc`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::datum::GRS80;
+    /// let arc = GRS80.meridian_arc(45.);
+    /// assert!((GRS80.footpoint_latitude(arc) - 45.).abs() < 1e-8);
+    /// ```
+    pub fn footpoint_latitude(&self, arc: f64) -> f64 {
+        self.footpoint_latitude_radians(arc).to_degrees()
+    }
+
+    fn meridian_arc_radians(&self, latitude: f64) -> f64 {
+        let e2 = 2.0 * self.f - self.f * self.f;
+        let term1 = 1.0 - e2 / 4.0 - (3.0 * e2 * e2) / 64.0 - (5.0 * e2 * e2 * e2) / 256.0;
+        let term2 = (3.0 * e2) / 8.0 + (3.0 * e2 * e2) / 32.0 + (45.0 * e2 * e2 * e2) / 1024.0;
+        let term3 = (15.0 * e2 * e2) / 256.0 + (45.0 * e2 * e2 * e2) / 1024.0;
+        let term4 = (35.0 * e2 * e2 * e2) / 3072.0;
+
+        self.a
+            * (term1 * latitude - term2 * (2.0 * latitude).sin() + term3 * (4.0 * latitude).sin()
+                - term4 * (6.0 * latitude).sin())
+    }
+
+    fn footpoint_latitude_radians(&self, arc: f64) -> f64 {
+        let e2 = 2.0 * self.f - self.f * self.f;
+        let e1 = (1.0 - (1.0 - e2).sqrt()) / (1.0 + (1.0 - e2).sqrt());
+        let mu =
+            arc / (self.a * (1.0 - e2 / 4.0 - 3.0 * e2 * e2 / 64.0 - 5.0 * e2 * e2 * e2 / 256.0));
+
+        let term1 = 3.0 * e1 / 2.0 - 27.0 * e1 * e1 * e1 / 32.0;
+        let term2 = 21.0 * e1 * e1 / 16.0 - 55.0 * e1 * e1 * e1 * e1 / 32.0;
+        let term3 = 151.0 * e1 * e1 * e1 / 96.0;
+        let term4 = 1097.0 * e1 * e1 * e1 * e1 / 512.0;
+
+        mu + term1 * (2.0 * mu).sin()
+            + term2 * (4.0 * mu).sin()
+            + term3 * (6.0 * mu).sin()
+            + term4 * (8.0 * mu).sin()
+    }
+}
+
 const WGS84: Ellipsoid = Ellipsoid {
     a: 6378137.0,
     f: 1.0 / 298.257222101,
 };
 
-const ZONE_LETTERS: &'static str = "CDEFGHJKLMNPQRSTUVWXX";
+const ZONE_LETTERS: &str = "CDEFGHJKLMNPQRSTUVWXX";
+
+/// A UTM coordinate, with its zone number and letter.
+///
+/// Its `Display` implementation uses the zone, easting, and northing separated by spaces, e.g.
+/// `33T 315000 5741000`, and with the `parse` feature so does `FromStr`. With the `serde` feature
+/// it serializes as a struct of its fields, or, with `parse` too, as that string with
+/// `serde_utm_string`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UtmCoordinate {
+    /// The easting in meters.
+    pub easting: f64,
+    /// The northing in meters.
+    pub northing: f64,
+    /// The zone number, between 1 and 60.
+    pub zone_num: u8,
+    /// The zone (latitude band) letter, between C and X.
+    pub zone_letter: char,
+}
+
+impl UtmCoordinate {
+    /// Creates a new UTM coordinate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::UtmCoordinate;
+    /// let capetown = UtmCoordinate::new(261878., 6243186., 34, 'H');
+    /// ```
+    pub fn new(easting: f64, northing: f64, zone_num: u8, zone_letter: char) -> UtmCoordinate {
+        UtmCoordinate {
+            easting,
+            northing,
+            zone_num,
+            zone_letter,
+        }
+    }
+
+    /// Returns this coordinate formatted with a rounding policy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::precision::{Precision, Rounding};
+    /// use utm::UtmCoordinate;
+    /// let coordinate = UtmCoordinate::new(315000.237, 5741000.5, 33, 'T');
+    /// assert_eq!(
+    ///     coordinate.rounded(Precision::CENTIMETER).to_string(),
+    ///     "33T 315000.24 5741000.50"
+    /// );
+    /// let floor = Precision::METER.with_rounding(Rounding::Floor);
+    /// assert_eq!(coordinate.rounded(floor).to_string(), "33T 315000 5741000");
+    /// ```
+    pub fn rounded(&self, precision: Precision) -> Rounded {
+        Rounded::new(*self, precision)
+    }
+
+    #[cfg(feature = "inverse")]
+    /// Returns this coordinate formatted with as few decimals as keep its latitude and longitude
+    /// within `tolerance` decimal degrees once read back, as picked by `Precision::round_trip`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::UtmCoordinate;
+    /// let coordinate = UtmCoordinate::new(315012.237, 5741034.5, 33, 'T');
+    /// assert_eq!(
+    ///     coordinate.rounded_within(1e-5).unwrap().to_string(),
+    ///     "33T 315012 5741035"
+    /// );
+    /// ```
+    pub fn rounded_within(&self, tolerance: f64) -> Result<Rounded, WSG84ToLatLonError> {
+        Ok(self.rounded(Precision::round_trip(self, tolerance)?))
+    }
+
+    #[cfg(feature = "inverse")]
+    /// Converts this coordinate to a latitude and longitude, like `wsg84_utm_to_lat_lon`.
+    pub fn to_lat_lon(&self) -> Result<(f64, f64), WSG84ToLatLonError> {
+        wsg84_utm_to_lat_lon(self.easting, self.northing, self.zone_num, self.zone_letter)
+    }
+}
+
+impl std::fmt::Display for UtmCoordinate {
+    /// Formats the coordinate as `33T 315000 5741000`. A precision, e.g. `{:.1}`, applies to the
+    /// easting and northing. Use `rounded` to format with a `precision::Precision` instead.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}{} ", self.zone_num, self.zone_letter)?;
+        match f.precision() {
+            Some(precision) => write!(
+                f,
+                "{:.*} {:.*}",
+                precision, self.easting, precision, self.northing
+            ),
+            None => write!(f, "{} {}", self.easting, self.northing),
+        }
+    }
+}
+
+#[cfg(feature = "parse")]
+impl std::str::FromStr for UtmCoordinate {
+    type Err = ParseUtmCoordinateError;
+
+    /// Parses a coordinate like `33T 315000 5741000`.
+    ///
+    /// The zone, easting, and northing may be separated by any whitespace, and the zone letter
+    /// may be lowercase. The zone number and letter are checked, but the easting and northing
+    /// are not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::UtmCoordinate;
+    /// let coordinate: UtmCoordinate = "33T 315000 5741000".parse().unwrap();
+    /// assert_eq!(coordinate, UtmCoordinate::new(315000., 5741000., 33, 'T'));
+    /// assert_eq!(coordinate.to_string(), "33T 315000 5741000");
+    /// ```
+    fn from_str(s: &str) -> Result<UtmCoordinate, ParseUtmCoordinateError> {
+        let mut parts = s.split_whitespace();
+        let (zone, easting, northing) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(zone), Some(easting), Some(northing)) => (zone, easting, northing),
+            _ => return Err(ParseUtmCoordinateError::Format),
+        };
+        if parts.next().is_some() {
+            return Err(ParseUtmCoordinateError::Format);
+        }
+        let letter_start = zone
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or(ParseUtmCoordinateError::Format)?;
+        let (zone_num, zone_letter) = zone.split_at(letter_start);
+        let zone_num: u8 = zone_num
+            .parse()
+            .map_err(|_| ParseUtmCoordinateError::ZoneNumOutOfRange)?;
+        if !(1..=60).contains(&zone_num) {
+            return Err(ParseUtmCoordinateError::ZoneNumOutOfRange);
+        }
+        let mut letters = zone_letter.chars();
+        let zone_letter = match (letters.next(), letters.next()) {
+            (Some(letter), None) => letter.to_ascii_uppercase(),
+            _ => return Err(ParseUtmCoordinateError::Format),
+        };
+        if !ZONE_LETTERS.contains(zone_letter) {
+            return Err(ParseUtmCoordinateError::ZoneLetterOutOfRange);
+        }
+        let easting = easting
+            .parse()
+            .map_err(|_| ParseUtmCoordinateError::InvalidEasting)?;
+        let northing = northing
+            .parse()
+            .map_err(|_| ParseUtmCoordinateError::InvalidNorthing)?;
+        Ok(UtmCoordinate::new(easting, northing, zone_num, zone_letter))
+    }
+}
+
+#[cfg(feature = "parse")]
+/// Error type for parsing a `UtmCoordinate` from a string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseUtmCoordinateError {
+    /// The string isn't a zone, easting, and northing separated by whitespace.
+    Format,
+    /// The zone number is not between 1 and 60.
+    ZoneNumOutOfRange,
+    /// The zone letter is not one of C to X, excluding I and O.
+    ZoneLetterOutOfRange,
+    /// The easting is not a number.
+    InvalidEasting,
+    /// The northing is not a number.
+    InvalidNorthing,
+}
+
+#[cfg(feature = "parse")]
+impl std::fmt::Display for ParseUtmCoordinateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParseUtmCoordinateError::Format => {
+                write!(
+                    f,
+                    "Expected a zone, easting, and northing, like 33T 315000 5741000"
+                )
+            }
+            ParseUtmCoordinateError::ZoneNumOutOfRange => {
+                write!(f, "Zone num out of range, must be between 1 and 60")
+            }
+            ParseUtmCoordinateError::ZoneLetterOutOfRange => {
+                write!(f, "Zone letter out of range, must be between C and X")
+            }
+            ParseUtmCoordinateError::InvalidEasting => write!(f, "Easting is not a number"),
+            ParseUtmCoordinateError::InvalidNorthing => write!(f, "Northing is not a number"),
+        }
+    }
+}
+
+#[cfg(all(feature = "parse", not(feature = "no_std")))]
+impl std::error::Error for ParseUtmCoordinateError {}
+
+/// A hemisphere, which decides whether UTM northings carry the 10,000,000 m false northing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Hemisphere {
+    /// Northings are measured from the equator.
+    North,
+    /// Northings are measured from 10,000,000 m south of the equator.
+    South,
+}
 
 /// Converts a latitude and longitude in decimal degrees to UTM coordinates using the WGS84 ellipsoid.
 ///
+/// Uses the global converter instead, if one was set with `converter::set_global`.
+///
 /// # Examples
 ///
 /// ```
@@ -35,9 +458,39 @@ const ZONE_LETTERS: &'static str = "CDEFGHJKLMNPQRSTUVWXX";
 /// let (northing, easting, meridian_convergence) = to_utm_wgs84(40.62, -123.45, 10);
 /// ```
 pub fn to_utm_wgs84(latitude: f64, longitude: f64, zone: u8) -> (f64, f64, f64) {
+    #[cfg(all(feature = "forward", not(feature = "no_std")))]
+    {
+        if let Some(workspace) = converter::global_workspace() {
+            return global_forward(workspace, latitude, longitude, zone);
+        }
+    }
+    series_to_utm_wgs84(latitude, longitude, zone)
+}
+
+/// Like `to_utm_wgs84`, but always with the classic series on WGS84.
+pub(crate) fn series_to_utm_wgs84(latitude: f64, longitude: f64, zone: u8) -> (f64, f64, f64) {
     let latitude = latitude * PI / 180.0;
     let longitude = longitude * PI / 180.0;
-    radians_to_utm_wgs84(latitude, longitude, zone)
+    radians_to_utm_series(latitude, longitude, zone)
+}
+
+#[cfg(all(feature = "forward", not(feature = "no_std")))]
+/// Converts a latitude and longitude in decimal degrees with the global converter, keeping the
+/// series' convention of a false northing only south of the equator.
+fn global_forward(
+    workspace: &workspace::Workspace,
+    latitude: f64,
+    longitude: f64,
+    zone: u8,
+) -> (f64, f64, f64) {
+    let northern = latitude >= 0.;
+    match workspace.projection(zone, northern) {
+        Some(projection) => projection.forward(latitude, longitude),
+        None => workspace
+            .converter()
+            .projection(zone, northern)
+            .forward(latitude, longitude),
+    }
 }
 
 /// Converts a latitude and longitude in decimal degrees (using the latitude and longitude to compute the zone) to UTM coordinates using the WGS84 ellipsoid.
@@ -58,6 +511,8 @@ pub fn to_utm_wgs84_no_zone(latitude: f64, longitude: f64) -> (f64, f64, f64) {
 
 /// Converts a latitude and longitude in radians to UTM coordinates using the WGS84 ellipsoid.
 ///
+/// Uses the global converter instead, if one was set with `converter::set_global`.
+///
 /// # Examples
 ///
 /// ```
@@ -68,6 +523,21 @@ pub fn to_utm_wgs84_no_zone(latitude: f64, longitude: f64) -> (f64, f64, f64) {
 /// let (northing, easting, meridian_convergence) = radians_to_utm_wgs84(latitude, longitude, 10);
 /// ```
 pub fn radians_to_utm_wgs84(latitude: f64, longitude: f64, zone: u8) -> (f64, f64, f64) {
+    #[cfg(all(feature = "forward", not(feature = "no_std")))]
+    {
+        if let Some(workspace) = converter::global_workspace() {
+            return global_forward(
+                workspace,
+                latitude.to_degrees(),
+                longitude.to_degrees(),
+                zone,
+            );
+        }
+    }
+    radians_to_utm_series(latitude, longitude, zone)
+}
+
+fn radians_to_utm_series(latitude: f64, longitude: f64, zone: u8) -> (f64, f64, f64) {
     let ellipsoid = WGS84;
     let long_origin = zone as f64 * 6.0 - 183.0;
     let e2 = 2.0 * ellipsoid.f - ellipsoid.f * ellipsoid.f;
@@ -78,14 +548,7 @@ pub fn radians_to_utm_wgs84(latitude: f64, longitude: f64, zone: u8) -> (f64, f6
     let c = ep2 * latitude.cos() * latitude.cos();
     let a = latitude.cos() * (longitude - (long_origin * PI / 180.0));
 
-    let term1 = 1.0 - e2 / 4.0 - (3.0 * e2 * e2) / 64.0 - (5.0 * e2 * e2 * e2) / 256.0;
-    let term2 = (3.0 * e2) / 8.0 + (3.0 * e2 * e2) / 32.0 + (45.0 * e2 * e2 * e2) / 1024.0;
-    let term3 = (15.0 * e2 * e2) / 256.0 + (45.0 * e2 * e2 * e2) / 1024.0;
-    let term4 = (35.0 * e2 * e2 * e2) / 3072.0;
-
-    let m = ellipsoid.a
-        * (term1 * latitude - term2 * (2.0 * latitude).sin() + term3 * (4.0 * latitude).sin()
-            - term4 * (6.0 * latitude).sin());
+    let m = ellipsoid.meridian_arc_radians(latitude);
 
     let x1 = ((1.0 - t + c) * a * a * a) / 6.0;
     let x2 = ((5.0 - 18.0 * t + t * t + 72.0 * c - 58.0 * ep2) * a * a * a * a * a) / 120.0;
@@ -96,25 +559,20 @@ pub fn radians_to_utm_wgs84(latitude: f64, longitude: f64, zone: u8) -> (f64, f6
     let y3 = (a * a) / 2.0 + y1 + y2;
     let y = 0.9996 * (m + n * latitude.tan() * y3);
 
-    // Southern hemisphere coords add a factor
-    let northing_mod = if latitude > 0.0 { 0.0 } else { 10000000.0 };
+    // Southern hemisphere coords add a factor. The equator is in band N, so it is northern.
+    let northing_mod = if latitude >= 0.0 { 0.0 } else { 10000000.0 };
 
     let northing = y + northing_mod;
     let easting = x + 500000.0;
 
-    let meridian_convergence = meridian_convergence(northing, easting, WGS84);
+    // The footpoint is found from the distance to the equator, before the false northing.
+    let meridian_convergence = meridian_convergence(y, easting, WGS84);
     (northing, easting, meridian_convergence)
 }
 
 fn meridian_convergence(northing: f64, easting: f64, ellipsoid: Ellipsoid) -> f64 {
     let e2: f64 = 2.0 * ellipsoid.f - ellipsoid.f * ellipsoid.f;
-    let e1 = (1.0 - (1.0 - e2).sqrt()) / (1.0 + (1.0 - e2).sqrt());
-    let mu_const =
-        ellipsoid.a * (1.0 - e2 / 4.0 - 3.0 * e2 * e2 / 64.0 - 5.0 * e2 * e2 * e2 / 256.0);
-
-    let np = northing / 0.9996;
-    let mu = np / mu_const;
-    let foot_lat = footprint_latitude(e1, mu);
+    let foot_lat = ellipsoid.footpoint_latitude_radians(northing / 0.9996);
 
     let ep = (easting - 500000.0) / 0.9996;
     let n = ellipsoid.a / (1.0 - e2 * foot_lat.sin() * foot_lat.sin()).sqrt();
@@ -129,41 +587,31 @@ fn meridian_convergence(northing: f64, easting: f64, ellipsoid: Ellipsoid) -> f6
     conv1 + conv2
 }
 
-fn footprint_latitude(e1: f64, mu: f64) -> f64 {
-    let term1 = 3.0 * e1 / 2.0 - 27.0 * e1 * e1 * e1 / 32.0;
-    let term2 = 21.0 * e1 * e1 / 16.0 - 55.0 * e1 * e1 * e1 * e1 / 32.0;
-    let term3 = 151.0 * e1 * e1 * e1 / 96.0;
-    let term4 = 1097.0 * e1 * e1 * e1 * e1 / 512.0;
-
-    mu + term1 * (2.0 * mu).sin()
-        + term2 * (4.0 * mu).sin()
-        + term3 * (6.0 * mu).sin()
-        + term4 * (8.0 * mu).sin()
-}
-
 const K0: f64 = 0.9996;
 const E: f64 = 0.00669438;
 
-#[cfg(feature = "std")]
-impl std::error::Error for WSG84ToLatLonError {
-    fn description(&self) -> &str {
+impl std::fmt::Display for WSG84ToLatLonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             WSG84ToLatLonError::EastingOutOfRange => {
-                "Easting out of range, must be between 100000 and 999999"
+                write!(f, "Easting out of range, must be between 100000 and 999999")
             }
             WSG84ToLatLonError::NorthingOutOfRange => {
-                "Northing out of range, must be between 0 and 10000000"
+                write!(f, "Northing out of range, must be between 0 and 10000000")
             }
             WSG84ToLatLonError::ZoneNumOutOfRange => {
-                "Zone num out of range, must be between 1 and 60"
+                write!(f, "Zone num out of range, must be between 1 and 60")
             }
             WSG84ToLatLonError::ZoneLetterOutOfRange => {
-                "Zone letter out of range, must be between C and X"
+                write!(f, "Zone letter out of range, must be between C and X")
             }
         }
     }
 }
 
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for WSG84ToLatLonError {}
+
 #[derive(Debug, PartialEq)]
 /// Error type for the wsg84_utm_to_lat_lon function.
 pub enum WSG84ToLatLonError {
@@ -177,6 +625,8 @@ pub enum WSG84ToLatLonError {
 /// zone_num can be obtain by calling lat_lon_to_zone_number
 /// zone_letter can be obtain by calling lat_to_zone_letter
 ///
+/// Uses the global converter instead, if one was set with `converter::set_global`.
+///
 /// # Examples
 ///
 /// ```
@@ -200,19 +650,78 @@ pub fn wsg84_utm_to_lat_lon(
     zone_num: u8,
     zone_letter: char,
 ) -> Result<(f64, f64), WSG84ToLatLonError> {
-    if easting < 100000. || 1000000. <= easting {
+    #[cfg(all(feature = "inverse", not(feature = "no_std")))]
+    {
+        // The valid eastings and northings depend on the global converter's constants, so only
+        // the zone is checked, like `Converter::to_lat_lon`
+        if let Some(workspace) = converter::global_workspace() {
+            return workspace.to_lat_lon(&UtmCoordinate::new(
+                easting,
+                northing,
+                zone_num,
+                zone_letter,
+            ));
+        }
+    }
+    check_utm(easting, northing, zone_num, zone_letter)?;
+    Ok(utm_to_lat_lon_unchecked(
+        easting,
+        northing,
+        zone_num,
+        zone_letter >= 'N',
+    ))
+}
+
+#[cfg(feature = "inverse")]
+/// Like `wsg84_utm_to_lat_lon`, but always with the classic series on WGS84.
+pub(crate) fn series_wsg84_utm_to_lat_lon(
+    easting: f64,
+    northing: f64,
+    zone_num: u8,
+    zone_letter: char,
+) -> Result<(f64, f64), WSG84ToLatLonError> {
+    check_utm(easting, northing, zone_num, zone_letter)?;
+    Ok(utm_to_lat_lon_unchecked(
+        easting,
+        northing,
+        zone_num,
+        zone_letter >= 'N',
+    ))
+}
+
+/// Checks that a UTM coordinate is within the ranges `wsg84_utm_to_lat_lon` accepts.
+fn check_utm(
+    easting: f64,
+    northing: f64,
+    zone_num: u8,
+    zone_letter: char,
+) -> Result<(), WSG84ToLatLonError> {
+    if !(100000. ..1000000.).contains(&easting) {
         return Err(WSG84ToLatLonError::EastingOutOfRange);
     }
-    if northing < 0. || northing > 10000000. {
+    if !(0. ..=10000000.).contains(&northing) {
         return Err(WSG84ToLatLonError::NorthingOutOfRange);
     }
-    if zone_num < 1 || zone_num > 60 {
+    if !(1..=60).contains(&zone_num) {
         return Err(WSG84ToLatLonError::ZoneNumOutOfRange);
     }
-    if zone_letter < 'C' || zone_letter > 'X' {
+    if !('C'..='X').contains(&zone_letter) {
         return Err(WSG84ToLatLonError::ZoneLetterOutOfRange);
     }
+    Ok(())
+}
 
+/// Converts a UTM coordinate to a latitude and longitude without any range checks.
+///
+/// Easting and northing are allowed to fall outside of the zone, which is what
+/// re-zoning needs.
+#[inline]
+fn utm_to_lat_lon_unchecked(
+    easting: f64,
+    northing: f64,
+    zone_num: u8,
+    northern: bool,
+) -> (f64, f64) {
     let ellipsoid = WGS84;
 
     let e2 = E.powi(2);
@@ -234,13 +743,8 @@ pub fn wsg84_utm_to_lat_lon(
     let p5: f64 = 1097. / 512. * _e4;
 
     let x = easting - 500000_f64;
-    let mut y = northing;
-
-    let northern = zone_letter >= 'N';
-
-    if !northern {
-        y -= 1e7;
-    }
+    // Selected without a branch, so batch loops over mixed hemispheres stay branch free.
+    let y = northing - 1e7 * f64::from(u8::from(!northern));
 
     let m = y / K0;
     let mu = m / (ellipsoid.a * m1);
@@ -284,14 +788,44 @@ pub fn wsg84_utm_to_lat_lon(
         + d5 / 120. * (5. - 2. * c + 28. * p_tan2 - 3. * c2 + 8. * e_p2 + 24. * p_tan4))
         / p_cos;
 
-    Ok((
+    (
         latitude / PI * 180.,
         longitude / PI * 180. + (f64::from(zone_num) - 1.) * 6. - 180. + 3.,
-    ))
+    )
+}
+
+/// How to assign a point that lies exactly on a zone or band boundary.
+///
+/// Zones and bands are half-open: each one includes its western and southern edge and excludes
+/// its eastern and northern edge, so a point on a boundary belongs to the zone to its east or
+/// the band to its north. That is the `Upper` rule, which `lat_lon_to_zone_number` and
+/// `lat_to_zone_letter` always use. The comparisons are done against the exact boundary values,
+/// so the same input always lands in the same zone and band regardless of rounding.
+///
+/// The meridian at ±180° is treated as the western edge of zone 1 and the eastern edge of zone
+/// 60. The outer edges of the band table, 80°S and 84°N, are not ties: they belong to bands C
+/// and X respectively, as does the northern edge of the Svalbard zones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TieBreak {
+    /// Assign the point to the zone to its west or the band to its south.
+    Lower,
+    /// Assign the point to the zone to its east or the band to its north.
+    #[default]
+    Upper,
+    /// Assign the point to the zone whose central meridian is nearest, falling back to `Upper`
+    /// when both are equally far away. This only makes a difference on the irregular Norway and
+    /// Svalbard boundaries, since a regular boundary is halfway between two central meridians.
+    /// Band edges have no central meridian, so this behaves like `Upper` for bands, and only
+    /// the zones that exist in the upper band are candidates.
+    NearestCentralMeridian,
 }
 
 /// Convert a latitude to the UTM zone letter.
 ///
+/// Points on a band edge belong to the band to their north, except for 84°N, which is the
+/// northern edge of band X. See `TieBreak`. Use `band::lat_to_band` to tell polar latitudes
+/// apart from invalid ones.
+///
 /// # Examples
 ///
 /// ```
@@ -301,18 +835,117 @@ pub fn wsg84_utm_to_lat_lon(
 /// assert_eq!(lat_to_zone_letter(50.77535), Some('U'));
 /// ```
 pub fn lat_to_zone_letter(latitude: f64) -> Option<char> {
-    if -80. <= latitude && latitude <= 84. {
-        let (_, c) = ZONE_LETTERS
-            .char_indices()
-            .nth(((latitude + 80.) / 8.).floor() as usize)
-            .unwrap();
-        return Some(c);
+    if (-80. ..=84.).contains(&latitude) {
+        return Some(char::from(ZONE_LETTERS.as_bytes()[band_index(latitude)]));
+    }
+    None
+}
+
+/// Convert a latitude to the UTM zone letter, resolving band edges with a `TieBreak` rule.
+///
+/// # Examples
+///
+/// ```
+/// use utm::{lat_to_zone_letter_with, TieBreak};
+/// assert_eq!(lat_to_zone_letter_with(0., TieBreak::Upper), Some('N'));
+/// assert_eq!(lat_to_zone_letter_with(0., TieBreak::Lower), Some('M'));
+/// ```
+pub fn lat_to_zone_letter_with(latitude: f64, tie_break: TieBreak) -> Option<char> {
+    match tie_break {
+        TieBreak::Lower if latitude > -80. => lat_to_zone_letter(next_down(latitude)),
+        _ => lat_to_zone_letter(latitude),
     }
-    return None;
+}
+
+/// A grid zone whose width differs from the regular six degrees.
+///
+/// Zone 32V is widened to cover southwestern Norway, at the expense of 31V. In band X, around
+/// Svalbard, zones 31, 33, 35, and 37 are widened to cover zones 32, 34, and 36 entirely, so
+/// 32X, 34X, and 36X don't exist.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ZoneException {
+    /// The zone number.
+    pub zone_num: u8,
+    /// The zone (latitude band) letter.
+    pub zone_letter: char,
+    /// The southern edge, in decimal degrees.
+    pub min_latitude: f64,
+    /// The northern edge, in decimal degrees.
+    pub max_latitude: f64,
+    /// The western edge, in decimal degrees.
+    pub min_longitude: f64,
+    /// The eastern edge, in decimal degrees.
+    pub max_longitude: f64,
+}
+
+impl ZoneException {
+    /// Returns true if a latitude and longitude are in this grid zone.
+    ///
+    /// Like the regular zones, the southern and western edges are included and the northern and
+    /// eastern edges are not, except for 84°N, the northern edge of band X.
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        let north = latitude < self.max_latitude || latitude == 84. && self.max_latitude == 84.;
+        latitude >= self.min_latitude
+            && north
+            && (self.min_longitude..self.max_longitude).contains(&longitude)
+    }
+}
+
+/// The irregular grid zones, which `lat_lon_to_zone_number` applies before the regular
+/// six-degree zones.
+pub const ZONE_EXCEPTIONS: [ZoneException; 6] = [
+    zone_exception(31, 'V', 56., 64., 0., 3.),
+    zone_exception(32, 'V', 56., 64., 3., 12.),
+    zone_exception(31, 'X', 72., 84., 0., 9.),
+    zone_exception(33, 'X', 72., 84., 9., 21.),
+    zone_exception(35, 'X', 72., 84., 21., 33.),
+    zone_exception(37, 'X', 72., 84., 33., 42.),
+];
+
+const fn zone_exception(
+    zone_num: u8,
+    zone_letter: char,
+    min_latitude: f64,
+    max_latitude: f64,
+    min_longitude: f64,
+    max_longitude: f64,
+) -> ZoneException {
+    ZoneException {
+        zone_num,
+        zone_letter,
+        min_latitude,
+        max_latitude,
+        min_longitude,
+        max_longitude,
+    }
+}
+
+/// Returns true if a zone number and letter name a grid zone that exists.
+///
+/// Every zone from 1 to 60 exists in every band from C to X, except 32X, 34X, and 36X, which
+/// are covered by the widened Svalbard zones. See `ZONE_EXCEPTIONS`.
+///
+/// # Examples
+///
+/// ```
+/// use utm::is_standard_zone;
+/// assert!(is_standard_zone(33, 'X'));
+/// assert!(!is_standard_zone(34, 'X'));
+/// assert!(is_standard_zone(34, 'W'));
+/// assert!(!is_standard_zone(61, 'N'));
+/// assert!(!is_standard_zone(10, 'I'));
+/// ```
+pub fn is_standard_zone(zone_num: u8, zone_letter: char) -> bool {
+    (1..=60).contains(&zone_num)
+        && ZONE_LETTERS.contains(zone_letter)
+        && !(zone_letter == 'X' && [32, 34, 36].contains(&zone_num))
 }
 
 /// Convert a latitude and longitude to the UTM zone number.
 ///
+/// Points on a zone boundary belong to the zone to their east (or, on the Norway and Svalbard
+/// boundaries of latitude, to their north). See `TieBreak`.
+///
 /// # Examples
 ///
 /// ```
@@ -322,26 +955,100 @@ pub fn lat_to_zone_letter(latitude: f64) -> Option<char> {
 /// assert_eq!(lat_lon_to_zone_number(50.77535, 6.08389), 32);
 /// ```
 pub fn lat_lon_to_zone_number(latitude: f64, longitude: f64) -> u8 {
-    if 56. <= latitude && latitude < 64. && 3. <= longitude && longitude < 12. {
-        return 32;
+    // Every exception is north of 56°N, so most points never look at the table.
+    if latitude >= 56. {
+        if let Some(exception) = ZONE_EXCEPTIONS
+            .iter()
+            .find(|exception| exception.contains(latitude, longitude))
+        {
+            return exception.zone_num;
+        }
     }
 
-    if 72. <= latitude && latitude <= 84. && longitude >= 0. {
-        if longitude < 9. {
-            return 31;
-        }
-        if longitude < 21. {
-            return 33;
-        }
-        if longitude < 33. {
-            return 35;
-        }
-        if longitude < 42. {
-            return 37;
+    let longitude = if longitude == 180. { -180. } else { longitude };
+    let mut index = (((longitude + 180.) / 6.).floor() as i32).clamp(0, 59);
+    if index > 0 && longitude < -180. + 6. * index as f64 {
+        index -= 1;
+    } else if index < 59 && longitude >= -180. + 6. * (index + 1) as f64 {
+        index += 1;
+    }
+    index as u8 + 1
+}
+
+/// Convert a latitude and longitude to the UTM zone number, resolving boundaries with a
+/// `TieBreak` rule.
+///
+/// # Examples
+///
+/// ```
+/// use utm::{lat_lon_to_zone_number_with, TieBreak};
+/// // On the boundary between zones 31 and 32
+/// assert_eq!(lat_lon_to_zone_number_with(45., 6., TieBreak::Lower), 31);
+/// assert_eq!(lat_lon_to_zone_number_with(45., 6., TieBreak::Upper), 32);
+/// // On the western edge of the widened zone 32V, which is zone 31's central meridian
+/// assert_eq!(lat_lon_to_zone_number_with(60., 3., TieBreak::Upper), 32);
+/// assert_eq!(lat_lon_to_zone_number_with(60., 3., TieBreak::NearestCentralMeridian), 31);
+/// ```
+pub fn lat_lon_to_zone_number_with(latitude: f64, longitude: f64, tie_break: TieBreak) -> u8 {
+    let upper = lat_lon_to_zone_number(latitude, longitude);
+    let lower = if longitude == 180. || longitude == -180. {
+        60
+    } else {
+        lat_lon_to_zone_number(next_down(latitude), next_down(longitude))
+    };
+    match tie_break {
+        TieBreak::Lower => lower,
+        TieBreak::Upper => upper,
+        TieBreak::NearestCentralMeridian => {
+            // The band is the upper one, so only compare against the zone to the west in it.
+            // Going south as well could pick a zone that doesn't exist in this band, e.g. 32X.
+            let west = if longitude == 180. || longitude == -180. {
+                60
+            } else {
+                lat_lon_to_zone_number(latitude, next_down(longitude))
+            };
+            let distance = |zone: u8| {
+                let central_meridian = f64::from(zone) * 6. - 183.;
+                ((longitude - central_meridian + 540.) % 360. - 180.).abs()
+            };
+            if distance(west) < distance(upper) {
+                west
+            } else {
+                upper
+            }
         }
     }
+}
 
-    return (((longitude + 180.) / 6.).floor() + 1.) as u8;
+/// Returns the index into `ZONE_LETTERS` of the band containing a latitude between 80°S and
+/// 84°N, comparing against the exact band edges.
+fn band_index(latitude: f64) -> usize {
+    let mut index = (((latitude + 80.) / 8.).floor() as i32).clamp(0, 19);
+    if index > 0 && latitude < -80. + 8. * index as f64 {
+        index -= 1;
+    } else if index < 19 && latitude >= -80. + 8. * (index + 1) as f64 {
+        index += 1;
+    }
+    index as usize
+}
+
+/// Returns the largest float less than `x`.
+fn next_down(x: f64) -> f64 {
+    if x.is_nan() || x == f64::NEG_INFINITY {
+        x
+    } else if x == 0. {
+        -f64::from_bits(1)
+    } else if x > 0. {
+        f64::from_bits(x.to_bits() - 1)
+    } else {
+        f64::from_bits(x.to_bits() + 1)
+    }
+}
+
+/// Returns the smallest float greater than `x`.
+#[cfg(all(feature = "interval", any(feature = "forward", feature = "inverse")))]
+fn next_up(x: f64) -> f64 {
+    -next_down(-x)
 }
 
 #[cfg(test)]
@@ -368,10 +1075,28 @@ mod tests {
         dbg!(northing, easting, meridian_convergence);
         assert!((635900.94 - easting).abs() < 1e-2);
         assert!((7366197.82 - northing).abs() < 1e-2);
-        assert!((-0.0486 - meridian_convergence).abs() < 1e-4);
+        // East of the central meridian in the south, grid north is east of true north.
+        assert!((0.0094 - meridian_convergence).abs() < 1e-4);
     }
 
     #[test]
+    fn southern_convergence_matches_kruger() {
+        for &(latitude, longitude, zone) in &[
+            (-23.809749, 148.334111, 55),
+            (-33.92487, 18.42406, 34),
+            (-0.5, -119.2, 11),
+            (-79.5, 100.7, 47),
+        ] {
+            let (_, _, series) = to_utm_wgs84(latitude, longitude, zone);
+            let (_, _, kruger) = converter::Converter::new()
+                .projection(zone, false)
+                .forward(latitude, longitude);
+            assert!((series - kruger).abs() < 1e-6, "{} {}", series, kruger);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_to_lat_lon() {
         let (expected_lat, expected_lon) = (-41.28646, 174.77624);
 
@@ -424,6 +1149,145 @@ mod tests {
         assert_eq!(meridian_convergence, meridian_convergence_2);
     }
 
+    #[test]
+    fn test_band_edges() {
+        for (i, letter) in ZONE_LETTERS.chars().take(20).enumerate() {
+            let south = -80. + 8. * i as f64;
+            assert_eq!(lat_to_zone_letter(south), Some(letter));
+            assert_eq!(
+                lat_to_zone_letter_with(south, TieBreak::Upper),
+                Some(letter)
+            );
+            if i > 0 {
+                let below = ZONE_LETTERS.chars().nth(i - 1);
+                assert_eq!(lat_to_zone_letter(next_down(south)), below);
+                assert_eq!(lat_to_zone_letter_with(south, TieBreak::Lower), below);
+            }
+        }
+        assert_eq!(lat_to_zone_letter(84.), Some('X'));
+        assert_eq!(lat_to_zone_letter_with(84., TieBreak::Lower), Some('X'));
+        assert_eq!(lat_to_zone_letter_with(-80., TieBreak::Lower), Some('C'));
+        assert_eq!(lat_to_zone_letter(next_down(-80.)), None);
+    }
+
+    #[test]
+    fn test_zone_edges() {
+        for zone in 1..=60u8 {
+            let west = -186. + 6. * f64::from(zone);
+            assert_eq!(lat_lon_to_zone_number(0., west), zone);
+            assert_eq!(lat_lon_to_zone_number(0., next_down(west + 6.)), zone);
+            let lower = if zone == 1 { 60 } else { zone - 1 };
+            assert_eq!(
+                lat_lon_to_zone_number_with(0., west, TieBreak::Lower),
+                lower
+            );
+            assert_eq!(
+                lat_lon_to_zone_number_with(0., west, TieBreak::NearestCentralMeridian),
+                zone
+            );
+        }
+        assert_eq!(lat_lon_to_zone_number(0., 180.), 1);
+        assert_eq!(lat_lon_to_zone_number_with(0., 180., TieBreak::Lower), 60);
+        assert_eq!(lat_lon_to_zone_number_with(56., 5., TieBreak::Upper), 32);
+        assert_eq!(lat_lon_to_zone_number_with(56., 5., TieBreak::Lower), 31);
+        assert_eq!(lat_lon_to_zone_number_with(72., 10., TieBreak::Upper), 33);
+        // On the southern edge of band X, where 32X doesn't exist
+        assert_eq!(
+            lat_lon_to_zone_number_with(72., 10., TieBreak::NearestCentralMeridian),
+            33
+        );
+        // Equally far from the central meridians of 31 and 33
+        assert_eq!(
+            lat_lon_to_zone_number_with(72., 9., TieBreak::NearestCentralMeridian),
+            33
+        );
+        assert_eq!(
+            lat_lon_to_zone_number_with(56., 5., TieBreak::NearestCentralMeridian),
+            32
+        );
+        // Every zone the rule picks exists in the band it picks
+        for i in 0..=120 {
+            let longitude = -180. + 3. * f64::from(i);
+            for &latitude in &[56., 64., 72., 84.] {
+                let rule = TieBreak::NearestCentralMeridian;
+                let zone = lat_lon_to_zone_number_with(latitude, longitude, rule);
+                let band = lat_to_zone_letter_with(latitude, rule).unwrap();
+                assert!(
+                    is_standard_zone(zone, band),
+                    "{} {} {}{}",
+                    latitude,
+                    longitude,
+                    zone,
+                    band
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_zone_exceptions() {
+        for exception in ZONE_EXCEPTIONS.iter() {
+            let latitude = (exception.min_latitude + exception.max_latitude) / 2.;
+            let mut longitude = exception.min_longitude;
+            while longitude < exception.max_longitude {
+                assert_eq!(
+                    lat_lon_to_zone_number(latitude, longitude),
+                    exception.zone_num
+                );
+                longitude += 0.25;
+            }
+            assert_eq!(lat_to_zone_letter(latitude), Some(exception.zone_letter));
+            assert!(is_standard_zone(exception.zone_num, exception.zone_letter));
+        }
+        // Every point in band X lands in a zone that exists.
+        for i in 0..=360 {
+            let longitude = -180. + f64::from(i);
+            for &latitude in &[72., 78., 84.] {
+                let zone = lat_lon_to_zone_number(latitude, longitude);
+                assert!(is_standard_zone(zone, 'X'), "{} {}", latitude, longitude);
+            }
+        }
+    }
+
+    #[test]
+    fn test_meridian_arc() {
+        assert_eq!(WGS84.meridian_arc(0.), 0.);
+        assert_eq!(WGS84.meridian_arc(-30.), -WGS84.meridian_arc(30.));
+        for latitude in -90..=90 {
+            let latitude = f64::from(latitude);
+            let arc = WGS84.meridian_arc(latitude);
+            assert!(is_close(WGS84.footpoint_latitude(arc), latitude, 1e-8));
+        }
+    }
+
+    #[cfg(feature = "parse")]
+    #[test]
+    fn test_parse_utm_coordinate() {
+        let coordinate: UtmCoordinate = "  34h\t261878.5   6243186 ".parse().unwrap();
+        assert_eq!(coordinate, UtmCoordinate::new(261878.5, 6243186., 34, 'H'));
+        assert_eq!(format!("{:.2}", coordinate), "34H 261878.50 6243186.00");
+        assert_eq!(
+            "34H 261878".parse::<UtmCoordinate>(),
+            Err(ParseUtmCoordinateError::Format)
+        );
+        assert_eq!(
+            "261878 6243186 34H".parse::<UtmCoordinate>(),
+            Err(ParseUtmCoordinateError::Format)
+        );
+        assert_eq!(
+            "0H 261878 6243186".parse::<UtmCoordinate>(),
+            Err(ParseUtmCoordinateError::ZoneNumOutOfRange)
+        );
+        assert_eq!(
+            "34I 261878 6243186".parse::<UtmCoordinate>(),
+            Err(ParseUtmCoordinateError::ZoneLetterOutOfRange)
+        );
+        assert_eq!(
+            "34H 261878 north".parse::<UtmCoordinate>(),
+            Err(ParseUtmCoordinateError::InvalidNorthing)
+        );
+    }
+
     fn is_close(a: f64, b: f64, epsilon: f64) -> bool {
         (a - b).abs() < epsilon
     }
@@ -0,0 +1,152 @@
+//! Small local coordinates relative to a site origin in a UTM zone.
+//!
+//! UTM northings run to seven digits before the decimal point, which is all the precision an
+//! `f32` has, so storing them in GPU buffers or game engine transforms rounds them to a meter
+//! or worse. Subtracting a site origin first leaves coordinates small enough to keep their
+//! precision: an `f32` resolves about a millimeter within 16 km of the origin and about a
+//! centimeter within 160 km.
+//!
+//! ```
+//! use utm::local::LocalOrigin;
+//! use utm::UtmCoordinate;
+//! let site = LocalOrigin::new(UtmCoordinate::new(261000., 6243000., 34, 'H'));
+//! let point = UtmCoordinate::new(261878.123, 6243186.456, 34, 'H');
+//! let (x, y) = site.to_local(&point).unwrap();
+//! assert_eq!((x, y), (878.123, 186.456));
+//! let back = site.to_utm(x, y);
+//! assert!((back.easting - point.easting).abs() < 1e-4);
+//! ```
+
+use super::converter::Converter;
+use super::tm::TransverseMercator;
+use super::UtmCoordinate;
+
+/// A site origin in a UTM zone that coordinates are expressed relative to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LocalOrigin {
+    origin: UtmCoordinate,
+    projection: TransverseMercator,
+}
+
+impl LocalOrigin {
+    /// Creates a local frame with its origin at a UTM coordinate.
+    pub fn new(origin: UtmCoordinate) -> LocalOrigin {
+        LocalOrigin {
+            origin,
+            projection: Converter::new().projection(origin.zone_num, origin.zone_letter >= 'N'),
+        }
+    }
+
+    /// Returns the origin.
+    pub fn origin(&self) -> UtmCoordinate {
+        self.origin
+    }
+
+    /// Returns a coordinate relative to the origin, in meters, or `None` if it is in another
+    /// zone or hemisphere.
+    pub fn to_local(&self, coordinate: &UtmCoordinate) -> Option<(f32, f32)> {
+        self.to_local_f64(coordinate)
+            .map(|(x, y)| (x as f32, y as f32))
+    }
+
+    /// Returns a coordinate relative to the origin in full precision, or `None` if it is in
+    /// another zone or hemisphere.
+    pub fn to_local_f64(&self, coordinate: &UtmCoordinate) -> Option<(f64, f64)> {
+        if coordinate.zone_num != self.origin.zone_num
+            || (coordinate.zone_letter >= 'N') != (self.origin.zone_letter >= 'N')
+        {
+            return None;
+        }
+        Some((
+            coordinate.easting - self.origin.easting,
+            coordinate.northing - self.origin.northing,
+        ))
+    }
+
+    /// Returns the UTM coordinate of a local position.
+    ///
+    /// The coordinate has the origin's zone letter, even if the position is in another band.
+    pub fn to_utm(&self, x: f32, y: f32) -> UtmCoordinate {
+        self.to_utm_f64(f64::from(x), f64::from(y))
+    }
+
+    /// Returns the UTM coordinate of a local position in full precision.
+    ///
+    /// The coordinate has the origin's zone letter, even if the position is in another band.
+    pub fn to_utm_f64(&self, x: f64, y: f64) -> UtmCoordinate {
+        UtmCoordinate {
+            easting: self.origin.easting + x,
+            northing: self.origin.northing + y,
+            ..self.origin
+        }
+    }
+
+    /// Projects a latitude and longitude in decimal degrees into the origin's zone and returns
+    /// it relative to the origin.
+    ///
+    /// The point does not need to be inside the zone, so a site that straddles a zone boundary
+    /// stays in one frame. Projection uses Krüger's series through `converter::Converter`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::local::LocalOrigin;
+    /// use utm::UtmCoordinate;
+    /// let site = LocalOrigin::new(UtmCoordinate::new(261000., 6243000., 34, 'H'));
+    /// let (x, y) = site.from_lat_lon(-33.92487, 18.42406);
+    /// assert!((x - 878.).abs() < 1. && (y - 186.).abs() < 1.);
+    /// ```
+    pub fn from_lat_lon(&self, latitude: f64, longitude: f64) -> (f32, f32) {
+        let (northing, easting, _) = self.projection.forward(latitude, longitude);
+        (
+            (easting - self.origin.easting) as f32,
+            (northing - self.origin.northing) as f32,
+        )
+    }
+
+    #[cfg(feature = "inverse")]
+    /// Converts a local position to a latitude and longitude in decimal degrees.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::local::LocalOrigin;
+    /// use utm::UtmCoordinate;
+    /// let site = LocalOrigin::new(UtmCoordinate::new(261000., 6243000., 34, 'H'));
+    /// let (latitude, longitude) = site.to_lat_lon(878., 186.);
+    /// assert!((latitude + 33.92487).abs() < 1e-4 && (longitude - 18.42406).abs() < 1e-4);
+    /// ```
+    pub fn to_lat_lon(&self, x: f32, y: f32) -> (f64, f64) {
+        let coordinate = self.to_utm(x, y);
+        self.projection
+            .inverse(coordinate.easting, coordinate.northing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precision_is_kept() {
+        let site = LocalOrigin::new(UtmCoordinate::new(500000., 5000000., 32, 'T'));
+        let point = UtmCoordinate::new(512345.678, 4987654.321, 32, 'T');
+        let (x, y) = site.to_local(&point).unwrap();
+        let back = site.to_utm(x, y);
+        assert!((back.easting - point.easting).abs() < 1e-3);
+        assert!((back.northing - point.northing).abs() < 1e-3);
+        // The same northing as an f32 on its own is off by decimeters.
+        assert!((f64::from(point.northing as f32) - point.northing).abs() > 0.05);
+    }
+
+    #[test]
+    fn other_zones() {
+        let site = LocalOrigin::new(UtmCoordinate::new(500000., 5000000., 32, 'T'));
+        let point = UtmCoordinate::new(500000., 5000000., 33, 'T');
+        assert_eq!(site.to_local(&point), None);
+        let point = UtmCoordinate::new(500000., 5000000., 32, 'G');
+        assert_eq!(site.to_local(&point), None);
+        let point = UtmCoordinate::new(500000., 5000000., 32, 'U');
+        assert_eq!(site.to_local(&point), Some((0., 0.)));
+    }
+}
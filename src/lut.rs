@@ -0,0 +1,267 @@
+//! A forward conversion with lookup table trigonometry, behind the `lut` feature.
+//!
+//! Microcontrollers without a floating point unit spend most of a conversion in `sin`, `cos`,
+//! and `tan`, and how long those take depends on the argument. `to_utm_wgs84` here is the same
+//! series as the crate's `to_utm_wgs84`, with every trigonometric function read from a table
+//! of sines over a quarter turn and interpolated, so each call does the same fixed amount of
+//! work.
+//!
+//! The table has `SIZE` intervals, 256 unless the `UTM_LUT_SIZE` environment variable is set to
+//! another number between 16 and 16384 when the crate is built. It is computed at compile time
+//! and takes `8 * (SIZE + 1)` bytes. Sines are interpolated with cubic Hermite polynomials,
+//! using the table's own cosines as slopes, so the error is within `MAX_ERROR`, about
+//! `(π / 2 / SIZE)^4 / 384`. With the default table that is under 4e-12, which keeps eastings
+//! and northings within a millimeter of `to_utm_wgs84`.
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use std::f64::consts::FRAC_PI_2;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use super::generic;
+
+/// The number of table intervals used unless `UTM_LUT_SIZE` is set.
+const DEFAULT_SIZE: usize = 256;
+
+/// The number of intervals the quarter turn is divided into.
+pub const SIZE: usize = parse_size(option_env!("UTM_LUT_SIZE"));
+
+/// The width of a table interval in radians.
+const STEP: f64 = FRAC_PI_2 / SIZE as f64;
+
+/// The largest error of `sin` and `cos` for arguments within four turns of zero, which covers
+/// every argument of the forward series.
+///
+/// This is the bound on the interpolation error plus the rounding of the argument reduction.
+pub const MAX_ERROR: f64 = STEP * STEP * STEP * STEP / 384. + 16. * f64::EPSILON;
+
+/// The sines of `SIZE + 1` evenly spaced angles from zero to a quarter turn.
+static TABLE: [f64; SIZE + 1] = table();
+
+/// Parses the table size from the build environment.
+const fn parse_size(value: Option<&str>) -> usize {
+    let bytes = match value {
+        Some(value) => value.as_bytes(),
+        None => return DEFAULT_SIZE,
+    };
+    let mut size = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        assert!(bytes[i].is_ascii_digit(), "UTM_LUT_SIZE must be a number");
+        size = size * 10 + (bytes[i] - b'0') as usize;
+        i += 1;
+    }
+    assert!(
+        size >= 16 && size <= 16384,
+        "UTM_LUT_SIZE must be between 16 and 16384"
+    );
+    size
+}
+
+const fn table() -> [f64; SIZE + 1] {
+    let mut table = [0.; SIZE + 1];
+    let mut i = 0;
+    while i <= SIZE {
+        table[i] = taylor_sin(i as f64 * STEP);
+        i += 1;
+    }
+    table
+}
+
+/// Returns the sine of an angle between zero and a quarter turn from its Taylor series, for
+/// building the table at compile time.
+const fn taylor_sin(x: f64) -> f64 {
+    let x2 = x * x;
+    let mut term = x;
+    let mut sum = x;
+    let mut n = 1;
+    while n < 15 {
+        term = -term * x2 / ((2 * n) * (2 * n + 1)) as f64;
+        sum += term;
+        n += 1;
+    }
+    sum
+}
+
+/// Returns the sine of an angle in radians from the table.
+///
+/// # Examples
+///
+/// ```
+/// use utm::lut::{sin, MAX_ERROR};
+/// for &x in &[0., 0.3, 1., 2.5, -4., 10.] {
+///     assert!((sin(x) - f64::sin(x)).abs() <= MAX_ERROR);
+/// }
+/// ```
+pub fn sin(x: f64) -> f64 {
+    let quarters = (x / FRAC_PI_2).floor();
+    // Rounding can put this just outside of the quarter turn, where the end intervals
+    // extrapolate smoothly
+    let r = x - quarters * FRAC_PI_2;
+    match (quarters as i64).rem_euclid(4) {
+        0 => quarter_sin(r),
+        1 => quarter_sin(FRAC_PI_2 - r),
+        2 => -quarter_sin(r),
+        _ => -quarter_sin(FRAC_PI_2 - r),
+    }
+}
+
+/// Returns the cosine of an angle in radians from the table.
+///
+/// # Examples
+///
+/// ```
+/// use utm::lut::{cos, MAX_ERROR};
+/// assert!((cos(0.7) - f64::cos(0.7)).abs() <= MAX_ERROR);
+/// ```
+pub fn cos(x: f64) -> f64 {
+    sin(x + FRAC_PI_2)
+}
+
+/// Interpolates the sine of an angle between zero and a quarter turn.
+fn quarter_sin(x: f64) -> f64 {
+    let t = x / STEP;
+    // Truncation is the floor here, and saturates to zero for NaN
+    let i = (t as usize).min(SIZE - 1);
+    let u = t - i as f64;
+    let (p0, p1) = (TABLE[i], TABLE[i + 1]);
+    // The derivative of the sine is the cosine, the sine of the complement
+    let (m0, m1) = (STEP * TABLE[SIZE - i], STEP * TABLE[SIZE - i - 1]);
+    let u2 = u * u;
+    let u3 = u2 * u;
+    (2. * u3 - 3. * u2 + 1.) * p0
+        + (u3 - 2. * u2 + u) * m0
+        + (3. * u2 - 2. * u3) * p1
+        + (u3 - u2) * m1
+}
+
+/// A scalar that takes its trigonometry from the table.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Lut(f64);
+
+impl Add for Lut {
+    type Output = Lut;
+    fn add(self, other: Lut) -> Lut {
+        Lut(self.0 + other.0)
+    }
+}
+
+impl Sub for Lut {
+    type Output = Lut;
+    fn sub(self, other: Lut) -> Lut {
+        Lut(self.0 - other.0)
+    }
+}
+
+impl Mul for Lut {
+    type Output = Lut;
+    fn mul(self, other: Lut) -> Lut {
+        Lut(self.0 * other.0)
+    }
+}
+
+impl Div for Lut {
+    type Output = Lut;
+    fn div(self, other: Lut) -> Lut {
+        Lut(self.0 / other.0)
+    }
+}
+
+impl Neg for Lut {
+    type Output = Lut;
+    fn neg(self) -> Lut {
+        Lut(-self.0)
+    }
+}
+
+impl generic::Scalar for Lut {
+    fn from_f64(value: f64) -> Lut {
+        Lut(value)
+    }
+
+    fn sin(self) -> Lut {
+        Lut(sin(self.0))
+    }
+
+    fn cos(self) -> Lut {
+        Lut(cos(self.0))
+    }
+
+    fn tan(self) -> Lut {
+        Lut(sin(self.0) / cos(self.0))
+    }
+
+    fn sqrt(self) -> Lut {
+        Lut(generic::Scalar::sqrt(self.0))
+    }
+
+    fn powi(self, n: i32) -> Lut {
+        Lut(generic::Scalar::powi(self.0, n))
+    }
+}
+
+/// Converts a latitude and longitude in decimal degrees to UTM coordinates using the WGS84
+/// ellipsoid, like `to_utm_wgs84`, with lookup table trigonometry.
+///
+/// # Examples
+///
+/// ```
+/// use utm::lut;
+/// let (northing, easting, _) = lut::to_utm_wgs84(40.62, -123.45, 10);
+/// let (expected_northing, expected_easting, _) = utm::to_utm_wgs84(40.62, -123.45, 10);
+/// assert!((easting - expected_easting).abs() < 1e-3);
+/// assert!((northing - expected_northing).abs() < 1e-3);
+/// ```
+pub fn to_utm_wgs84(latitude: f64, longitude: f64, zone: u8) -> (f64, f64, f64) {
+    let (northing, easting, convergence) = generic::forward(
+        Lut(latitude.to_radians()),
+        Lut(longitude.to_radians()),
+        zone,
+        latitude >= 0.,
+    );
+    (northing.0, easting.0, convergence.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use series_to_utm_wgs84;
+
+    #[test]
+    fn trig_is_within_the_bound() {
+        let mut x = -25.;
+        while x < 25. {
+            assert!((sin(x) - x.sin()).abs() <= MAX_ERROR, "{}", x);
+            assert!((cos(x) - x.cos()).abs() <= MAX_ERROR, "{}", x);
+            x += 0.00123;
+        }
+        assert_eq!(TABLE[0], 0.);
+        assert!((TABLE[SIZE] - 1.).abs() <= f64::EPSILON);
+        assert!(sin(f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn matches_the_series() {
+        // A millimeter with the default table, and about the trig error at the earth's radius
+        // with coarser ones
+        let tolerance = (6378137. * MAX_ERROR).max(1e-3);
+        for latitude in -80..=84 {
+            for &offset in &[-3.5, -1., 0., 0.5, 3.] {
+                let latitude = f64::from(latitude);
+                let longitude = 15. + offset;
+                let (n1, e1, c1) = to_utm_wgs84(latitude, longitude, 33);
+                let (n2, e2, c2) = series_to_utm_wgs84(latitude, longitude, 33);
+                assert!((n1 - n2).abs() < tolerance, "{} {}", latitude, n1 - n2);
+                assert!((e1 - e2).abs() < tolerance, "{} {}", latitude, e1 - e2);
+                assert!(
+                    (c1 - c2).abs() < (10. * MAX_ERROR).max(1e-9),
+                    "{} {}",
+                    latitude,
+                    c1 - c2
+                );
+            }
+        }
+    }
+}
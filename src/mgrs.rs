@@ -0,0 +1,1026 @@
+//! Military Grid Reference System (MGRS) references, behind the `mgrs` feature.
+//!
+//! An MGRS reference is a UTM zone and band, a two letter 100 km square identifier, and an
+//! easting and northing within that square, e.g. `18SUJ2348306479`. Only the UTM part of MGRS
+//! is supported, not the polar UPS areas.
+//!
+//! Two lettering schemes are in use for the 100 km squares. They share the column letters but
+//! start the row letters at different points. The `AA` scheme is used with WGS84, GRS80, and
+//! International 1924 grids, while the older `AL` scheme is used with grids on the Clarke 1866,
+//! Clarke 1880, and Bessel 1841 ellipsoids. Decoding a reference with the wrong scheme puts it
+//! 1,000 km or more away from where it belongs, so pass the scheme that matches the reference's
+//! datum, e.g. with `Lettering::for_datum`.
+//!
+//! MGRS is defined on standard UTM, so conversions to and from latitudes and longitudes use the
+//! classic series even if a global converter was set with `converter::set_global`.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(all(feature = "forward", feature = "inverse"))]
+use super::converter::Converter;
+use super::datum::{Datum, BESSEL_1841, CLARKE_1866, CLARKE_1880};
+#[cfg(all(feature = "forward", feature = "inverse"))]
+use super::easting::easting_range;
+use super::precision::Precision;
+#[cfg(feature = "inverse")]
+use super::series_wsg84_utm_to_lat_lon;
+#[cfg(feature = "forward")]
+use super::{lat_lon_to_zone_number, lat_to_zone_letter, series_to_utm_wgs84};
+use super::{Ellipsoid, UtmCoordinate, WSG84ToLatLonError, ZONE_LETTERS};
+#[cfg(all(feature = "no_std", feature = "alloc"))]
+use alloc::{string::String, vec::Vec};
+
+/// The letters used for 100 km square columns, three sets of eight.
+const COLUMN_LETTERS: &[u8; 24] = b"ABCDEFGHJKLMNPQRSTUVWXYZ";
+
+/// The letters used for 100 km square rows, repeating every 2,000 km.
+const ROW_LETTERS: &[u8; 20] = b"ABCDEFGHJKLMNPQRSTUV";
+
+/// The northing in meters below which no point in each band lies, from C to X.
+const MIN_NORTHINGS: [f64; 20] = [
+    1100000., 2000000., 2800000., 3700000., 4600000., 5500000., 6400000., 7300000., 8200000.,
+    9100000., 0., 800000., 1700000., 2600000., 3500000., 4400000., 5300000., 6200000., 7000000.,
+    7900000.,
+];
+
+/// A scheme for lettering the 100 km squares.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum Lettering {
+    /// Rows start at A in odd zones and F in even zones. Used with WGS84 and most modern grids.
+    #[default]
+    AA,
+    /// Rows start at L in odd zones and R in even zones. Used with grids on the Clarke 1866,
+    /// Clarke 1880, and Bessel 1841 ellipsoids.
+    AL,
+}
+
+impl Lettering {
+    /// Returns the lettering scheme used with grids on an ellipsoid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::datum::{CLARKE_1866, GRS80};
+    /// use utm::mgrs::Lettering;
+    /// assert_eq!(Lettering::for_ellipsoid(GRS80), Lettering::AA);
+    /// assert_eq!(Lettering::for_ellipsoid(CLARKE_1866), Lettering::AL);
+    /// ```
+    pub fn for_ellipsoid(ellipsoid: Ellipsoid) -> Lettering {
+        if ellipsoid == CLARKE_1866 || ellipsoid == CLARKE_1880 || ellipsoid == BESSEL_1841 {
+            Lettering::AL
+        } else {
+            Lettering::AA
+        }
+    }
+
+    /// Returns the lettering scheme used with grids on a datum.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::datum::{Nad27, Wgs84};
+    /// use utm::mgrs::Lettering;
+    /// assert_eq!(Lettering::for_datum::<Wgs84>(), Lettering::AA);
+    /// assert_eq!(Lettering::for_datum::<Nad27>(), Lettering::AL);
+    /// ```
+    pub fn for_datum<D: Datum>() -> Lettering {
+        Lettering::for_ellipsoid(D::ELLIPSOID)
+    }
+
+    /// Returns the index into `ROW_LETTERS` of the row letter at the equator.
+    fn row_offset(self, zone_num: u8) -> usize {
+        let even = usize::from(zone_num.is_multiple_of(2));
+        match self {
+            Lettering::AA => 5 * even,
+            Lettering::AL => 10 + 5 * even,
+        }
+    }
+}
+
+/// Errors that can occur when encoding, decoding, or parsing an MGRS reference.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The string isn't a zone, band, square, and an even number of digits.
+    Format,
+    /// The precision is more than five digits.
+    PrecisionOutOfRange,
+    /// The reference has a different precision than its type requires.
+    PrecisionMismatch,
+    /// The zone number is not between 1 and 60.
+    ZoneNumOutOfRange,
+    /// The zone letter is not one of C to X, excluding I and O.
+    ZoneLetterOutOfRange,
+    /// The 100 km square letters are not used in this zone.
+    SquareOutOfRange,
+    /// The easting is outside of the 100 km squares.
+    EastingOutOfRange,
+    /// The northing is not between 0 and 10,000,000 meters.
+    NorthingOutOfRange,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Format => write!(f, "Expected an MGRS reference, like 18SUJ2348306479"),
+            Error::PrecisionOutOfRange => write!(f, "Precision must be between 0 and 5 digits"),
+            Error::PrecisionMismatch => write!(f, "Reference has a different precision"),
+            Error::ZoneNumOutOfRange => {
+                write!(f, "Zone num out of range, must be between 1 and 60")
+            }
+            Error::ZoneLetterOutOfRange => {
+                write!(f, "Zone letter out of range, must be between C and X")
+            }
+            Error::SquareOutOfRange => write!(f, "100 km square is not used in this zone"),
+            Error::EastingOutOfRange => {
+                write!(f, "Easting out of range, must be between 100000 and 899999")
+            }
+            Error::NorthingOutOfRange => {
+                write!(f, "Northing out of range, must be between 0 and 10000000")
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for Error {}
+
+impl From<WSG84ToLatLonError> for Error {
+    fn from(err: WSG84ToLatLonError) -> Error {
+        match err {
+            WSG84ToLatLonError::EastingOutOfRange => Error::EastingOutOfRange,
+            WSG84ToLatLonError::NorthingOutOfRange => Error::NorthingOutOfRange,
+            WSG84ToLatLonError::ZoneNumOutOfRange => Error::ZoneNumOutOfRange,
+            WSG84ToLatLonError::ZoneLetterOutOfRange => Error::ZoneLetterOutOfRange,
+        }
+    }
+}
+
+/// An MGRS reference.
+///
+/// The easting and northing within the square are truncated to the precision, so a reference
+/// names the southwest corner of a cell that is `10^(5 - precision)` meters on a side.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Mgrs {
+    /// The zone number, between 1 and 60.
+    pub zone_num: u8,
+    /// The zone (latitude band) letter, between C and X.
+    pub zone_letter: char,
+    /// The 100 km square column letter.
+    pub column: char,
+    /// The 100 km square row letter.
+    pub row: char,
+    /// The easting within the 100 km square, in meters.
+    pub easting: u32,
+    /// The northing within the 100 km square, in meters.
+    pub northing: u32,
+    /// The number of digits each of the easting and northing is written with, between 0 and 5.
+    pub precision: u8,
+}
+
+impl Mgrs {
+    /// Creates the MGRS reference of a UTM coordinate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::mgrs::{Lettering, Mgrs};
+    /// use utm::UtmCoordinate;
+    /// let coordinate = UtmCoordinate::new(323483.7, 4306479.2, 18, 'S');
+    /// let mgrs = Mgrs::from_utm(&coordinate, 5, Lettering::AA).unwrap();
+    /// assert_eq!(mgrs.to_string(), "18SUJ2348306479");
+    /// let mgrs = Mgrs::from_utm(&coordinate, 3, Lettering::AL).unwrap();
+    /// assert_eq!(mgrs.to_string(), "18SUU234064");
+    /// ```
+    pub fn from_utm(
+        coordinate: &UtmCoordinate,
+        precision: u8,
+        lettering: Lettering,
+    ) -> Result<Mgrs, Error> {
+        if precision > 5 {
+            return Err(Error::PrecisionOutOfRange);
+        }
+        if !(1..=60).contains(&coordinate.zone_num) {
+            return Err(Error::ZoneNumOutOfRange);
+        }
+        if !ZONE_LETTERS.contains(coordinate.zone_letter) {
+            return Err(Error::ZoneLetterOutOfRange);
+        }
+        if !(100000. ..900000.).contains(&coordinate.easting) {
+            return Err(Error::EastingOutOfRange);
+        }
+        if !(0. ..=10000000.).contains(&coordinate.northing) {
+            return Err(Error::NorthingOutOfRange);
+        }
+        let easting = coordinate.easting as u32;
+        let northing = coordinate.northing as u32;
+        let set = usize::from((coordinate.zone_num - 1) % 3);
+        let column = COLUMN_LETTERS[set * 8 + easting as usize / 100000 - 1];
+        let row_index = northing as usize / 100000 + lettering.row_offset(coordinate.zone_num);
+        let row = ROW_LETTERS[row_index % 20];
+        let cell = 10u32.pow(5 - u32::from(precision));
+        Ok(Mgrs {
+            zone_num: coordinate.zone_num,
+            zone_letter: coordinate.zone_letter,
+            column: char::from(column),
+            row: char::from(row),
+            easting: easting % 100000 / cell * cell,
+            northing: northing % 100000 / cell * cell,
+            precision,
+        })
+    }
+
+    /// Creates the MGRS reference of a UTM coordinate, with the number of digits and the
+    /// rounding taken from a `Precision`.
+    ///
+    /// With `Rounding::Floor` this is the same as `from_utm` with `precision.mgrs_digits()`.
+    /// With `Rounding::Nearest` the reference names the cell whose southwest corner is nearest,
+    /// which may be in the next 100 km square.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::mgrs::{Lettering, Mgrs};
+    /// use utm::precision::{Precision, Rounding};
+    /// use utm::UtmCoordinate;
+    /// let coordinate = UtmCoordinate::new(323483.7, 4306479.2, 18, 'S');
+    /// let nearest = Precision::new(-2, Rounding::Nearest);
+    /// let mgrs = Mgrs::from_utm_with(&coordinate, nearest, Lettering::AA).unwrap();
+    /// assert_eq!(mgrs.to_string(), "18SUJ235065");
+    /// let floor = nearest.with_rounding(Rounding::Floor);
+    /// let mgrs = Mgrs::from_utm_with(&coordinate, floor, Lettering::AA).unwrap();
+    /// assert_eq!(mgrs.to_string(), "18SUJ234064");
+    /// ```
+    pub fn from_utm_with(
+        coordinate: &UtmCoordinate,
+        precision: Precision,
+        lettering: Lettering,
+    ) -> Result<Mgrs, Error> {
+        let digits = precision.mgrs_digits();
+        let cell = Precision::new(digits as i8 - 5, precision.rounding());
+        let quantized = UtmCoordinate::new(
+            cell.quantize(coordinate.easting),
+            cell.quantize(coordinate.northing),
+            coordinate.zone_num,
+            coordinate.zone_letter,
+        );
+        Mgrs::from_utm(&quantized, digits, lettering)
+    }
+
+    /// Returns the UTM coordinate of the southwest corner of this reference's cell.
+    ///
+    /// The row letters repeat every 2,000 km, so the band is used to pick the right repetition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::mgrs::{Lettering, Mgrs};
+    /// let mgrs: Mgrs = "18SUJ2348306479".parse().unwrap();
+    /// let coordinate = mgrs.to_utm(Lettering::AA).unwrap();
+    /// assert_eq!(coordinate.easting, 323483.);
+    /// assert_eq!(coordinate.northing, 4306479.);
+    /// ```
+    pub fn to_utm(&self, lettering: Lettering) -> Result<UtmCoordinate, Error> {
+        if self.precision > 5 {
+            return Err(Error::PrecisionOutOfRange);
+        }
+        if !(1..=60).contains(&self.zone_num) {
+            return Err(Error::ZoneNumOutOfRange);
+        }
+        let band = ZONE_LETTERS
+            .find(self.zone_letter)
+            .ok_or(Error::ZoneLetterOutOfRange)?;
+        if self.easting >= 100000 || self.northing >= 100000 {
+            return Err(Error::Format);
+        }
+        let set = usize::from((self.zone_num - 1) % 3);
+        let column = COLUMN_LETTERS[set * 8..set * 8 + 8]
+            .iter()
+            .position(|&c| char::from(c) == self.column)
+            .ok_or(Error::SquareOutOfRange)?;
+        let row = ROW_LETTERS
+            .iter()
+            .position(|&c| char::from(c) == self.row)
+            .ok_or(Error::SquareOutOfRange)?;
+        let row = (row + 20 - lettering.row_offset(self.zone_num)) % 20;
+        let mut northing = (row * 100000) as f64 + f64::from(self.northing);
+        while northing < MIN_NORTHINGS[band] {
+            northing += 2000000.;
+        }
+        Ok(UtmCoordinate::new(
+            ((column + 1) * 100000) as f64 + f64::from(self.easting),
+            northing,
+            self.zone_num,
+            self.zone_letter,
+        ))
+    }
+
+    #[cfg(feature = "forward")]
+    /// Creates the MGRS reference of a WGS84 latitude and longitude in decimal degrees, using the
+    /// `AA` lettering scheme.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::mgrs::Mgrs;
+    /// // The Washington Monument
+    /// let mgrs = Mgrs::from_lat_lon(38.88949, -77.03525, 3).unwrap();
+    /// assert_eq!(mgrs.to_string(), "18SUJ234064");
+    /// ```
+    pub fn from_lat_lon(latitude: f64, longitude: f64, precision: u8) -> Result<Mgrs, Error> {
+        let zone_letter = lat_to_zone_letter(latitude).ok_or(Error::ZoneLetterOutOfRange)?;
+        let zone_num = lat_lon_to_zone_number(latitude, longitude);
+        let (northing, easting, _) = series_to_utm_wgs84(latitude, longitude, zone_num);
+        let coordinate = UtmCoordinate::new(easting, northing, zone_num, zone_letter);
+        Mgrs::from_utm(&coordinate, precision, Lettering::AA)
+    }
+
+    #[cfg(feature = "inverse")]
+    /// Returns the WGS84 latitude and longitude in decimal degrees of the southwest corner of
+    /// this reference's cell, using the `AA` lettering scheme.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::mgrs::Mgrs;
+    /// let mgrs: Mgrs = "18SUJ2348306479".parse().unwrap();
+    /// let (latitude, longitude) = mgrs.to_lat_lon().unwrap();
+    /// assert!((latitude - 38.88949).abs() < 1e-4);
+    /// assert!((longitude - -77.03525).abs() < 1e-4);
+    /// ```
+    pub fn to_lat_lon(&self) -> Result<(f64, f64), Error> {
+        let coordinate = self.to_utm(Lettering::AA)?;
+        series_wsg84_utm_to_lat_lon(
+            coordinate.easting,
+            coordinate.northing,
+            coordinate.zone_num,
+            coordinate.zone_letter,
+        )
+        .map_err(Error::from)
+    }
+
+    #[cfg(all(feature = "forward", feature = "inverse"))]
+    /// Returns the eight cells around this reference's cell, at the same precision, clockwise
+    /// from the north: north, northeast, east, southeast, south, southwest, west, and northwest.
+    ///
+    /// Each neighbor is the cell just across the shared edge or corner, so the 100 km square
+    /// letters roll over, and across zone and band boundaries the neighbor is named in its own
+    /// zone and band. Cells of neighboring zones don't line up, so there the same cell can be
+    /// the neighbor in two directions, and a cell cut by the zone boundary ends at the boundary.
+    /// A neighbor is `None` beyond the UTM bands. Zone and band boundaries are located on WGS84.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::mgrs::{Lettering, Mgrs};
+    /// let mgrs: Mgrs = "18SUJ999064".parse().unwrap();
+    /// let neighbors = mgrs.neighbors(Lettering::AA).unwrap();
+    /// assert_eq!(neighbors[0].unwrap().to_string(), "18SUJ999065");
+    /// // The next 100 km square to the east
+    /// assert_eq!(neighbors[2].unwrap().to_string(), "18SVJ000064");
+    /// ```
+    pub fn neighbors(&self, lettering: Lettering) -> Result<[Option<Mgrs>; 8], Error> {
+        const DIRECTIONS: [(f64, f64); 8] = [
+            (0., 1.),
+            (1., 1.),
+            (1., 0.),
+            (1., -1.),
+            (0., -1.),
+            (-1., -1.),
+            (-1., 0.),
+            (-1., 1.),
+        ];
+        let corner = self.to_utm(lettering)?;
+        let converter = Converter::new();
+        let projection = converter.projection(self.zone_num, self.zone_letter >= 'N');
+        let size = f64::from(10u32.pow(5 - u32::from(self.precision)));
+        // Probe a hundredth of a cell across each edge or corner.
+        let margin = size / 100.;
+        let mut neighbors = [None; 8];
+        for (neighbor, &(east, north)) in neighbors.iter_mut().zip(DIRECTIONS.iter()) {
+            let northing = corner.northing + size / 2. + north * (size / 2. + margin);
+            // Cells cut by the zone boundary end at the boundary, not at their full width.
+            let (west, east_edge) = match easting_range(northing, self.zone_num, self.zone_letter) {
+                Ok((min, max)) if corner.easting.max(min) < (corner.easting + size).min(max) => {
+                    (corner.easting.max(min), (corner.easting + size).min(max))
+                }
+                _ => (corner.easting, corner.easting + size),
+            };
+            let easting = if east < 0. {
+                west - margin
+            } else if east > 0. {
+                east_edge + margin
+            } else {
+                (west + east_edge) / 2.
+            };
+            let (latitude, longitude) = projection.inverse(easting, northing);
+            *neighbor = converter
+                .to_utm(latitude, longitude)
+                .and_then(|coordinate| Mgrs::from_utm(&coordinate, self.precision, lettering).ok());
+        }
+        Ok(neighbors)
+    }
+
+    #[cfg(all(feature = "forward", feature = "inverse"))]
+    /// Returns true if another reference is one of this reference's `neighbors`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::mgrs::{Lettering, Mgrs};
+    /// let mgrs: Mgrs = "18SUJ234064".parse().unwrap();
+    /// assert!(mgrs.is_adjacent(&"18SUJ233063".parse().unwrap(), Lettering::AA).unwrap());
+    /// assert!(!mgrs.is_adjacent(&mgrs, Lettering::AA).unwrap());
+    /// ```
+    pub fn is_adjacent(&self, other: &Mgrs, lettering: Lettering) -> Result<bool, Error> {
+        Ok(self
+            .neighbors(lettering)?
+            .iter()
+            .any(|neighbor| neighbor.as_ref() == Some(other)))
+    }
+}
+
+impl fmt::Display for Mgrs {
+    /// Formats the reference without spaces, e.g. `18SUJ2348306479`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}{}",
+            self.zone_num, self.zone_letter, self.column, self.row
+        )?;
+        if self.precision > 0 {
+            let cell = 10u32.pow(5 - u32::from(self.precision.min(5)));
+            let width = usize::from(self.precision);
+            write!(
+                f,
+                "{:0width$}{:0width$}",
+                self.easting / cell,
+                self.northing / cell,
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Mgrs {
+    type Err = Error;
+
+    /// Parses a reference like `18SUJ2348306479`.
+    ///
+    /// Whitespace is ignored, letters may be lowercase, and the zone number may have a leading
+    /// zero. The zone and square letters are checked when the reference is converted, since
+    /// which squares exist depends on the zone and the lettering scheme.
+    fn from_str(s: &str) -> Result<Mgrs, Error> {
+        let mut chars = s
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_ascii_uppercase())
+            .peekable();
+        let mut zone_num = 0u8;
+        let mut zone_digits = 0;
+        while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+            zone_num = zone_num.saturating_mul(10).saturating_add(digit as u8);
+            zone_digits += 1;
+            chars.next();
+        }
+        if !(1..=2).contains(&zone_digits) {
+            return Err(Error::Format);
+        }
+        if !(1..=60).contains(&zone_num) {
+            return Err(Error::ZoneNumOutOfRange);
+        }
+        let mut letter = || {
+            chars
+                .next()
+                .filter(|c| c.is_ascii_alphabetic())
+                .ok_or(Error::Format)
+        };
+        let zone_letter = letter()?;
+        let column = letter()?;
+        let row = letter()?;
+        if !ZONE_LETTERS.contains(zone_letter) {
+            return Err(Error::ZoneLetterOutOfRange);
+        }
+        let mut digits = [0u32; 10];
+        let mut count = 0;
+        for c in chars {
+            let digit = c.to_digit(10).ok_or(Error::Format)?;
+            if count == digits.len() {
+                return Err(Error::PrecisionOutOfRange);
+            }
+            digits[count] = digit;
+            count += 1;
+        }
+        if count % 2 != 0 {
+            return Err(Error::Format);
+        }
+        let precision = count / 2;
+        let value = |digits: &[u32]| {
+            digits.iter().fold(0, |value, digit| value * 10 + digit)
+                * 10u32.pow(5 - precision as u32)
+        };
+        Ok(Mgrs {
+            zone_num,
+            zone_letter,
+            column,
+            row,
+            easting: value(&digits[..precision]),
+            northing: value(&digits[precision..count]),
+            precision: precision as u8,
+        })
+    }
+}
+
+/// An MGRS reference with its precision in its type.
+///
+/// `DIGITS` is the number of digits each of the easting and northing is written with, between 0
+/// and 5, so a `FixedMgrs<5>` names a 1 m cell and a `FixedMgrs<3>` a 100 m cell. APIs that
+/// take one can't be handed the other by accident. Going to a coarser precision is explicit,
+/// with `coarsen`, and going to a finer one isn't possible, since the digits aren't known.
+/// Using more than five digits is a compile error.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use utm::mgrs::{FixedMgrs, Lettering, Mgrs};
+/// use utm::UtmCoordinate;
+/// let coordinate = UtmCoordinate::new(323483.7, 4306479.2, 18, 'S');
+/// let meter = FixedMgrs::<5>::from_utm(&coordinate, Lettering::AA).unwrap();
+/// let hundred_meters: FixedMgrs<3> = meter.coarsen();
+/// assert_eq!(hundred_meters.to_string(), "18SUJ234064");
+/// // Plain references are checked when converted
+/// let mgrs: Mgrs = "18SUJ234064".parse().unwrap();
+/// assert_eq!(FixedMgrs::<3>::try_from(mgrs), Ok(hundred_meters));
+/// assert!(FixedMgrs::<5>::try_from(mgrs).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedMgrs<const DIGITS: usize> {
+    mgrs: Mgrs,
+}
+
+impl<const DIGITS: usize> FixedMgrs<DIGITS> {
+    const VALID: () = assert!(DIGITS <= 5, "MGRS references have at most five digits");
+
+    /// Creates the reference of a UTM coordinate, truncated to `DIGITS` digits.
+    pub fn from_utm(
+        coordinate: &UtmCoordinate,
+        lettering: Lettering,
+    ) -> Result<FixedMgrs<DIGITS>, Error> {
+        let () = Self::VALID;
+        Mgrs::from_utm(coordinate, DIGITS as u8, lettering).map(|mgrs| FixedMgrs { mgrs })
+    }
+
+    #[cfg(feature = "forward")]
+    /// Creates the reference of a WGS84 latitude and longitude in decimal degrees, using the
+    /// `AA` lettering scheme.
+    pub fn from_lat_lon(latitude: f64, longitude: f64) -> Result<FixedMgrs<DIGITS>, Error> {
+        let () = Self::VALID;
+        Mgrs::from_lat_lon(latitude, longitude, DIGITS as u8).map(|mgrs| FixedMgrs { mgrs })
+    }
+
+    /// Returns this reference at a coarser precision, truncating the digits that are dropped.
+    ///
+    /// Asking for a finer precision is a compile error:
+    ///
+    /// ```compile_fail
+    /// use utm::mgrs::{FixedMgrs, Lettering};
+    /// use utm::UtmCoordinate;
+    /// let coordinate = UtmCoordinate::new(323483.7, 4306479.2, 18, 'S');
+    /// let kilometer = FixedMgrs::<2>::from_utm(&coordinate, Lettering::AA).unwrap();
+    /// let meter: FixedMgrs<5> = kilometer.coarsen();
+    /// ```
+    pub fn coarsen<const TO: usize>(self) -> FixedMgrs<TO> {
+        let () = Coarsen::<DIGITS, TO>::VALID;
+        let cell = 10u32.pow(5 - TO as u32);
+        FixedMgrs {
+            mgrs: Mgrs {
+                easting: self.mgrs.easting / cell * cell,
+                northing: self.mgrs.northing / cell * cell,
+                precision: TO as u8,
+                ..self.mgrs
+            },
+        }
+    }
+
+    /// Returns the reference without its precision in its type.
+    pub fn mgrs(&self) -> Mgrs {
+        self.mgrs
+    }
+
+    /// Returns the UTM coordinate of the southwest corner of this reference's cell.
+    pub fn to_utm(&self, lettering: Lettering) -> Result<UtmCoordinate, Error> {
+        self.mgrs.to_utm(lettering)
+    }
+
+    #[cfg(feature = "inverse")]
+    /// Returns the WGS84 latitude and longitude in decimal degrees of the southwest corner of
+    /// this reference's cell, using the `AA` lettering scheme.
+    pub fn to_lat_lon(&self) -> Result<(f64, f64), Error> {
+        self.mgrs.to_lat_lon()
+    }
+}
+
+/// Checks at compile time that a precision is no finer than the one it comes from.
+struct Coarsen<const FROM: usize, const TO: usize>;
+
+impl<const FROM: usize, const TO: usize> Coarsen<FROM, TO> {
+    const VALID: () = assert!(TO <= FROM, "coarsen can't add digits to a reference");
+}
+
+impl<const DIGITS: usize> TryFrom<Mgrs> for FixedMgrs<DIGITS> {
+    type Error = Error;
+
+    /// Checks that a reference has `DIGITS` digits.
+    fn try_from(mgrs: Mgrs) -> Result<FixedMgrs<DIGITS>, Error> {
+        let () = Self::VALID;
+        if usize::from(mgrs.precision) == DIGITS {
+            Ok(FixedMgrs { mgrs })
+        } else {
+            Err(Error::PrecisionMismatch)
+        }
+    }
+}
+
+impl<const DIGITS: usize> From<FixedMgrs<DIGITS>> for Mgrs {
+    fn from(fixed: FixedMgrs<DIGITS>) -> Mgrs {
+        fixed.mgrs
+    }
+}
+
+impl<const DIGITS: usize> fmt::Display for FixedMgrs<DIGITS> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.mgrs.fmt(f)
+    }
+}
+
+impl<const DIGITS: usize> FromStr for FixedMgrs<DIGITS> {
+    type Err = Error;
+
+    /// Parses a reference like `Mgrs`, and checks that it has `DIGITS` digits.
+    fn from_str(s: &str) -> Result<FixedMgrs<DIGITS>, Error> {
+        FixedMgrs::try_from(s.parse::<Mgrs>()?)
+    }
+}
+
+/// An error converting one entry of a batch, with the index of the entry that failed.
+///
+/// Every entry before `index` has been converted and written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatchError {
+    /// The index of the entry that could not be converted.
+    pub index: usize,
+    /// What was wrong with it.
+    pub error: Error,
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Entry {}: {}", self.index, self.error)
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for BatchError {}
+
+/// MGRS references packed into one string, for encoding many references without an
+/// allocation each.
+///
+/// Clearing an arena keeps its capacity, so one arena can be reused across batches.
+#[cfg(any(feature = "alloc", not(feature = "no_std")))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Arena {
+    text: String,
+    ends: Vec<usize>,
+}
+
+#[cfg(any(feature = "alloc", not(feature = "no_std")))]
+impl Arena {
+    /// Creates an empty arena.
+    pub fn new() -> Arena {
+        Arena::default()
+    }
+
+    /// Creates an empty arena with room for `references` references of the longest form.
+    pub fn with_capacity(references: usize) -> Arena {
+        Arena {
+            text: String::with_capacity(references * 15),
+            ends: Vec::with_capacity(references),
+        }
+    }
+
+    /// Removes every reference, keeping the allocated capacity.
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.ends.clear();
+    }
+
+    /// Returns the number of references.
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Returns true if there are no references.
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Returns a reference by index.
+    pub fn get(&self, index: usize) -> Option<&str> {
+        let end = *self.ends.get(index)?;
+        let start = if index == 0 { 0 } else { self.ends[index - 1] };
+        Some(&self.text[start..end])
+    }
+
+    /// Returns an iterator over the references.
+    pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+        (0..self.len()).map(move |index| self.get(index).unwrap())
+    }
+
+    /// Appends a reference.
+    pub fn push(&mut self, mgrs: &Mgrs) {
+        use std::fmt::Write;
+        write!(self.text, "{}", mgrs).unwrap();
+        self.ends.push(self.text.len());
+    }
+}
+
+/// Encodes UTM coordinates as MGRS references, appending them to an arena.
+///
+/// Stops at the first coordinate that can't be encoded, after appending every one before it.
+///
+/// # Examples
+///
+/// ```
+/// use utm::mgrs::{encode_into_arena, Arena, Lettering};
+/// use utm::UtmCoordinate;
+/// let coordinates = [
+///     UtmCoordinate::new(323483.7, 4306479.2, 18, 'S'),
+///     UtmCoordinate::new(261878., 6243186., 34, 'H'),
+/// ];
+/// let mut arena = Arena::new();
+/// encode_into_arena(&coordinates, 5, Lettering::AA, &mut arena).unwrap();
+/// assert_eq!(arena.get(0), Some("18SUJ2348306479"));
+/// assert_eq!(arena.len(), 2);
+/// ```
+#[cfg(any(feature = "alloc", not(feature = "no_std")))]
+pub fn encode_into_arena(
+    coordinates: &[UtmCoordinate],
+    precision: u8,
+    lettering: Lettering,
+    arena: &mut Arena,
+) -> Result<(), BatchError> {
+    for (index, coordinate) in coordinates.iter().enumerate() {
+        let mgrs = Mgrs::from_utm(coordinate, precision, lettering)
+            .map_err(|error| BatchError { index, error })?;
+        arena.push(&mgrs);
+    }
+    Ok(())
+}
+
+/// Encodes UTM coordinates as MGRS references, one string each, reusing the strings'
+/// allocations.
+///
+/// Converts as many entries as fit in both slices and returns how many were written.
+///
+/// # Examples
+///
+/// ```
+/// use utm::mgrs::{encode_into_strings, Lettering};
+/// use utm::UtmCoordinate;
+/// let coordinates = [UtmCoordinate::new(323483.7, 4306479.2, 18, 'S')];
+/// let mut references = vec![String::new(); 1];
+/// let written = encode_into_strings(&coordinates, 3, Lettering::AA, &mut references).unwrap();
+/// assert_eq!(written, 1);
+/// assert_eq!(references[0], "18SUJ234064");
+/// ```
+#[cfg(any(feature = "alloc", not(feature = "no_std")))]
+pub fn encode_into_strings(
+    coordinates: &[UtmCoordinate],
+    precision: u8,
+    lettering: Lettering,
+    references: &mut [String],
+) -> Result<usize, BatchError> {
+    use std::fmt::Write;
+    let mut written = 0;
+    for (index, (coordinate, reference)) in
+        coordinates.iter().zip(references.iter_mut()).enumerate()
+    {
+        let mgrs = Mgrs::from_utm(coordinate, precision, lettering)
+            .map_err(|error| BatchError { index, error })?;
+        reference.clear();
+        write!(reference, "{}", mgrs).unwrap();
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// Decodes MGRS references into the UTM coordinates of the southwest corners of their cells.
+///
+/// Converts as many entries as fit in both slices and returns how many were written. Conversion
+/// stops at the first reference that can't be parsed or decoded.
+///
+/// # Examples
+///
+/// ```
+/// use utm::mgrs::{decode, Lettering};
+/// use utm::UtmCoordinate;
+/// let references = ["18SUJ2348306479", "34HBH6187843186"];
+/// let mut coordinates = [UtmCoordinate::new(0., 0., 1, 'C'); 2];
+/// assert_eq!(decode(&references, Lettering::AA, &mut coordinates), Ok(2));
+/// assert_eq!(coordinates[1].northing, 6243186.);
+/// ```
+pub fn decode<S: AsRef<str>>(
+    references: &[S],
+    lettering: Lettering,
+    coordinates: &mut [UtmCoordinate],
+) -> Result<usize, BatchError> {
+    let mut written = 0;
+    for (index, (reference, coordinate)) in
+        references.iter().zip(coordinates.iter_mut()).enumerate()
+    {
+        *coordinate = reference
+            .as_ref()
+            .parse::<Mgrs>()
+            .and_then(|mgrs| mgrs.to_utm(lettering))
+            .map_err(|error| BatchError { index, error })?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_every_square() {
+        for &lettering in &[Lettering::AA, Lettering::AL] {
+            for zone_num in 1..=60 {
+                for &(zone_letter, northing) in &[
+                    ('C', 1200000.),
+                    ('M', 9950000.),
+                    ('N', 50000.),
+                    ('X', 9300000.),
+                ] {
+                    for column in 1..9 {
+                        let easting = column as f64 * 100000. + 12345.;
+                        let coordinate =
+                            UtmCoordinate::new(easting, northing, zone_num, zone_letter);
+                        let mgrs = Mgrs::from_utm(&coordinate, 5, lettering).unwrap();
+                        let parsed: Mgrs = mgrs.to_string().parse().unwrap();
+                        assert_eq!(parsed, mgrs);
+                        assert_eq!(parsed.to_utm(lettering).unwrap(), coordinate);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn wrong_lettering_is_far_off() {
+        let coordinate = UtmCoordinate::new(323483., 4306479., 18, 'S');
+        let mgrs = Mgrs::from_utm(&coordinate, 5, Lettering::AL).unwrap();
+        let wrong = mgrs.to_utm(Lettering::AA).unwrap();
+        assert!((wrong.northing - coordinate.northing).abs() >= 1000000.);
+    }
+
+    #[test]
+    fn parse() {
+        let mgrs: Mgrs = "04q fj 1 6".parse().unwrap();
+        assert_eq!(
+            (mgrs.zone_num, mgrs.zone_letter, mgrs.column, mgrs.row),
+            (4, 'Q', 'F', 'J')
+        );
+        assert_eq!(
+            (mgrs.easting, mgrs.northing, mgrs.precision),
+            (10000, 60000, 1)
+        );
+        assert_eq!(mgrs.to_string(), "4QFJ16");
+        assert_eq!("4QFJ".parse::<Mgrs>().unwrap().precision, 0);
+        assert_eq!("4QFJ123".parse::<Mgrs>(), Err(Error::Format));
+        assert_eq!("61QFJ12".parse::<Mgrs>(), Err(Error::ZoneNumOutOfRange));
+        assert_eq!("4IFJ12".parse::<Mgrs>(), Err(Error::ZoneLetterOutOfRange));
+        assert_eq!(
+            "4QFJ123456123456".parse::<Mgrs>(),
+            Err(Error::PrecisionOutOfRange)
+        );
+        assert_eq!(
+            "4QSJ12".parse::<Mgrs>().unwrap().to_utm(Lettering::AA),
+            Err(Error::SquareOutOfRange)
+        );
+    }
+
+    #[test]
+    fn batch_round_trip() {
+        let coordinates: Vec<UtmCoordinate> = (0..100)
+            .map(|i| {
+                UtmCoordinate::new(
+                    150000. + f64::from(i) * 7000.,
+                    4000000. + f64::from(i) * 3000.,
+                    31,
+                    'S',
+                )
+            })
+            .collect();
+        let mut arena = Arena::with_capacity(coordinates.len());
+        encode_into_arena(&coordinates, 5, Lettering::AL, &mut arena).unwrap();
+        let references: Vec<&str> = arena.iter().collect();
+        let mut decoded = vec![UtmCoordinate::new(0., 0., 1, 'C'); coordinates.len()];
+        assert_eq!(decode(&references, Lettering::AL, &mut decoded), Ok(100));
+        assert_eq!(decoded, coordinates);
+        arena.clear();
+        assert!(arena.is_empty());
+        assert_eq!(arena.get(0), None);
+    }
+
+    #[test]
+    fn batch_errors() {
+        let coordinates = [
+            UtmCoordinate::new(323483., 4306479., 18, 'S'),
+            UtmCoordinate::new(50000., 4306479., 18, 'S'),
+        ];
+        let mut arena = Arena::new();
+        assert_eq!(
+            encode_into_arena(&coordinates, 5, Lettering::AA, &mut arena),
+            Err(BatchError {
+                index: 1,
+                error: Error::EastingOutOfRange
+            })
+        );
+        assert_eq!(arena.len(), 1);
+        let mut decoded = [UtmCoordinate::new(0., 0., 1, 'C'); 2];
+        assert_eq!(
+            decode(
+                &["18SUJ2348306479", "18SUJ234"],
+                Lettering::AA,
+                &mut decoded
+            ),
+            Err(BatchError {
+                index: 1,
+                error: Error::Format
+            })
+        );
+    }
+
+    #[cfg(all(feature = "forward", feature = "inverse"))]
+    #[test]
+    fn neighbors() {
+        let names = |reference: &str, lettering| -> Vec<String> {
+            let mgrs: Mgrs = reference.parse().unwrap();
+            let neighbors = mgrs.neighbors(lettering).unwrap();
+            for neighbor in neighbors.iter().flatten() {
+                if neighbor.zone_num == mgrs.zone_num {
+                    assert!(neighbor.neighbors(lettering).unwrap().contains(&Some(mgrs)));
+                }
+            }
+            neighbors
+                .iter()
+                .map(|neighbor| neighbor.map_or(String::new(), |n| n.to_string()))
+                .collect()
+        };
+        // Row letters roll over from V to A, and the west edge is the zone boundary
+        assert_eq!(
+            names("31QAV", Lettering::AA),
+            ["31QAA", "31QBA", "31QBV", "31QBU", "31QAU", "30QZD", "30QZE", "30QZF"]
+        );
+        // Band N to P, 8° north
+        let mgrs = Mgrs::from_lat_lon(7.9999, 9., 1).unwrap();
+        assert_eq!(mgrs.zone_letter, 'N');
+        let north = mgrs.neighbors(Lettering::AA).unwrap()[0].unwrap();
+        assert_eq!(north.zone_letter, 'P');
+        assert_eq!((north.column, north.easting), (mgrs.column, mgrs.easting));
+        // Beyond 84°N
+        let mgrs = Mgrs::from_lat_lon(83.9999, 9., 2).unwrap();
+        assert_eq!(mgrs.neighbors(Lettering::AA).unwrap()[0], None);
+        // Zone 32V is widened west over 31V
+        let mgrs = Mgrs::from_lat_lon(60., 3.01, 0).unwrap();
+        assert_eq!(mgrs.zone_num, 32);
+        let west = mgrs.neighbors(Lettering::AA).unwrap()[6].unwrap();
+        assert_eq!((west.zone_num, west.zone_letter), (31, 'V'));
+    }
+
+    #[test]
+    fn fixed_precision() {
+        let coordinate = UtmCoordinate::new(323483.7, 4306479.2, 18, 'S');
+        let meter = FixedMgrs::<5>::from_utm(&coordinate, Lettering::AA).unwrap();
+        assert_eq!(
+            meter.mgrs(),
+            Mgrs::from_utm(&coordinate, 5, Lettering::AA).unwrap()
+        );
+        let square: FixedMgrs<0> = meter.coarsen();
+        assert_eq!(square.to_string(), "18SUJ");
+        assert_eq!(square.to_utm(Lettering::AA).unwrap().easting, 300000.);
+        let same: FixedMgrs<5> = meter.coarsen();
+        assert_eq!(same, meter);
+        assert_eq!(
+            "18SUJ2348306479".parse::<FixedMgrs<4>>(),
+            Err(Error::PrecisionMismatch)
+        );
+        assert_eq!("18SUJ2348306479".parse::<FixedMgrs<5>>(), Ok(meter));
+        assert_eq!(Mgrs::from(meter).precision, 5);
+    }
+}
@@ -0,0 +1,272 @@
+//! Move a GNSS antenna position to another point on the same vehicle.
+//!
+//! Machine guidance needs the position of the implement, a blade or a nozzle, not that of the
+//! antenna on the cab roof. A `LeverArm` is the mounting offset from the antenna to that point
+//! in the vehicle's body frame. With the vehicle's heading, the offset is turned into an east,
+//! north, and up vector, rotated into earth-centered earth-fixed coordinates, and added there, so
+//! the result is exact for any offset and any latitude. `LeverArm::to_grid` then projects the
+//! offset point into a UTM zone and turns the heading to grid north, like
+//! `frame::GeodeticPose::to_grid`.
+//!
+//! Headings are in radians clockwise from true north, heights in meters above the ellipsoid,
+//! and the vehicle is assumed to be level.
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use super::frame::GeodeticPose;
+#[cfg(feature = "forward")]
+use super::frame::GridPose;
+use super::{Ellipsoid, WGS84};
+
+/// A mounting offset in meters in the vehicle's body frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LeverArm {
+    /// The distance ahead of the antenna.
+    pub forward: f64,
+    /// The distance to the right of the antenna.
+    pub right: f64,
+    /// The distance above the antenna.
+    pub up: f64,
+}
+
+impl LeverArm {
+    /// Creates a lever arm from its forward, right, and up offsets in meters.
+    pub fn new(forward: f64, right: f64, up: f64) -> LeverArm {
+        LeverArm { forward, right, up }
+    }
+
+    /// Returns the offset as east, north, and up meters for a vehicle with a heading in
+    /// radians from true north.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::f64::consts::FRAC_PI_2;
+    /// use utm::mounting::LeverArm;
+    /// // Two meters ahead of a vehicle heading east is two meters east
+    /// let (east, north, up) = LeverArm::new(2., 0., -1.).to_enu(FRAC_PI_2);
+    /// assert!((east - 2.).abs() < 1e-12 && north.abs() < 1e-12 && up == -1.);
+    /// ```
+    pub fn to_enu(&self, heading: f64) -> (f64, f64, f64) {
+        let (sin, cos) = heading.sin_cos();
+        (
+            self.forward * sin + self.right * cos,
+            self.forward * cos - self.right * sin,
+            self.up,
+        )
+    }
+
+    /// Returns the pose and height of the offset point for an antenna pose and height on WGS84.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::frame::GeodeticPose;
+    /// use utm::mounting::LeverArm;
+    /// let antenna = GeodeticPose { latitude: 47.5, longitude: -119.2, heading: 0. };
+    /// // The blade is 3 m ahead of and 2.5 m below the antenna
+    /// let (blade, height) = LeverArm::new(3., 0., -2.5).apply(&antenna, 400.);
+    /// assert!(blade.latitude > antenna.latitude);
+    /// assert!((blade.longitude - antenna.longitude).abs() < 1e-12);
+    /// assert!((height - 397.5).abs() < 1e-3);
+    /// ```
+    pub fn apply(&self, antenna: &GeodeticPose, height: f64) -> (GeodeticPose, f64) {
+        let (x, y, z) = geodetic_to_ecef(WGS84, antenna.latitude, antenna.longitude, height);
+        let (dx, dy, dz) = enu_to_ecef(
+            antenna.latitude,
+            antenna.longitude,
+            self.to_enu(antenna.heading),
+        );
+        let (latitude, longitude, height) = ecef_to_geodetic(WGS84, x + dx, y + dy, z + dz);
+        (
+            GeodeticPose {
+                latitude,
+                longitude,
+                heading: antenna.heading,
+            },
+            height,
+        )
+    }
+
+    #[cfg(feature = "forward")]
+    /// Returns the grid pose and height of the offset point for an antenna pose and height on
+    /// WGS84, projected into a zone.
+    ///
+    /// Returns `None` if the offset point is outside of the UTM bands.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::frame::GeodeticPose;
+    /// use utm::mounting::LeverArm;
+    /// let antenna = GeodeticPose { latitude: 47.5, longitude: -119.2, heading: 1. };
+    /// let cab = antenna.to_grid(11).unwrap();
+    /// let (blade, _) = LeverArm::new(10., 0., 0.).to_grid(&antenna, 400., 11).unwrap();
+    /// // The blade is 10 m from the antenna along the grid heading
+    /// let east = blade.coordinate.easting - cab.coordinate.easting;
+    /// let north = blade.coordinate.northing - cab.coordinate.northing;
+    /// assert!((east.hypot(north) - 10.).abs() < 0.01);
+    /// assert!((east.atan2(north) - cab.heading).abs() < 1e-5);
+    /// ```
+    pub fn to_grid(
+        &self,
+        antenna: &GeodeticPose,
+        height: f64,
+        zone: u8,
+    ) -> Option<(GridPose, f64)> {
+        let (pose, height) = self.apply(antenna, height);
+        Some((pose.to_grid(zone)?, height))
+    }
+}
+
+/// Converts a latitude and longitude in decimal degrees and a height in meters above an
+/// ellipsoid to earth-centered earth-fixed coordinates in meters.
+///
+/// # Examples
+///
+/// ```
+/// use utm::mounting::geodetic_to_ecef;
+/// use utm::datum::GRS80;
+/// let (x, y, z) = geodetic_to_ecef(GRS80, 0., 90., 0.);
+/// assert!(x.abs() < 1e-9 && (y - 6378137.).abs() < 1e-9 && z == 0.);
+/// ```
+pub fn geodetic_to_ecef(
+    ellipsoid: Ellipsoid,
+    latitude: f64,
+    longitude: f64,
+    height: f64,
+) -> (f64, f64, f64) {
+    let e2 = eccentricity_squared(ellipsoid);
+    let (sin_lat, cos_lat) = latitude.to_radians().sin_cos();
+    let (sin_lon, cos_lon) = longitude.to_radians().sin_cos();
+    let n = ellipsoid.semi_major_axis() / (1. - e2 * sin_lat * sin_lat).sqrt();
+    (
+        (n + height) * cos_lat * cos_lon,
+        (n + height) * cos_lat * sin_lon,
+        (n * (1. - e2) + height) * sin_lat,
+    )
+}
+
+/// Converts earth-centered earth-fixed coordinates in meters to a latitude and longitude in
+/// decimal degrees and a height in meters above an ellipsoid.
+///
+/// # Examples
+///
+/// ```
+/// use utm::mounting::{ecef_to_geodetic, geodetic_to_ecef};
+/// use utm::datum::GRS80;
+/// let (x, y, z) = geodetic_to_ecef(GRS80, -33.92487, 18.42406, 12.);
+/// let (latitude, longitude, height) = ecef_to_geodetic(GRS80, x, y, z);
+/// assert!((latitude + 33.92487).abs() < 1e-12 && (longitude - 18.42406).abs() < 1e-12);
+/// assert!((height - 12.).abs() < 1e-6);
+/// ```
+pub fn ecef_to_geodetic(ellipsoid: Ellipsoid, x: f64, y: f64, z: f64) -> (f64, f64, f64) {
+    let a = ellipsoid.semi_major_axis();
+    let e2 = eccentricity_squared(ellipsoid);
+    let p = x.hypot(y);
+    let mut latitude = z.atan2(p * (1. - e2));
+    let mut height = 0.;
+    for _ in 0..8 {
+        let sin_lat = latitude.sin();
+        let n = a / (1. - e2 * sin_lat * sin_lat).sqrt();
+        // Stable at the poles, unlike p / cos(latitude) - n.
+        height = p * latitude.cos() + z * sin_lat - a * a / n;
+        latitude = z.atan2(p * (1. - e2 * n / (n + height)));
+    }
+    (latitude.to_degrees(), y.atan2(x).to_degrees(), height)
+}
+
+/// Rotates an east, north, and up vector at a latitude and longitude in decimal degrees into
+/// earth-centered earth-fixed axes.
+fn enu_to_ecef(
+    latitude: f64,
+    longitude: f64,
+    (east, north, up): (f64, f64, f64),
+) -> (f64, f64, f64) {
+    let (sin_lat, cos_lat) = latitude.to_radians().sin_cos();
+    let (sin_lon, cos_lon) = longitude.to_radians().sin_cos();
+    (
+        -sin_lon * east - sin_lat * cos_lon * north + cos_lat * cos_lon * up,
+        cos_lon * east - sin_lat * sin_lon * north + cos_lat * sin_lon * up,
+        cos_lat * north + sin_lat * up,
+    )
+}
+
+/// Returns the square of the first eccentricity.
+fn eccentricity_squared(ellipsoid: Ellipsoid) -> f64 {
+    let f = ellipsoid.flattening();
+    f * (2. - f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        for &(latitude, longitude, height) in &[
+            (0., 0., 0.),
+            (89.9999, -45., 1000.),
+            (-60., 179.5, -30.),
+            (45., 10., 8000.),
+        ] {
+            let (x, y, z) = geodetic_to_ecef(WGS84, latitude, longitude, height);
+            let (lat, lon, h) = ecef_to_geodetic(WGS84, x, y, z);
+            assert!((lat - latitude).abs() < 1e-11, "{}", lat);
+            assert!((lon - longitude).abs() < 1e-11, "{}", lon);
+            assert!((h - height).abs() < 1e-6, "{}", h);
+        }
+    }
+
+    #[test]
+    fn offsets_keep_their_length() {
+        let antenna = GeodeticPose {
+            latitude: -70.,
+            longitude: 100.,
+            heading: 4.,
+        };
+        let arm = LeverArm::new(3., -1.5, 2.);
+        let (pose, height) = arm.apply(&antenna, 50.);
+        assert_eq!(pose.heading, 4.);
+        let start = geodetic_to_ecef(WGS84, -70., 100., 50.);
+        let end = geodetic_to_ecef(WGS84, pose.latitude, pose.longitude, height);
+        let length =
+            ((end.0 - start.0).powi(2) + (end.1 - start.1).powi(2) + (end.2 - start.2).powi(2))
+                .sqrt();
+        assert!((length - (9f64 + 2.25 + 4.).sqrt()).abs() < 1e-6);
+        // Up is along the normal, so the height only changes by the curvature over 3.4 m
+        assert!((height - 52.).abs() < 1e-5);
+    }
+
+    #[cfg(feature = "forward")]
+    #[test]
+    fn grid_heading_points_at_the_offset() {
+        // Both sides of the central meridian, in both hemispheres
+        for &(latitude, longitude, zone) in &[
+            (47.5, -119.2, 11),
+            (-33.92487, 18.42406, 34),
+            (-33.92487, 23.5, 34),
+        ] {
+            let antenna = GeodeticPose {
+                latitude,
+                longitude,
+                heading: 1.,
+            };
+            let cab = antenna.to_grid(zone).unwrap();
+            let (blade, _) = LeverArm::new(10., 0., 0.)
+                .to_grid(&antenna, 400., zone)
+                .unwrap();
+            let east = blade.coordinate.easting - cab.coordinate.easting;
+            let north = blade.coordinate.northing - cab.coordinate.northing;
+            assert!((east.hypot(north) - 10.).abs() < 0.01);
+            assert!(
+                (east.atan2(north) - cab.heading).abs() < 1e-5,
+                "{} {}",
+                east.atan2(north),
+                cab.heading
+            );
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Prepare UTM coordinates from several zones for a nearest-neighbor search.
+//!
+//! Eastings and northings from different zones live in different planes, so a KD-tree or grid
+//! index built on them misses neighbors just across a zone boundary. `Prefilter` re-projects
+//! candidates into the query's zone and hemisphere, past the zone's edges if need be, so plain
+//! Euclidean distances between the results are grid distances in one plane. Re-projection uses
+//! Krüger's series through `converter::Converter`, which stays accurate well outside of the
+//! zone.
+//!
+//! Candidates too far from the query's zone to project sensibly, beyond the extended domain of
+//! `accuracy::AccuracyClass`, are dropped. They are several degrees of longitude from the zone.
+
+use super::accuracy::{accuracy_class, AccuracyClass};
+use super::converter::Converter;
+use super::tm::TransverseMercator;
+use super::{UtmCoordinate, WSG84ToLatLonError};
+
+/// Re-projects candidates into the zone and hemisphere of a query point.
+#[derive(Clone, Copy, Debug)]
+pub struct Prefilter {
+    zone_num: u8,
+    northern: bool,
+    converter: Converter,
+    projection: TransverseMercator,
+}
+
+impl Prefilter {
+    /// Creates a prefilter for the zone and hemisphere of a query point.
+    pub fn new(query: &UtmCoordinate) -> Prefilter {
+        let converter = Converter::new();
+        let northern = query.zone_letter >= 'N';
+        Prefilter {
+            zone_num: query.zone_num,
+            northern,
+            converter,
+            projection: converter.projection(query.zone_num, northern),
+        }
+    }
+
+    /// Returns a candidate's easting and northing in the query's zone and hemisphere, or `None`
+    /// if it is outside of the zone's extended domain.
+    ///
+    /// Candidates already in the query's zone and hemisphere are returned as is. Candidates on
+    /// the other side of the equator get a northing in the query's convention, i.e. negative or
+    /// above 10,000,000 m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::nearest::Prefilter;
+    /// use utm::UtmCoordinate;
+    /// // Either side of the boundary between zones 32 and 33 at 45°N
+    /// let query = UtmCoordinate::new(736000., 4987000., 32, 'T');
+    /// let candidate = UtmCoordinate::new(264000., 4987000., 33, 'T');
+    /// let (easting, northing) = Prefilter::new(&query).normalize(&candidate).unwrap().unwrap();
+    /// let distance = (easting - query.easting).hypot(northing - query.northing);
+    /// assert!(distance < 2000.);
+    /// ```
+    pub fn normalize(
+        &self,
+        candidate: &UtmCoordinate,
+    ) -> Result<Option<(f64, f64)>, WSG84ToLatLonError> {
+        if candidate.zone_num == self.zone_num && (candidate.zone_letter >= 'N') == self.northern {
+            return Ok(Some((candidate.easting, candidate.northing)));
+        }
+        let (latitude, longitude) = self.converter.to_lat_lon(candidate)?;
+        if accuracy_class(latitude, longitude, self.zone_num) == AccuracyClass::OutOfDomain {
+            return Ok(None);
+        }
+        let (northing, easting, _) = self.projection.forward(latitude, longitude);
+        Ok(Some((easting, northing)))
+    }
+}
+
+/// Re-projects candidates into the zone and hemisphere of a query point and returns how many
+/// were written to `output`.
+///
+/// Index the `Some` outputs with their positions to map neighbors back to the candidates.
+///
+/// # Examples
+///
+/// ```
+/// use utm::nearest::normalize_candidates;
+/// use utm::UtmCoordinate;
+/// let query = UtmCoordinate::new(736000., 4987000., 32, 'T');
+/// let candidates = [
+///     UtmCoordinate::new(700000., 4987000., 32, 'T'),
+///     UtmCoordinate::new(264000., 4987000., 33, 'T'),
+///     UtmCoordinate::new(500000., 4987000., 40, 'T'),
+/// ];
+/// let mut output = [None; 3];
+/// assert_eq!(normalize_candidates(&query, &candidates, &mut output), Ok(3));
+/// assert_eq!(output[0], Some((700000., 4987000.)));
+/// assert!(output[1].unwrap().0 > 736000.);
+/// assert_eq!(output[2], None);
+/// ```
+pub fn normalize_candidates(
+    query: &UtmCoordinate,
+    candidates: &[UtmCoordinate],
+    output: &mut [Option<(f64, f64)>],
+) -> Result<usize, WSG84ToLatLonError> {
+    let prefilter = Prefilter::new(query);
+    let len = candidates.len().min(output.len());
+    for (candidate, output) in candidates.iter().zip(output.iter_mut()) {
+        *output = prefilter.normalize(candidate)?;
+    }
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn across_the_equator() {
+        let query = UtmCoordinate::new(500000., 10000., 31, 'N');
+        let candidate = UtmCoordinate::new(500000., 9990000., 31, 'M');
+        let (easting, northing) = Prefilter::new(&query)
+            .normalize(&candidate)
+            .unwrap()
+            .unwrap();
+        assert!((easting - 500000.).abs() < 1e-6);
+        assert!((northing + 10000.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn nearest_across_the_boundary() {
+        // The nearest candidate in the query's own zone is 20 km away, but one just over the
+        // boundary is about a kilometer away.
+        let query = UtmCoordinate::new(736000., 4987000., 32, 'T');
+        let candidates = [
+            UtmCoordinate::new(716000., 4987000., 32, 'T'),
+            UtmCoordinate::new(264000., 4987000., 33, 'T'),
+        ];
+        let mut output = [None; 2];
+        normalize_candidates(&query, &candidates, &mut output).unwrap();
+        let distance = |point: Option<(f64, f64)>| {
+            let (easting, northing) = point.unwrap();
+            (easting - query.easting).hypot(northing - query.northing)
+        };
+        assert!(distance(output[1]) < distance(output[0]));
+    }
+
+    #[test]
+    fn invalid_candidate() {
+        let query = UtmCoordinate::new(500000., 5000000., 31, 'T');
+        let candidate = UtmCoordinate::new(500000., 5000000., 61, 'T');
+        assert_eq!(
+            Prefilter::new(&query).normalize(&candidate),
+            Err(WSG84ToLatLonError::ZoneNumOutOfRange)
+        );
+    }
+}
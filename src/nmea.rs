@@ -0,0 +1,522 @@
+//! A streaming pipeline from NMEA 0183 sentences to UTM fixes.
+//!
+//! GNSS receivers report positions as GGA and RMC sentences with latitudes and longitudes in
+//! degrees and decimal minutes. A `Pipeline` takes those sentences one line at a time, checks
+//! them, and projects every position into UTM, so a telemetry service can go from a serial
+//! port to grid coordinates with nothing else.
+//!
+//! The pipeline locks onto the zone of its first fix and keeps projecting into it while the
+//! receiver stays in the zone or its overlap region, `accuracy::OVERLAP_DEGREES` either side.
+//! A track along a zone boundary therefore doesn't flip between zones with every fix. Once a
+//! fix is further out, the lock moves to that fix's zone. Projections use Krüger's series
+//! through `converter::Converter`, which stays accurate in the overlap.
+//!
+//! GGA sentences don't carry a date, so fixes from them get the date of the latest RMC
+//! sentence, if there has been one. Other sentence types are skipped.
+//!
+//! ```
+//! use utm::nmea::Pipeline;
+//! let sentences = [
+//!     "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A",
+//!     "$GPGSV,2,1,08,01,40,083,46,02,17,308,41,12,07,344,39,14,22,228,45*75",
+//!     "$GPGGA,123520,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*4D",
+//! ];
+//! let fixes: Vec<_> = Pipeline::new().fixes(sentences.iter()).collect();
+//! assert_eq!(fixes.len(), 2);
+//! let fix = fixes[1].unwrap();
+//! assert_eq!((fix.coordinate.zone_num, fix.coordinate.zone_letter), (32, 'U'));
+//! assert_eq!(fix.date.unwrap().year, 1994);
+//! assert_eq!(fix.quality.satellites, Some(8));
+//! ```
+
+use std::fmt;
+
+use super::accuracy::{accuracy_class, AccuracyClass};
+use super::converter::Converter;
+use super::tm::TransverseMercator;
+use super::{lat_lon_to_zone_number, lat_to_zone_letter, UtmCoordinate};
+
+/// Errors that can occur when reading a sentence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The sentence doesn't match its checksum.
+    Checksum,
+    /// The sentence isn't valid NMEA, or one of its fields can't be parsed.
+    Malformed,
+    /// The position is outside of the UTM latitude bands, 80°S to 84°N.
+    OutsideUtm,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Checksum => write!(f, "Sentence does not match its checksum"),
+            Error::Malformed => write!(f, "Sentence is malformed"),
+            Error::OutsideUtm => write!(f, "Position is outside of the UTM latitude bands"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A UTC time of day.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Time {
+    /// The hour, from 0 to 23.
+    pub hour: u8,
+    /// The minute, from 0 to 59.
+    pub minute: u8,
+    /// The second, with any fraction the receiver reports.
+    pub second: f64,
+}
+
+/// A UTC date.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Date {
+    /// The year. NMEA only has two digits, which are read as 1980 to 2079.
+    pub year: u16,
+    /// The month, from 1 to 12.
+    pub month: u8,
+    /// The day of the month, from 1 to 31.
+    pub day: u8,
+}
+
+/// The kind of position solution, from the GGA fix quality or the RMC status and mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FixQuality {
+    /// No valid fix. The position may be stale or made up.
+    Invalid,
+    /// An autonomous GNSS fix.
+    Autonomous,
+    /// A differential fix, e.g. with SBAS corrections.
+    Differential,
+    /// A precise positioning service fix.
+    Precise,
+    /// A real-time kinematic fix with fixed integer ambiguities.
+    RtkFixed,
+    /// A real-time kinematic fix with float ambiguities.
+    RtkFloat,
+    /// A dead reckoning estimate.
+    Estimated,
+    /// A position entered by hand.
+    Manual,
+    /// A simulated position.
+    Simulated,
+}
+
+/// How good a fix is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quality {
+    /// The kind of solution.
+    pub fix: FixQuality,
+    /// The number of satellites used, from GGA sentences.
+    pub satellites: Option<u8>,
+    /// The horizontal dilution of precision, from GGA sentences.
+    pub hdop: Option<f64>,
+    /// Where the fix lies relative to the locked zone.
+    pub accuracy: AccuracyClass,
+}
+
+/// A timestamped position in the locked zone.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fix {
+    /// The time of the fix.
+    pub time: Time,
+    /// The date of the fix, from the sentence or the latest RMC sentence.
+    pub date: Option<Date>,
+    /// The latitude in decimal degrees.
+    pub latitude: f64,
+    /// The longitude in decimal degrees.
+    pub longitude: f64,
+    /// The position in the locked zone, with the band of its latitude.
+    pub coordinate: UtmCoordinate,
+    /// The altitude above mean sea level in meters, from GGA sentences.
+    pub altitude: Option<f64>,
+    /// How good the fix is.
+    pub quality: Quality,
+}
+
+/// Turns NMEA sentences into UTM fixes in a locked zone.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pipeline {
+    converter: Converter,
+    lock: Option<(u8, bool, TransverseMercator)>,
+    date: Option<Date>,
+}
+
+impl Pipeline {
+    /// Creates a pipeline for standard UTM on WGS84.
+    pub fn new() -> Pipeline {
+        Pipeline::with_converter(Converter::new())
+    }
+
+    /// Creates a pipeline that projects with a converter's grid constants.
+    pub fn with_converter(converter: Converter) -> Pipeline {
+        Pipeline {
+            converter,
+            lock: None,
+            date: None,
+        }
+    }
+
+    /// Returns the locked zone, or `None` before the first fix.
+    pub fn zone(&self) -> Option<u8> {
+        self.lock.map(|(zone_num, _, _)| zone_num)
+    }
+
+    /// Reads one sentence and returns its fix.
+    ///
+    /// Returns `Ok(None)` for sentences other than GGA and RMC, and for those without a
+    /// position, which receivers send before their first fix. A checksum is checked if the
+    /// sentence has one. Trailing line endings are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::nmea::{Error, FixQuality, Pipeline};
+    /// let mut pipeline = Pipeline::new();
+    /// assert_eq!(pipeline.push("$GPGGA,123519,,,,,0,00,,,M,,M,,*6B"), Ok(None));
+    /// let fix = pipeline
+    ///     .push("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\r\n")
+    ///     .unwrap()
+    ///     .unwrap();
+    /// assert_eq!(fix.quality.fix, FixQuality::Autonomous);
+    /// assert_eq!(fix.altitude, Some(545.4));
+    /// assert_eq!(
+    ///     pipeline.push("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*48"),
+    ///     Err(Error::Checksum)
+    /// );
+    /// ```
+    pub fn push(&mut self, sentence: &str) -> Result<Option<Fix>, Error> {
+        let body = checked_body(sentence.trim_end())?;
+        let mut fields = [""; 13];
+        let mut count = 0;
+        for (field, value) in fields.iter_mut().zip(body.split(',')) {
+            *field = value;
+            count += 1;
+        }
+        if fields[0].len() != 5 || !fields[0].is_ascii() {
+            return Err(Error::Malformed);
+        }
+        let (time, date, latitude, longitude, altitude, quality) = match &fields[0][2..] {
+            "GGA" if count >= 10 => {
+                if fields[2].is_empty() || fields[4].is_empty() {
+                    return Ok(None);
+                }
+                let fix = match fields[6] {
+                    "0" => FixQuality::Invalid,
+                    "1" => FixQuality::Autonomous,
+                    "2" => FixQuality::Differential,
+                    "3" => FixQuality::Precise,
+                    "4" => FixQuality::RtkFixed,
+                    "5" => FixQuality::RtkFloat,
+                    "6" => FixQuality::Estimated,
+                    "7" => FixQuality::Manual,
+                    "8" => FixQuality::Simulated,
+                    _ => return Err(Error::Malformed),
+                };
+                (
+                    parse_time(fields[1])?,
+                    self.date,
+                    parse_angle(fields[2], fields[3], 2)?,
+                    parse_angle(fields[4], fields[5], 3)?,
+                    optional(fields[9])?,
+                    (fix, optional(fields[7])?, optional(fields[8])?),
+                )
+            }
+            "RMC" if count >= 10 => {
+                if fields[3].is_empty() || fields[5].is_empty() {
+                    return Ok(None);
+                }
+                let fix = match (fields[2], if count > 12 { fields[12] } else { "" }) {
+                    ("V", _) | (_, "N") => FixQuality::Invalid,
+                    ("A", "D") => FixQuality::Differential,
+                    ("A", "P") => FixQuality::Precise,
+                    ("A", "R") => FixQuality::RtkFixed,
+                    ("A", "F") => FixQuality::RtkFloat,
+                    ("A", "E") => FixQuality::Estimated,
+                    ("A", "M") => FixQuality::Manual,
+                    ("A", "S") => FixQuality::Simulated,
+                    ("A", _) => FixQuality::Autonomous,
+                    _ => return Err(Error::Malformed),
+                };
+                let date = parse_date(fields[9])?;
+                self.date = Some(date);
+                (
+                    parse_time(fields[1])?,
+                    Some(date),
+                    parse_angle(fields[3], fields[4], 2)?,
+                    parse_angle(fields[5], fields[6], 3)?,
+                    None,
+                    (fix, None, None),
+                )
+            }
+            "GGA" | "RMC" => return Err(Error::Malformed),
+            _ => return Ok(None),
+        };
+        let zone_letter = lat_to_zone_letter(latitude).ok_or(Error::OutsideUtm)?;
+        let (zone_num, accuracy) = self.lock_zone(latitude, longitude);
+        let northern = zone_letter >= 'N';
+        let projection = match self.lock {
+            Some((locked, hemisphere, projection))
+                if locked == zone_num && hemisphere == northern =>
+            {
+                projection
+            }
+            _ => self.converter.projection(zone_num, northern),
+        };
+        self.lock = Some((zone_num, northern, projection));
+        let (northing, easting, _) = projection.forward(latitude, longitude);
+        let (fix, satellites, hdop) = quality;
+        Ok(Some(Fix {
+            time,
+            date,
+            latitude,
+            longitude,
+            coordinate: UtmCoordinate::new(easting, northing, zone_num, zone_letter),
+            altitude,
+            quality: Quality {
+                fix,
+                satellites,
+                hdop,
+                accuracy,
+            },
+        }))
+    }
+
+    /// Returns an iterator over the fixes of a sequence of sentences, skipping sentences
+    /// without one.
+    ///
+    /// To read from a serial port or file, pass the lines of a `std::io::BufRead`, e.g.
+    /// `reader.lines().map_while(Result::ok)`.
+    pub fn fixes<I>(self, sentences: I) -> Fixes<I::IntoIter>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        Fixes {
+            pipeline: self,
+            sentences: sentences.into_iter(),
+        }
+    }
+
+    /// Returns the zone to project a position into, and the position's class relative to it.
+    fn lock_zone(&self, latitude: f64, longitude: f64) -> (u8, AccuracyClass) {
+        if let Some((zone_num, _, _)) = self.lock {
+            let class = accuracy_class(latitude, longitude, zone_num);
+            if class <= AccuracyClass::OverlapRegion {
+                return (zone_num, class);
+            }
+        }
+        let zone_num = lat_lon_to_zone_number(latitude, longitude);
+        (zone_num, accuracy_class(latitude, longitude, zone_num))
+    }
+}
+
+impl Default for Pipeline {
+    fn default() -> Pipeline {
+        Pipeline::new()
+    }
+}
+
+/// An iterator over the fixes of a sequence of sentences, created by `Pipeline::fixes`.
+#[derive(Debug)]
+pub struct Fixes<I> {
+    pipeline: Pipeline,
+    sentences: I,
+}
+
+impl<I> Fixes<I> {
+    /// Returns the pipeline, e.g. to check the locked zone.
+    pub fn pipeline(&self) -> &Pipeline {
+        &self.pipeline
+    }
+}
+
+impl<I> Iterator for Fixes<I>
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    type Item = Result<Fix, Error>;
+
+    fn next(&mut self) -> Option<Result<Fix, Error>> {
+        for sentence in &mut self.sentences {
+            match self.pipeline.push(sentence.as_ref()) {
+                Ok(Some(fix)) => return Some(Ok(fix)),
+                Ok(None) => {}
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        None
+    }
+}
+
+/// Returns the fields of a sentence between the `$` and the checksum, after checking it.
+fn checked_body(sentence: &str) -> Result<&str, Error> {
+    let body = sentence.strip_prefix('$').ok_or(Error::Malformed)?;
+    match body.split_once('*') {
+        Some((body, checksum)) => {
+            let expected = u8::from_str_radix(checksum, 16).map_err(|_| Error::Malformed)?;
+            if body.bytes().fold(0, |sum, byte| sum ^ byte) == expected {
+                Ok(body)
+            } else {
+                Err(Error::Checksum)
+            }
+        }
+        None => Ok(body),
+    }
+}
+
+/// Parses an angle written as degrees and decimal minutes, e.g. `4807.038` with `N`.
+fn parse_angle(value: &str, hemisphere: &str, degree_digits: usize) -> Result<f64, Error> {
+    if value.len() < degree_digits + 2 || !value.is_ascii() {
+        return Err(Error::Malformed);
+    }
+    let (degrees, minutes) = value.split_at(degree_digits);
+    let degrees: f64 = parse(degrees)?;
+    let minutes: f64 = parse(minutes)?;
+    if minutes >= 60. {
+        return Err(Error::Malformed);
+    }
+    let angle = degrees + minutes / 60.;
+    match hemisphere {
+        "N" | "E" => Ok(angle),
+        "S" | "W" => Ok(-angle),
+        _ => Err(Error::Malformed),
+    }
+}
+
+/// Parses a time written as `hhmmss` with an optional fraction of a second.
+fn parse_time(value: &str) -> Result<Time, Error> {
+    if value.len() < 6 || !value.is_ascii() {
+        return Err(Error::Malformed);
+    }
+    let time = Time {
+        hour: parse(&value[..2])?,
+        minute: parse(&value[2..4])?,
+        second: parse(&value[4..])?,
+    };
+    if time.hour > 23 || time.minute > 59 || !(0. ..61.).contains(&time.second) {
+        return Err(Error::Malformed);
+    }
+    Ok(time)
+}
+
+/// Parses a date written as `ddmmyy`.
+fn parse_date(value: &str) -> Result<Date, Error> {
+    if value.len() != 6 || !value.is_ascii() {
+        return Err(Error::Malformed);
+    }
+    let day = parse(&value[..2])?;
+    let month = parse(&value[2..4])?;
+    let year: u16 = parse(&value[4..])?;
+    if !(1..=31).contains(&day) || !(1..=12).contains(&month) {
+        return Err(Error::Malformed);
+    }
+    Ok(Date {
+        year: if year < 80 { 2000 + year } else { 1900 + year },
+        month,
+        day,
+    })
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, Error> {
+    value.parse().map_err(|_| Error::Malformed)
+}
+
+fn optional<T: std::str::FromStr>(value: &str) -> Result<Option<T>, Error> {
+    if value.is_empty() {
+        Ok(None)
+    } else {
+        parse(value).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gga(latitude: &str, longitude: &str) -> String {
+        format!(
+            "$GPGGA,000000.00,{},{},2,10,0.8,10.0,M,,M,,",
+            latitude, longitude
+        )
+    }
+
+    #[test]
+    fn zone_lock() {
+        let mut pipeline = Pipeline::new();
+        // 45°N 11.9°E, in zone 32
+        let fix = pipeline
+            .push(&gga("4500.000,N", "01154.000,E"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(fix.coordinate.zone_num, 32);
+        assert_eq!(fix.quality.accuracy, AccuracyClass::Nominal);
+        assert_eq!(fix.quality.fix, FixQuality::Differential);
+        // 12.3°E is in zone 33, but within the overlap of zone 32
+        let fix = pipeline
+            .push(&gga("4500.000,N", "01218.000,E"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(fix.coordinate.zone_num, 32);
+        assert_eq!(fix.quality.accuracy, AccuracyClass::OverlapRegion);
+        // 12.6°E is beyond it
+        let fix = pipeline
+            .push(&gga("4500.000,N", "01236.000,E"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(fix.coordinate.zone_num, 33);
+        assert_eq!(fix.quality.accuracy, AccuracyClass::Nominal);
+        // Back at 11.9°E, now within the overlap of zone 33
+        let fix = pipeline
+            .push(&gga("4500.000,N", "01154.000,E"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(fix.coordinate.zone_num, 33);
+        assert_eq!(pipeline.zone(), Some(33));
+    }
+
+    #[test]
+    fn positions() {
+        let mut pipeline = Pipeline::new();
+        let fix = pipeline
+            .push("$GNRMC,235959.50,A,3355.492,S,01825.444,E,0.0,0.0,010125,,,D")
+            .unwrap()
+            .unwrap();
+        assert!((fix.latitude + 33.92487).abs() < 1e-5);
+        assert!((fix.longitude - 18.42407).abs() < 1e-5);
+        assert_eq!(
+            (fix.coordinate.zone_num, fix.coordinate.zone_letter),
+            (34, 'H')
+        );
+        assert!((fix.coordinate.easting - 261878.).abs() < 2.);
+        assert_eq!(fix.time.second, 59.5);
+        assert_eq!(fix.quality.fix, FixQuality::Differential);
+        assert_eq!(
+            fix.date,
+            Some(Date {
+                year: 2025,
+                month: 1,
+                day: 1
+            })
+        );
+        let fix = pipeline
+            .push(&gga("4500.000,N", "07500.000,W"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(fix.longitude, -75.);
+        assert_eq!(fix.date.unwrap().year, 2025);
+        assert_eq!(
+            pipeline.push(&gga("8500.000,N", "00000.000,E")),
+            Err(Error::OutsideUtm)
+        );
+        assert_eq!(
+            pipeline.push(&gga("4565.000,N", "00000.000,E")),
+            Err(Error::Malformed)
+        );
+        assert_eq!(pipeline.push("GPGGA,"), Err(Error::Malformed));
+        assert_eq!(pipeline.push("$GPGGA,1,2"), Err(Error::Malformed));
+    }
+}
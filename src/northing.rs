@@ -0,0 +1,265 @@
+//! Southern hemisphere northings with or without the false northing.
+//!
+//! Standard UTM adds a false northing of 10,000,000 m in the southern hemisphere so northings
+//! are never negative. Some toolchains leave it out and exchange southern northings as negative
+//! distances from the equator instead. Nothing in a northing says which convention it is in, so
+//! the functions here take a `Convention` rather than guessing.
+//!
+//! A band letter does say which northings are plausible, though. `normalize` uses it to bring a
+//! northing in either convention into the standard range for its band, and `check_hemisphere`
+//! to catch records whose hemisphere flag disagrees with their band and northing.
+//!
+//! The conversions use the classic series on standard UTM, whose false northing is the one being
+//! converted, even if a global converter with other constants was set with
+//! `converter::set_global`.
+
+use std::fmt;
+
+use super::band::Band;
+use super::converter::UTM_FALSE_NORTHING_SOUTH;
+#[cfg(feature = "forward")]
+use super::series_to_utm_wgs84;
+use super::Hemisphere;
+#[cfg(feature = "inverse")]
+use super::{series_wsg84_utm_to_lat_lon, WSG84ToLatLonError};
+
+/// How far in meters a northing may be outside of `Band::northing_range` and still count as
+/// inside the band, for the overlap between zones and the widened zones in bands V and X.
+const BAND_MARGIN: f64 = 25000.;
+
+/// Errors that can occur when checking a northing against its band.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The zone letter is not one of C to X, excluding I and O.
+    InvalidBand,
+    /// The northing is outside of the band in either convention.
+    OutOfBand,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidBand => write!(f, "Zone letter out of range, must be between C and X"),
+            Error::OutOfBand => write!(f, "Northing is outside of its band"),
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for Error {}
+
+/// How southern hemisphere northings are written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum Convention {
+    /// With the 10,000,000 m false northing, as everywhere else in this crate.
+    #[default]
+    FalseNorthing,
+    /// Without the false northing, so southern northings are negative.
+    Negative,
+}
+
+/// Converts a northing from one convention to another.
+///
+/// Northern hemisphere northings are the same in both conventions.
+///
+/// # Examples
+///
+/// ```
+/// use utm::northing::{convert, Convention};
+/// use utm::Hemisphere::South;
+/// let negative = convert(6243186., South, Convention::FalseNorthing, Convention::Negative);
+/// assert_eq!(negative, -3756814.);
+/// let standard = convert(negative, South, Convention::Negative, Convention::FalseNorthing);
+/// assert_eq!(standard, 6243186.);
+/// ```
+pub fn convert(northing: f64, hemisphere: Hemisphere, from: Convention, to: Convention) -> f64 {
+    match (hemisphere, from, to) {
+        (Hemisphere::South, Convention::FalseNorthing, Convention::Negative) => {
+            northing - UTM_FALSE_NORTHING_SOUTH
+        }
+        (Hemisphere::South, Convention::Negative, Convention::FalseNorthing) => {
+            northing + UTM_FALSE_NORTHING_SOUTH
+        }
+        _ => northing,
+    }
+}
+
+/// Returns a northing in the standard `FalseNorthing` convention for its band, and the
+/// convention it was written in.
+///
+/// A northing counts as inside a band if it is within 25 km of `Band::northing_range`. Southern
+/// northings that only fit the band once the false northing is added are taken to be in the
+/// `Negative` convention.
+///
+/// # Examples
+///
+/// ```
+/// use utm::northing::{normalize, Convention, Error};
+/// assert_eq!(normalize(6243186., 'H'), Ok((6243186., Convention::FalseNorthing)));
+/// assert_eq!(normalize(-3756814., 'H'), Ok((6243186., Convention::Negative)));
+/// // Band H is 32°S to 40°S, nowhere near 2,000 km from the equator
+/// assert_eq!(normalize(2000000., 'H'), Err(Error::OutOfBand));
+/// ```
+pub fn normalize(northing: f64, zone_letter: char) -> Result<(f64, Convention), Error> {
+    let band = Band::from_letter(zone_letter).ok_or(Error::InvalidBand)?;
+    let (min, max) = band.northing_range();
+    let fits = |northing: f64| (min - BAND_MARGIN..=max + BAND_MARGIN).contains(&northing);
+    if fits(northing) {
+        Ok((northing, Convention::FalseNorthing))
+    } else if band.hemisphere() == Hemisphere::South && fits(northing + UTM_FALSE_NORTHING_SOUTH) {
+        Ok((northing + UTM_FALSE_NORTHING_SOUTH, Convention::Negative))
+    } else {
+        Err(Error::OutOfBand)
+    }
+}
+
+/// Checks a record's hemisphere flag against its band letter and northing.
+///
+/// Returns `None` if they agree, or the hemisphere of the band if the flag is the odd one out.
+/// A northing that fits the band, in either convention, vouches for the band letter. If it
+/// doesn't, there is no telling which of the three is wrong, and this returns
+/// `Error::OutOfBand`.
+///
+/// # Examples
+///
+/// ```
+/// use utm::northing::check_hemisphere;
+/// use utm::Hemisphere;
+/// // Cape Town, flagged as northern
+/// assert_eq!(
+///     check_hemisphere(6243186., 'H', Hemisphere::North),
+///     Ok(Some(Hemisphere::South))
+/// );
+/// assert_eq!(check_hemisphere(6243186., 'H', Hemisphere::South), Ok(None));
+/// ```
+pub fn check_hemisphere(
+    northing: f64,
+    zone_letter: char,
+    hemisphere: Hemisphere,
+) -> Result<Option<Hemisphere>, Error> {
+    normalize(northing, zone_letter)?;
+    let band_hemisphere = if zone_letter >= 'N' {
+        Hemisphere::North
+    } else {
+        Hemisphere::South
+    };
+    if band_hemisphere == hemisphere {
+        Ok(None)
+    } else {
+        Ok(Some(band_hemisphere))
+    }
+}
+
+#[cfg(feature = "forward")]
+/// Converts a latitude and longitude in decimal degrees to UTM coordinates, like
+/// `to_utm_wgs84`, with the northing in the given convention.
+///
+/// # Examples
+///
+/// ```
+/// use utm::northing::{to_utm, Convention};
+/// let (northing, _, _) = to_utm(-33.92487, 18.42406, 34, Convention::Negative);
+/// assert!((northing + 3756814.).abs() < 1.);
+/// ```
+pub fn to_utm(latitude: f64, longitude: f64, zone: u8, convention: Convention) -> (f64, f64, f64) {
+    let (northing, easting, convergence) = series_to_utm_wgs84(latitude, longitude, zone);
+    // The series adds the false northing strictly south of the equator.
+    let hemisphere = if latitude >= 0. {
+        Hemisphere::North
+    } else {
+        Hemisphere::South
+    };
+    (
+        convert(northing, hemisphere, Convention::FalseNorthing, convention),
+        easting,
+        convergence,
+    )
+}
+
+#[cfg(feature = "inverse")]
+/// Converts a UTM coordinate with its northing in the given convention to a latitude and
+/// longitude, like `wsg84_utm_to_lat_lon`.
+///
+/// # Examples
+///
+/// ```
+/// use utm::northing::{to_lat_lon, Convention};
+/// let (latitude, _) = to_lat_lon(261878., -3756814., 34, 'H', Convention::Negative).unwrap();
+/// assert!((latitude + 33.92487).abs() < 1e-4);
+/// ```
+pub fn to_lat_lon(
+    easting: f64,
+    northing: f64,
+    zone_num: u8,
+    zone_letter: char,
+    convention: Convention,
+) -> Result<(f64, f64), WSG84ToLatLonError> {
+    let hemisphere = if zone_letter >= 'N' {
+        Hemisphere::North
+    } else {
+        Hemisphere::South
+    };
+    let northing = convert(northing, hemisphere, convention, Convention::FalseNorthing);
+    series_wsg84_utm_to_lat_lon(easting, northing, zone_num, zone_letter)
+}
+
+#[cfg(all(test, feature = "forward", feature = "inverse"))]
+mod tests {
+    use super::*;
+    use {lat_lon_to_zone_number, lat_to_zone_letter};
+
+    #[test]
+    fn round_trip() {
+        for &(latitude, longitude) in &[(-33.92487, 18.42406), (-0.5, 1.), (45., 9.)] {
+            let zone = lat_lon_to_zone_number(latitude, longitude);
+            let letter = lat_to_zone_letter(latitude).unwrap();
+            let (standard, _, _) = to_utm(latitude, longitude, zone, Convention::FalseNorthing);
+            let (negative, easting, _) = to_utm(latitude, longitude, zone, Convention::Negative);
+            assert!(standard >= 0.);
+            assert_eq!(negative < 0., latitude < 0.);
+            let (lat, lon) =
+                to_lat_lon(easting, negative, zone, letter, Convention::Negative).unwrap();
+            assert!((lat - latitude).abs() < 1e-5 && (lon - longitude).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn north_is_unchanged() {
+        assert_eq!(
+            convert(
+                4000000.,
+                Hemisphere::North,
+                Convention::Negative,
+                Convention::FalseNorthing
+            ),
+            4000000.
+        );
+    }
+
+    #[test]
+    fn normalize_every_band() {
+        for band in ::band::bands() {
+            let latitude = (band.min_latitude + band.max_latitude) / 2.;
+            let (northing, _, _) = to_utm(latitude, 15., 33, Convention::FalseNorthing);
+            let (negative, _, _) = to_utm(latitude, 15., 33, Convention::Negative);
+            assert_eq!(
+                normalize(northing, band.letter),
+                Ok((northing, Convention::FalseNorthing))
+            );
+            let expected = if band.hemisphere() == Hemisphere::South {
+                Convention::Negative
+            } else {
+                Convention::FalseNorthing
+            };
+            let (normalized, convention) = normalize(negative, band.letter).unwrap();
+            assert!((normalized - northing).abs() < 1e-6);
+            assert_eq!(convention, expected);
+        }
+        assert_eq!(normalize(0., 'I'), Err(Error::InvalidBand));
+        assert_eq!(normalize(-1000000., 'N'), Err(Error::OutOfBand));
+        assert_eq!(
+            check_hemisphere(9000000., 'C', Hemisphere::North),
+            Err(Error::OutOfBand)
+        );
+    }
+}
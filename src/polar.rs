@@ -0,0 +1,348 @@
+//! What happens at the poles and at the edges of the latitude bands.
+//!
+//! UTM covers the latitudes from 80°S to 84°N. Every lookup and conversion in this crate uses
+//! the same rules at the edges of that range and of the bands within it:
+//!
+//! | Latitude                 | Band lookups              | `to_utm_wgs84` | `mgrs`, `batch` |
+//! |--------------------------|---------------------------|----------------|-----------------|
+//! | a band edge below 84°N   | the band to the north     | that band      | that band       |
+//! | the equator              | band N, northern          | northing 0     | band N          |
+//! | exactly 84°N             | band X                    | band X         | band X          |
+//! | exactly 80°S             | band C                    | band C         | band C          |
+//! | beyond 84°N or 80°S      | none                      | unchecked      | error           |
+//! | beyond ±90° or NaN       | none                      | unchecked      | error           |
+//!
+//! `band::lat_to_band` tells polar latitudes apart from invalid ones. Beyond the bands, the
+//! polar caps belong to the Universal Polar Stereographic grid, and `to_grid` projects a point
+//! in UTM or UPS by a `PolarPolicy`, so polar points can be rejected, clamped to the edge of
+//! UTM, or routed to UPS explicitly.
+
+use std::f64::consts::PI;
+use std::fmt;
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use super::band::{self, lat_to_band};
+use super::converter::Converter;
+use super::{UtmCoordinate, WGS84};
+
+/// The UPS scale factor at the pole.
+pub const UPS_SCALE_FACTOR: f64 = 0.994;
+
+/// The UPS false easting and false northing, in meters.
+pub const UPS_FALSE_ORIGIN: f64 = 2000000.;
+
+/// What to do with a latitude outside of the UTM bands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum PolarPolicy {
+    /// Return the `band::Error` for the latitude, wrapped in an `Error`.
+    #[default]
+    Error,
+    /// Move the latitude to 84°N or 80°S and project it in UTM.
+    Clamp,
+    /// Project the latitude in UPS.
+    Ups,
+}
+
+/// Errors that can occur when projecting a point with `to_grid`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The latitude is polar and the policy is `PolarPolicy::Error`, or the latitude is
+    /// invalid.
+    Latitude(band::Error),
+    /// The longitude is not between -180° and 180°, or is not a number.
+    LongitudeOutOfRange,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Latitude(err) => err.fmt(f),
+            Error::LongitudeOutOfRange => {
+                write!(f, "Longitude out of range, must be between -180 and 180")
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for Error {}
+
+impl From<band::Error> for Error {
+    fn from(err: band::Error) -> Error {
+        Error::Latitude(err)
+    }
+}
+
+/// A Universal Polar Stereographic coordinate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UpsCoordinate {
+    /// The easting in meters.
+    pub easting: f64,
+    /// The northing in meters.
+    pub northing: f64,
+    /// The zone letter: A or B in the south and Y or Z in the north, for west and east
+    /// longitudes.
+    pub zone_letter: char,
+}
+
+impl UpsCoordinate {
+    /// Projects a latitude and longitude in decimal degrees in UPS.
+    ///
+    /// Latitudes from 0° up are projected on the north pole and the others on the south pole.
+    /// UPS is only defined north of 84°N and south of 80°S, with some overlap into UTM, but the
+    /// projection is conformal everywhere in its hemisphere.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::polar::UpsCoordinate;
+    /// let pole = UpsCoordinate::from_lat_lon(90., 0.);
+    /// assert_eq!((pole.easting, pole.northing, pole.zone_letter), (2000000., 2000000., 'Z'));
+    /// let coordinate = UpsCoordinate::from_lat_lon(-85., -60.);
+    /// assert_eq!(coordinate.zone_letter, 'A');
+    /// assert!(coordinate.easting < 2000000. && coordinate.northing > 2000000.);
+    /// ```
+    pub fn from_lat_lon(latitude: f64, longitude: f64) -> UpsCoordinate {
+        let north = latitude >= 0.;
+        let phi = latitude.abs().to_radians();
+        let lambda = longitude.to_radians();
+        let e = eccentricity();
+        let sin_phi = phi.sin();
+        let t = (PI / 4. - phi / 2.).tan() / ((1. - e * sin_phi) / (1. + e * sin_phi)).powf(e / 2.);
+        let rho = 2. * WGS84.a * UPS_SCALE_FACTOR * t / c(e);
+        let (dx, dy) = (rho * lambda.sin(), rho * lambda.cos());
+        let zone_letter = match (north, longitude < 0.) {
+            (true, true) => 'Y',
+            (true, false) => 'Z',
+            (false, true) => 'A',
+            (false, false) => 'B',
+        };
+        UpsCoordinate {
+            easting: UPS_FALSE_ORIGIN + dx,
+            northing: if north {
+                UPS_FALSE_ORIGIN - dy
+            } else {
+                UPS_FALSE_ORIGIN + dy
+            },
+            zone_letter,
+        }
+    }
+
+    /// Returns true if this coordinate is on the north pole's grid, zones Y and Z.
+    pub fn is_north(&self) -> bool {
+        self.zone_letter >= 'Y'
+    }
+
+    /// Converts this coordinate to a latitude and longitude in decimal degrees.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::polar::UpsCoordinate;
+    /// let coordinate = UpsCoordinate::from_lat_lon(87.5, 135.);
+    /// let (latitude, longitude) = coordinate.to_lat_lon();
+    /// assert!((latitude - 87.5).abs() < 1e-9 && (longitude - 135.).abs() < 1e-9);
+    /// ```
+    pub fn to_lat_lon(&self) -> (f64, f64) {
+        let dx = self.easting - UPS_FALSE_ORIGIN;
+        let dy = self.northing - UPS_FALSE_ORIGIN;
+        let e = eccentricity();
+        let rho = dx.hypot(dy);
+        let t = rho * c(e) / (2. * WGS84.a * UPS_SCALE_FACTOR);
+        // The conformal latitude is a fine start, and five fixed-point steps reach full
+        // precision for an eccentricity this small.
+        let mut phi = PI / 2. - 2. * t.atan();
+        for _ in 0..5 {
+            let sin_phi = phi.sin();
+            phi =
+                PI / 2. - 2. * (t * ((1. - e * sin_phi) / (1. + e * sin_phi)).powf(e / 2.)).atan();
+        }
+        if self.is_north() {
+            (phi.to_degrees(), dx.atan2(-dy).to_degrees())
+        } else {
+            (-phi.to_degrees(), dx.atan2(dy).to_degrees())
+        }
+    }
+}
+
+/// A coordinate on either the UTM or the UPS grid, returned by `to_grid`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Grid {
+    /// A UTM coordinate.
+    Utm(UtmCoordinate),
+    /// A UPS coordinate.
+    Ups(UpsCoordinate),
+}
+
+/// Projects a latitude and longitude in decimal degrees in UTM, handling latitudes beyond the
+/// UTM bands by a `PolarPolicy`.
+///
+/// UTM coordinates are projected with `converter::Converter`. Latitudes beyond ±90° or NaN, and
+/// longitudes outside of -180° to 180°, are always an error.
+///
+/// # Examples
+///
+/// ```
+/// use utm::band;
+/// use utm::polar::{to_grid, Error, Grid, PolarPolicy};
+/// let err = to_grid(85., 10., PolarPolicy::Error).unwrap_err();
+/// assert_eq!(err, Error::Latitude(band::Error::NorthPolar));
+/// match to_grid(85., 10., PolarPolicy::Clamp).unwrap() {
+///     Grid::Utm(coordinate) => assert_eq!(coordinate.zone_letter, 'X'),
+///     Grid::Ups(_) => unreachable!(),
+/// }
+/// match to_grid(85., 10., PolarPolicy::Ups).unwrap() {
+///     Grid::Ups(coordinate) => assert_eq!(coordinate.zone_letter, 'Z'),
+///     Grid::Utm(_) => unreachable!(),
+/// }
+/// ```
+pub fn to_grid(latitude: f64, longitude: f64, policy: PolarPolicy) -> Result<Grid, Error> {
+    if !(-180. ..=180.).contains(&longitude) {
+        return Err(Error::LongitudeOutOfRange);
+    }
+    let latitude = match lat_to_band(latitude) {
+        Ok(_) => latitude,
+        Err(band::Error::InvalidLatitude) => return Err(band::Error::InvalidLatitude.into()),
+        Err(err) => match policy {
+            PolarPolicy::Error => return Err(err.into()),
+            PolarPolicy::Clamp => latitude.clamp(-80., 84.),
+            PolarPolicy::Ups => {
+                return Ok(Grid::Ups(UpsCoordinate::from_lat_lon(latitude, longitude)))
+            }
+        },
+    };
+    // The latitude and longitude are in range, so this always converts.
+    Converter::new()
+        .to_utm(latitude, longitude)
+        .map(Grid::Utm)
+        .ok_or(Error::Latitude(band::Error::InvalidLatitude))
+}
+
+/// Returns the first eccentricity of WGS84.
+fn eccentricity() -> f64 {
+    (WGS84.f * (2. - WGS84.f)).sqrt()
+}
+
+/// Returns the constant √((1 + e)^(1 + e) (1 − e)^(1 − e)) of the polar stereographic
+/// projection.
+fn c(e: f64) -> f64 {
+    ((1. + e).powf(1. + e) * (1. - e).powf(1. - e)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use band::band_containing;
+    use batch::zone_letters;
+    use lat_to_zone_letter;
+
+    /// Every band edge, and the values just either side of it.
+    fn edges() -> impl Iterator<Item = f64> {
+        (0..=20)
+            .map(|i| {
+                if i == 20 {
+                    84.
+                } else {
+                    -80. + 8. * f64::from(i)
+                }
+            })
+            .flat_map(|edge| [edge - 1e-9, edge, edge + 1e-9])
+    }
+
+    #[test]
+    fn band_edges_agree() {
+        for latitude in edges() {
+            for &longitude in &[-180., -0.5, 0., 3., 8.9, 179.9] {
+                let letter = lat_to_zone_letter(latitude);
+                assert_eq!(letter, band_containing(latitude).map(|band| band.letter));
+                assert_eq!(letter, lat_to_band(latitude).ok().map(|band| band.letter));
+                let mut batch = [None];
+                zone_letters(&[(latitude, longitude)], &mut batch);
+                assert_eq!(letter, batch[0]);
+                let grid = to_grid(latitude, longitude, PolarPolicy::Error).ok();
+                let coordinate = grid.map(|grid| match grid {
+                    Grid::Utm(coordinate) => coordinate,
+                    Grid::Ups(_) => unreachable!(),
+                });
+                assert_eq!(letter, coordinate.map(|coordinate| coordinate.zone_letter));
+                #[cfg(all(feature = "mgrs", feature = "forward"))]
+                assert_eq!(
+                    letter,
+                    ::mgrs::Mgrs::from_lat_lon(latitude, longitude, 5)
+                        .ok()
+                        .map(|mgrs| mgrs.zone_letter)
+                );
+                #[cfg(feature = "inverse")]
+                if let Some(coordinate) = coordinate {
+                    let (lat, _) = ::wsg84_utm_to_lat_lon(
+                        coordinate.easting,
+                        coordinate.northing,
+                        coordinate.zone_num,
+                        coordinate.zone_letter,
+                    )
+                    .unwrap();
+                    // The series inverse is good to a few tenths of a meter at zone edges.
+                    assert!((lat - latitude).abs() < 1e-5, "{} {}", lat, latitude);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "forward")]
+    #[test]
+    fn equator_is_northern() {
+        let (northing, _, _) = ::to_utm_wgs84(0., 3., 31);
+        assert!(northing.abs() < 1e-6);
+        let coordinate = Converter::new().to_utm(0., 3.).unwrap();
+        assert_eq!(coordinate.zone_letter, 'N');
+        assert!(coordinate.northing.abs() < 1e-6);
+    }
+
+    #[test]
+    fn poles() {
+        for &latitude in &[90., -90.] {
+            assert!(to_grid(latitude, 0., PolarPolicy::Error).is_err());
+            match to_grid(latitude, 45., PolarPolicy::Ups).unwrap() {
+                Grid::Ups(coordinate) => {
+                    assert!((coordinate.easting - UPS_FALSE_ORIGIN).abs() < 1e-6);
+                    assert!((coordinate.northing - UPS_FALSE_ORIGIN).abs() < 1e-6);
+                    let (lat, _) = coordinate.to_lat_lon();
+                    assert!((lat - latitude).abs() < 1e-9);
+                }
+                Grid::Utm(_) => panic!("expected UPS"),
+            }
+            match to_grid(latitude, 0., PolarPolicy::Clamp).unwrap() {
+                Grid::Utm(coordinate) => {
+                    assert_eq!(
+                        coordinate.zone_letter,
+                        if latitude > 0. { 'X' } else { 'C' }
+                    )
+                }
+                Grid::Ups(_) => panic!("expected UTM"),
+            }
+        }
+        for &latitude in &[90.1, -90.1, f64::NAN] {
+            for &policy in &[PolarPolicy::Error, PolarPolicy::Clamp, PolarPolicy::Ups] {
+                assert_eq!(
+                    to_grid(latitude, 0., policy),
+                    Err(Error::Latitude(band::Error::InvalidLatitude))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ups_scale() {
+        // 5° from the pole is about 558.7 km along the meridian, scaled by a little over 0.994.
+        let coordinate = UpsCoordinate::from_lat_lon(85., 0.);
+        assert!((UPS_FALSE_ORIGIN - coordinate.northing - 555400.).abs() < 200.);
+        assert!((coordinate.easting - UPS_FALSE_ORIGIN).abs() < 1e-6);
+        for &(latitude, longitude) in &[(84., -179.), (-80., 179.), (-89.99, -90.), (86., 0.)] {
+            let (lat, lon) = UpsCoordinate::from_lat_lon(latitude, longitude).to_lat_lon();
+            assert!((lat - latitude).abs() < 1e-9 && (lon - longitude).abs() < 1e-9);
+        }
+    }
+}
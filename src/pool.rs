@@ -0,0 +1,214 @@
+//! Projections built on demand for data that spans zones and datums.
+//!
+//! A `workspace::Workspace` builds all 120 projections of one ellipsoid up front. A service
+//! ingesting worldwide data on several datums would need one per datum and would mostly touch
+//! a handful of zones. A `ConverterPool` builds the projection of a zone, hemisphere, and datum
+//! the first time a point needs it and keeps it for every later point, so after warming up
+//! each conversion is only the series.
+//!
+//! Points are projected on their own datum's ellipsoid without any datum shift, e.g. NAD27
+//! latitudes and longitudes to NAD27 UTM coordinates. Transform them first with
+//! `datum::DatumTag::to_wgs84` to get WGS84 UTM coordinates instead.
+
+#[cfg(feature = "no_std")]
+use alloc::{vec, vec::Vec};
+
+use super::converter::Converter;
+use super::datum::DatumTag;
+use super::tm::TransverseMercator;
+use super::{lat_lon_to_zone_number, lat_to_zone_letter, UtmCoordinate};
+#[cfg(feature = "inverse")]
+use super::{WSG84ToLatLonError, ZONE_LETTERS};
+
+/// The number of `DatumTag` variants.
+const DATUMS: usize = 4;
+
+/// Lazily built projections for every zone, hemisphere, and datum.
+///
+/// # Examples
+///
+/// ```
+/// use utm::datum::DatumTag;
+/// use utm::pool::ConverterPool;
+/// let mut pool = ConverterPool::new();
+/// let points = [
+///     (-33.92487, 18.42406, DatumTag::Wgs84),
+///     (39.224079, -98.541807, DatumTag::Nad27),
+///     (-33.9, 18.5, DatumTag::Wgs84),
+/// ];
+/// for &(latitude, longitude, datum) in &points {
+///     pool.to_utm(latitude, longitude, datum).unwrap();
+/// }
+/// assert_eq!(pool.len(), 2);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConverterPool {
+    converter: Converter,
+    projections: Vec<Option<TransverseMercator>>,
+}
+
+impl ConverterPool {
+    /// Creates an empty pool for the standard UTM grid constants.
+    pub fn new() -> ConverterPool {
+        ConverterPool::with_converter(Converter::new())
+    }
+
+    /// Creates an empty pool for a converter's grid constants.
+    ///
+    /// The converter's ellipsoid is replaced by each datum's.
+    pub fn with_converter(converter: Converter) -> ConverterPool {
+        ConverterPool {
+            converter,
+            projections: vec![None; DATUMS * 120],
+        }
+    }
+
+    /// Returns the converter this pool was built from.
+    pub fn converter(&self) -> Converter {
+        self.converter
+    }
+
+    /// Returns the number of projections built so far.
+    pub fn len(&self) -> usize {
+        self.projections.iter().filter(|p| p.is_some()).count()
+    }
+
+    /// Returns true if no projection has been built yet.
+    pub fn is_empty(&self) -> bool {
+        self.projections.iter().all(|p| p.is_none())
+    }
+
+    /// Returns the projection of a zone in one hemisphere on a datum, building it if this is
+    /// the first time it's needed, or `None` if the zone is not between 1 and 60.
+    pub fn projection(
+        &mut self,
+        zone_num: u8,
+        northern: bool,
+        datum: DatumTag,
+    ) -> Option<&TransverseMercator> {
+        if !(1..=60).contains(&zone_num) {
+            return None;
+        }
+        let index =
+            datum_index(datum) * 120 + 2 * usize::from(zone_num - 1) + usize::from(!northern);
+        let converter = self.converter.with_ellipsoid(datum.ellipsoid());
+        let projection =
+            self.projections[index].get_or_insert_with(|| converter.projection(zone_num, northern));
+        Some(projection)
+    }
+
+    /// Converts a latitude and longitude in decimal degrees on a datum to a coordinate in its
+    /// own zone and band, like `Converter::to_utm`.
+    pub fn to_utm(
+        &mut self,
+        latitude: f64,
+        longitude: f64,
+        datum: DatumTag,
+    ) -> Option<UtmCoordinate> {
+        if !(-180. ..=180.).contains(&longitude) {
+            return None;
+        }
+        let zone_letter = lat_to_zone_letter(latitude)?;
+        let zone_num = lat_lon_to_zone_number(latitude, longitude);
+        let (northing, easting, _) = self
+            .projection(zone_num, zone_letter >= 'N', datum)?
+            .forward(latitude, longitude);
+        Some(UtmCoordinate::new(easting, northing, zone_num, zone_letter))
+    }
+
+    #[cfg(feature = "inverse")]
+    /// Converts a coordinate on a datum to a latitude and longitude in decimal degrees on the
+    /// same datum, like `Converter::to_lat_lon`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::datum::DatumTag;
+    /// use utm::pool::ConverterPool;
+    /// let mut pool = ConverterPool::new();
+    /// let coordinate = pool.to_utm(39.224079, -98.541807, DatumTag::Nad27).unwrap();
+    /// let (latitude, longitude) = pool.to_lat_lon(&coordinate, DatumTag::Nad27).unwrap();
+    /// assert!((latitude - 39.224079).abs() < 1e-9 && (longitude + 98.541807).abs() < 1e-9);
+    /// assert_eq!(pool.len(), 1);
+    /// ```
+    pub fn to_lat_lon(
+        &mut self,
+        coordinate: &UtmCoordinate,
+        datum: DatumTag,
+    ) -> Result<(f64, f64), WSG84ToLatLonError> {
+        if !ZONE_LETTERS.contains(coordinate.zone_letter) {
+            return Err(WSG84ToLatLonError::ZoneLetterOutOfRange);
+        }
+        let projection = self
+            .projection(coordinate.zone_num, coordinate.zone_letter >= 'N', datum)
+            .ok_or(WSG84ToLatLonError::ZoneNumOutOfRange)?;
+        Ok(projection.inverse(coordinate.easting, coordinate.northing))
+    }
+
+    /// Converts latitudes and longitudes in decimal degrees, each on its own datum, to
+    /// coordinates in their own zones and returns how many were written.
+    ///
+    /// Entries that `to_utm` can't convert are written as `None`.
+    pub fn to_utm_batch(
+        &mut self,
+        points: &[(f64, f64, DatumTag)],
+        coordinates: &mut [Option<UtmCoordinate>],
+    ) -> usize {
+        let len = points.len().min(coordinates.len());
+        for (&(latitude, longitude, datum), coordinate) in points.iter().zip(coordinates.iter_mut())
+        {
+            *coordinate = self.to_utm(latitude, longitude, datum);
+        }
+        len
+    }
+}
+
+impl Default for ConverterPool {
+    fn default() -> ConverterPool {
+        ConverterPool::new()
+    }
+}
+
+/// Returns the position of a datum in the pool.
+fn datum_index(datum: DatumTag) -> usize {
+    match datum {
+        DatumTag::Wgs84 => 0,
+        DatumTag::Nad83 => 1,
+        DatumTag::Nad27 => 2,
+        DatumTag::Ed50 => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use workspace::Workspace;
+
+    #[test]
+    fn matches_workspace() {
+        let workspace = Workspace::new();
+        let mut pool = ConverterPool::new();
+        assert!(pool.is_empty());
+        let points = [
+            (-33.92487, 18.42406, DatumTag::Wgs84),
+            (60.5, 5.5, DatumTag::Wgs84),
+            (85., 0., DatumTag::Wgs84),
+            (48.8, 2.3, DatumTag::Ed50),
+        ];
+        let mut coordinates = [None; 4];
+        assert_eq!(pool.to_utm_batch(&points, &mut coordinates), 4);
+        assert_eq!(coordinates[0], workspace.to_utm(-33.92487, 18.42406));
+        assert_eq!(coordinates[1].unwrap().zone_num, 32);
+        assert_eq!(coordinates[2], None);
+        // Same zone and band, other ellipsoid
+        let wgs84 = workspace.to_utm(48.8, 2.3).unwrap();
+        let ed50 = coordinates[3].unwrap();
+        assert_eq!(
+            (ed50.zone_num, ed50.zone_letter),
+            (wgs84.zone_num, wgs84.zone_letter)
+        );
+        assert!((ed50.northing - wgs84.northing).abs() > 1.);
+        assert_eq!(pool.len(), 3);
+        assert!(pool.projection(0, true, DatumTag::Wgs84).is_none());
+    }
+}
@@ -0,0 +1,278 @@
+//! One rounding policy for every textual output of an application.
+//!
+//! A `Precision` says how finely eastings and northings are written and whether they are rounded
+//! to the nearest step or floored to the step below. Formatting with `UtmCoordinate::rounded`
+//! and encoding with `Mgrs::from_utm_with` both take one, so coordinates written by different
+//! parts of a program agree to the last digit.
+//!
+//! `Precision::round_trip` picks the coarsest precision that still gives back the same latitude
+//! and longitude, within a tolerance, once the text is parsed and converted again, so files don't
+//! carry digits nobody needs.
+
+use std::fmt;
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use super::UtmCoordinate;
+#[cfg(feature = "inverse")]
+use super::WSG84ToLatLonError;
+
+/// The coarsest number of decimals `Precision::round_trip` tries, hundreds of kilometers.
+#[cfg(feature = "inverse")]
+const ROUND_TRIP_MIN_DECIMALS: i8 = -5;
+
+/// The finest number of decimals `Precision::round_trip` tries, nanometers. Eastings and
+/// northings have no more digits than that to give.
+#[cfg(feature = "inverse")]
+const ROUND_TRIP_MAX_DECIMALS: i8 = 9;
+
+/// How a value is brought onto a step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum Rounding {
+    /// To the nearest step, with halfway values rounded away from zero.
+    #[default]
+    Nearest,
+    /// To the step at or below the value. This is how MGRS references are truncated.
+    Floor,
+}
+
+/// A quantization step and rounding rule for coordinates in meters.
+///
+/// # Examples
+///
+/// ```
+/// use utm::precision::{Precision, Rounding};
+/// assert_eq!(Precision::CENTIMETER.quantize(0.456), 0.46);
+/// let floor = Precision::CENTIMETER.with_rounding(Rounding::Floor);
+/// assert_eq!(floor.quantize(0.456), 0.45);
+/// assert_eq!(Precision::new(-1, Rounding::Nearest).quantize(1234.), 1230.);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Precision {
+    decimals: i8,
+    rounding: Rounding,
+}
+
+impl Precision {
+    /// Whole meters, rounded to the nearest.
+    pub const METER: Precision = Precision::new(0, Rounding::Nearest);
+    /// Centimeters, rounded to the nearest.
+    pub const CENTIMETER: Precision = Precision::new(2, Rounding::Nearest);
+    /// Millimeters, rounded to the nearest.
+    pub const MILLIMETER: Precision = Precision::new(3, Rounding::Nearest);
+
+    /// Creates a precision of `decimals` decimal places of a meter.
+    ///
+    /// Negative values are steps coarser than a meter, e.g. -1 for tens of meters.
+    pub const fn new(decimals: i8, rounding: Rounding) -> Precision {
+        Precision { decimals, rounding }
+    }
+
+    /// Returns this precision with another rounding rule.
+    pub const fn with_rounding(self, rounding: Rounding) -> Precision {
+        Precision {
+            decimals: self.decimals,
+            rounding,
+        }
+    }
+
+    /// Returns the number of decimal places.
+    pub fn decimals(&self) -> i8 {
+        self.decimals
+    }
+
+    /// Returns the rounding rule.
+    pub fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+
+    /// Returns the size of a step in meters.
+    pub fn step(&self) -> f64 {
+        10f64.powi(-i32::from(self.decimals))
+    }
+
+    /// Returns `value` brought onto a step.
+    pub fn quantize(&self, value: f64) -> f64 {
+        let round = |value: f64| {
+            // A decimal like 0.29 isn't exact in binary, so it scales to just under a whole
+            // number. Within rounding error of one, it is that number.
+            let nearest = value.round();
+            if (value - nearest).abs() <= nearest.abs() * f64::EPSILON {
+                return nearest;
+            }
+            match self.rounding {
+                Rounding::Nearest => nearest,
+                Rounding::Floor => value.floor(),
+            }
+        };
+        // Scale by an exact power of ten in whichever direction keeps it a whole number.
+        if self.decimals >= 0 {
+            let scale = 10f64.powi(i32::from(self.decimals));
+            round(value * scale) / scale
+        } else {
+            let scale = self.step();
+            round(value / scale) * scale
+        }
+    }
+
+    #[cfg(feature = "inverse")]
+    /// Returns the coarsest precision, rounded to the nearest, at which `coordinate` can be
+    /// written out and read back without its latitude or longitude moving by more than
+    /// `tolerance` decimal degrees.
+    ///
+    /// The precision is for this coordinate only, since a degree of longitude shrinks towards
+    /// the poles. If even nanometers aren't enough, e.g. for a zero tolerance, nanometers are
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::precision::Precision;
+    /// use utm::UtmCoordinate;
+    /// let coordinate = UtmCoordinate::new(261878.123456, 6243186.654321, 34, 'H');
+    /// // A millionth of a degree is about a decimeter
+    /// let precision = Precision::round_trip(&coordinate, 1e-6).unwrap();
+    /// assert_eq!(precision.decimals(), 1);
+    /// assert_eq!(coordinate.rounded(precision).to_string(), "34H 261878.1 6243186.7");
+    /// ```
+    pub fn round_trip(
+        coordinate: &UtmCoordinate,
+        tolerance: f64,
+    ) -> Result<Precision, WSG84ToLatLonError> {
+        let (latitude, longitude) = coordinate.to_lat_lon()?;
+        for decimals in ROUND_TRIP_MIN_DECIMALS..ROUND_TRIP_MAX_DECIMALS {
+            let precision = Precision::new(decimals, Rounding::Nearest);
+            let rounded = UtmCoordinate {
+                easting: precision.quantize(coordinate.easting),
+                northing: precision.quantize(coordinate.northing),
+                ..*coordinate
+            };
+            // Rounding can push a coordinate out of range, which just means a finer precision.
+            if let Ok((lat, lon)) = rounded.to_lat_lon() {
+                if (lat - latitude).abs() <= tolerance && (lon - longitude).abs() <= tolerance {
+                    return Ok(precision);
+                }
+            }
+        }
+        Ok(Precision::new(ROUND_TRIP_MAX_DECIMALS, Rounding::Nearest))
+    }
+
+    /// Returns the number of MGRS digits for each of the easting and northing that match this
+    /// precision, between 0 and 5.
+    pub fn mgrs_digits(&self) -> u8 {
+        (5 + i32::from(self.decimals)).clamp(0, 5) as u8
+    }
+}
+
+impl Default for Precision {
+    fn default() -> Precision {
+        Precision::METER
+    }
+}
+
+/// A UTM coordinate formatted with a `Precision`, returned by `UtmCoordinate::rounded`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rounded {
+    coordinate: UtmCoordinate,
+    precision: Precision,
+}
+
+impl Rounded {
+    pub(crate) fn new(coordinate: UtmCoordinate, precision: Precision) -> Rounded {
+        Rounded {
+            coordinate,
+            precision,
+        }
+    }
+}
+
+impl fmt::Display for Rounded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let decimals = self.precision.decimals.max(0) as usize;
+        write!(
+            f,
+            "{}{} {:.*} {:.*}",
+            self.coordinate.zone_num,
+            self.coordinate.zone_letter,
+            decimals,
+            self.precision.quantize(self.coordinate.easting),
+            decimals,
+            self.precision.quantize(self.coordinate.northing)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quantize() {
+        let floor = Precision::MILLIMETER.with_rounding(Rounding::Floor);
+        assert_eq!(floor.quantize(261878.9999), 261878.999);
+        let floor = Precision::CENTIMETER.with_rounding(Rounding::Floor);
+        assert_eq!(floor.quantize(0.29), 0.29);
+        assert_eq!(floor.quantize(1.15), 1.15);
+        assert_eq!(floor.quantize(4.35), 4.35);
+        assert_eq!(floor.quantize(4.359), 4.35);
+        assert_eq!(Precision::MILLIMETER.quantize(261878.9999), 261879.);
+        assert_eq!(Precision::METER.quantize(-0.5), -1.);
+        assert_eq!(
+            Precision::METER
+                .with_rounding(Rounding::Floor)
+                .quantize(-0.5),
+            -1.
+        );
+        assert_eq!(
+            Precision::new(-2, Rounding::Floor).quantize(6243186.),
+            6243100.
+        );
+    }
+
+    #[test]
+    fn mgrs_digits() {
+        assert_eq!(Precision::MILLIMETER.mgrs_digits(), 5);
+        assert_eq!(Precision::METER.mgrs_digits(), 5);
+        assert_eq!(Precision::new(-3, Rounding::Floor).mgrs_digits(), 2);
+        assert_eq!(Precision::new(-7, Rounding::Floor).mgrs_digits(), 0);
+    }
+
+    #[cfg(all(feature = "inverse", feature = "parse"))]
+    #[test]
+    fn round_trip() {
+        let coordinate = UtmCoordinate::new(315000.237891, 5741000.512345, 33, 'T');
+        let (latitude, longitude) = coordinate.to_lat_lon().unwrap();
+        let mut previous = ROUND_TRIP_MIN_DECIMALS;
+        for &tolerance in &[1e-2, 1e-4, 1e-6, 1e-8] {
+            let precision = Precision::round_trip(&coordinate, tolerance).unwrap();
+            assert!(precision.decimals() >= previous);
+            previous = precision.decimals();
+            let parsed: UtmCoordinate = coordinate.rounded(precision).to_string().parse().unwrap();
+            let (lat, lon) = parsed.to_lat_lon().unwrap();
+            assert!((lat - latitude).abs() <= tolerance);
+            assert!((lon - longitude).abs() <= tolerance);
+        }
+        assert!(Precision::round_trip(&UtmCoordinate::new(0., 0., 33, 'T'), 1.).is_err());
+    }
+
+    #[test]
+    fn display() {
+        let coordinate = UtmCoordinate::new(261878.456, 6243186.5, 34, 'H');
+        assert_eq!(
+            coordinate.rounded(Precision::METER).to_string(),
+            "34H 261878 6243187"
+        );
+        assert_eq!(
+            coordinate
+                .rounded(Precision::METER.with_rounding(Rounding::Floor))
+                .to_string(),
+            "34H 261878 6243186"
+        );
+        assert_eq!(
+            coordinate.rounded(Precision::CENTIMETER).to_string(),
+            "34H 261878.46 6243186.50"
+        );
+    }
+}
@@ -0,0 +1,426 @@
+//! Read the ESRI `.prj` files that sit next to shapefiles.
+//!
+//! A `.prj` file holds a single ESRI flavoured WKT1 coordinate system definition, e.g.
+//! `PROJCS["WGS_1984_UTM_Zone_33N",GEOGCS[...],PROJECTION["Transverse_Mercator"],...]`.
+//! `parse` recognizes transverse Mercator definitions, including every UTM zone, and returns a
+//! `Prj` that converts in the file's own linear unit.
+
+use std::fmt;
+use std::str::Chars;
+
+#[cfg(feature = "no_std")]
+use alloc::string::{String, ToString};
+#[cfg(feature = "no_std")]
+use alloc::vec::Vec;
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use super::tm::TransverseMercator;
+use super::{Ellipsoid, Hemisphere};
+
+/// The projection names that are read as a transverse Mercator.
+const TRANSVERSE_MERCATOR: [&str; 2] = ["transverse_mercator", "gauss_kruger"];
+
+/// Errors that can occur when reading a `.prj` file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The text isn't well-formed WKT.
+    Syntax,
+    /// The definition is a geographic coordinate system, not a projected one.
+    NotProjected,
+    /// The projection isn't a transverse Mercator.
+    UnsupportedProjection,
+    /// A required element or parameter is missing, or isn't a number.
+    Missing(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Syntax => write!(f, "Malformed WKT"),
+            Error::NotProjected => write!(f, "Not a projected coordinate system"),
+            Error::UnsupportedProjection => write!(f, "Only transverse Mercator is supported"),
+            Error::Missing(name) => write!(f, "Missing or invalid {}", name),
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for Error {}
+
+/// A transverse Mercator coordinate system read from a `.prj` file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Prj {
+    name: String,
+    datum: String,
+    projection: TransverseMercator,
+    meters_per_unit: f64,
+}
+
+impl Prj {
+    /// Returns the name of the coordinate system, e.g. `WGS_1984_UTM_Zone_33N`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the name of the datum, e.g. `D_WGS_1984`.
+    ///
+    /// The projection always works on the latitudes and longitudes of this datum, no datum
+    /// shift is applied.
+    pub fn datum(&self) -> &str {
+        &self.datum
+    }
+
+    /// Returns the projection, which works in meters.
+    pub fn projection(&self) -> TransverseMercator {
+        self.projection
+    }
+
+    /// Returns the length of the file's linear unit in meters, e.g. 0.3048 for international
+    /// feet.
+    pub fn meters_per_unit(&self) -> f64 {
+        self.meters_per_unit
+    }
+
+    /// Returns the UTM zone and hemisphere if this is a UTM coordinate system, judging by its
+    /// parameters rather than its name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::prj::parse;
+    /// use utm::Hemisphere;
+    /// let prj = parse(r#"PROJCS["WGS_1984_UTM_Zone_34S",GEOGCS["GCS_WGS_1984",DATUM["D_WGS_1984",SPHEROID["WGS_1984",6378137.0,298.257223563]],PRIMEM["Greenwich",0.0],UNIT["Degree",0.0174532925199433]],PROJECTION["Transverse_Mercator"],PARAMETER["False_Easting",500000.0],PARAMETER["False_Northing",10000000.0],PARAMETER["Central_Meridian",21.0],PARAMETER["Scale_Factor",0.9996],PARAMETER["Latitude_Of_Origin",0.0],UNIT["Meter",1.0]]"#).unwrap();
+    /// assert_eq!(prj.utm_zone(), Some((34, Hemisphere::South)));
+    /// ```
+    pub fn utm_zone(&self) -> Option<(u8, Hemisphere)> {
+        let projection = &self.projection;
+        let zone = (projection.central_meridian() + 183.) / 6.;
+        let hemisphere = if projection.false_northing() == 0. {
+            Hemisphere::North
+        } else if (projection.false_northing() - 10000000.).abs() < 1e-6 {
+            Hemisphere::South
+        } else {
+            return None;
+        };
+        if projection.scale_factor() == 0.9996
+            && projection.latitude_of_origin() == 0.
+            && (projection.false_easting() - 500000.).abs() < 1e-6
+            && (zone - zone.round()).abs() < 1e-9
+            && (1. ..=60.).contains(&zone)
+        {
+            Some((zone.round() as u8, hemisphere))
+        } else {
+            None
+        }
+    }
+
+    /// Converts a latitude and longitude in decimal degrees to the file's coordinates.
+    ///
+    /// Returns the northing, easting, and meridian convergence, like `TransverseMercator::forward`,
+    /// with the northing and easting in the file's linear unit.
+    pub fn forward(&self, latitude: f64, longitude: f64) -> (f64, f64, f64) {
+        let (northing, easting, convergence) = self.projection.forward(latitude, longitude);
+        (
+            northing / self.meters_per_unit,
+            easting / self.meters_per_unit,
+            convergence,
+        )
+    }
+
+    #[cfg(feature = "inverse")]
+    /// Converts an easting and northing in the file's linear unit to a latitude and longitude
+    /// in decimal degrees.
+    pub fn inverse(&self, easting: f64, northing: f64) -> (f64, f64) {
+        self.projection.inverse(
+            easting * self.meters_per_unit,
+            northing * self.meters_per_unit,
+        )
+    }
+}
+
+/// Parses the contents of a `.prj` file.
+///
+/// The spheroid, prime meridian, and linear unit all come from the file. Parameters are read in
+/// the geographic coordinate system's angular unit and the projected linear unit, and keywords
+/// and parameter names are matched without regard to case.
+///
+/// # Examples
+///
+/// ```
+/// use utm::prj::parse;
+/// let wkt = r#"PROJCS["WGS_1984_UTM_Zone_33N",GEOGCS["GCS_WGS_1984",DATUM["D_WGS_1984",SPHEROID["WGS_1984",6378137.0,298.257223563]],PRIMEM["Greenwich",0.0],UNIT["Degree",0.0174532925199433]],PROJECTION["Transverse_Mercator"],PARAMETER["False_Easting",500000.0],PARAMETER["False_Northing",0.0],PARAMETER["Central_Meridian",15.0],PARAMETER["Scale_Factor",0.9996],PARAMETER["Latitude_Of_Origin",0.0],UNIT["Meter",1.0]]"#;
+/// let prj = parse(wkt).unwrap();
+/// assert_eq!(prj.name(), "WGS_1984_UTM_Zone_33N");
+/// let (northing, easting, _) = prj.forward(51.8, 14.9);
+/// # #[cfg(feature = "forward")] {
+/// let (expected_northing, expected_easting, _) = utm::to_utm_wgs84(51.8, 14.9, 33);
+/// assert!((northing - expected_northing).abs() < 0.01);
+/// assert!((easting - expected_easting).abs() < 0.01);
+/// # }
+/// ```
+pub fn parse(wkt: &str) -> Result<Prj, Error> {
+    let mut parser = Parser {
+        chars: wkt.chars(),
+        peeked: None,
+    };
+    let root = parser.node()?;
+    parser.skip_whitespace();
+    if parser.peek().is_some() {
+        return Err(Error::Syntax);
+    }
+    if root.is("geogcs") {
+        return Err(Error::NotProjected);
+    }
+    if !root.is("projcs") {
+        return Err(Error::Syntax);
+    }
+
+    let geogcs = root.child("geogcs").ok_or(Error::Missing("GEOGCS"))?;
+    let datum = geogcs.child("datum").ok_or(Error::Missing("DATUM"))?;
+    let spheroid = datum.child("spheroid").ok_or(Error::Missing("SPHEROID"))?;
+    let a = spheroid.number(1).ok_or(Error::Missing("SPHEROID"))?;
+    let inverse_flattening = spheroid.number(2).ok_or(Error::Missing("SPHEROID"))?;
+    let ellipsoid = if inverse_flattening == 0. {
+        Ellipsoid::new(a, 0.)
+    } else {
+        Ellipsoid::new(a, 1. / inverse_flattening)
+    };
+    // Angular parameters are in the unit of the geographic coordinate system, usually degrees.
+    let degrees_per_unit = match geogcs.child("unit") {
+        Some(unit) => unit.number(1).ok_or(Error::Missing("UNIT"))?.to_degrees(),
+        None => 1.,
+    };
+    let prime_meridian = match geogcs.child("primem") {
+        Some(primem) => primem.number(1).ok_or(Error::Missing("PRIMEM"))? * degrees_per_unit,
+        None => 0.,
+    };
+
+    let projection = root
+        .child("projection")
+        .and_then(|projection| projection.text(0))
+        .ok_or(Error::Missing("PROJECTION"))?;
+    if !TRANSVERSE_MERCATOR
+        .iter()
+        .any(|name| projection.eq_ignore_ascii_case(name))
+    {
+        return Err(Error::UnsupportedProjection);
+    }
+    let meters_per_unit = match root.child("unit") {
+        Some(unit) => unit.number(1).ok_or(Error::Missing("UNIT"))?,
+        None => 1.,
+    };
+    let parameter = |name: &'static str| -> Result<f64, Error> {
+        root.values
+            .iter()
+            .filter_map(|value| match value {
+                Value::Node(node) if node.is("parameter") => Some(node),
+                _ => None,
+            })
+            .find(|node| {
+                node.text(0)
+                    .is_some_and(|text| text.eq_ignore_ascii_case(name))
+            })
+            .and_then(|node| node.number(1))
+            .ok_or(Error::Missing(name))
+    };
+    let projection = TransverseMercator::new(
+        ellipsoid,
+        parameter("Latitude_Of_Origin")? * degrees_per_unit,
+        parameter("Central_Meridian")? * degrees_per_unit + prime_meridian,
+        parameter("Scale_Factor")?,
+        parameter("False_Easting")? * meters_per_unit,
+        parameter("False_Northing")? * meters_per_unit,
+    );
+    Ok(Prj {
+        name: root.text(0).unwrap_or_default().to_string(),
+        datum: datum.text(0).unwrap_or_default().to_string(),
+        projection,
+        meters_per_unit,
+    })
+}
+
+/// A WKT element, e.g. `UNIT["Meter",1.0]`.
+struct Node {
+    keyword: String,
+    values: Vec<Value>,
+}
+
+enum Value {
+    Text(String),
+    Number(f64),
+    Node(Node),
+}
+
+impl Node {
+    fn is(&self, keyword: &str) -> bool {
+        self.keyword.eq_ignore_ascii_case(keyword)
+    }
+
+    fn child(&self, keyword: &str) -> Option<&Node> {
+        self.values.iter().find_map(|value| match value {
+            Value::Node(node) if node.is(keyword) => Some(node),
+            _ => None,
+        })
+    }
+
+    fn text(&self, index: usize) -> Option<&str> {
+        match self.values.get(index) {
+            Some(Value::Text(text)) => Some(text),
+            _ => None,
+        }
+    }
+
+    fn number(&self, index: usize) -> Option<f64> {
+        match self.values.get(index) {
+            Some(Value::Number(number)) => Some(*number),
+            _ => None,
+        }
+    }
+}
+
+struct Parser<'a> {
+    chars: Chars<'a>,
+    peeked: Option<char>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&mut self) -> Option<char> {
+        if self.peeked.is_none() {
+            self.peeked = self.chars.next();
+        }
+        self.peeked
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.peeked = None;
+        c
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.next();
+        }
+    }
+
+    /// Reads characters while `predicate` holds.
+    fn take_while<F: Fn(char) -> bool>(&mut self, predicate: F) -> String {
+        let mut s = String::new();
+        while let Some(c) = self.peek().filter(|&c| predicate(c)) {
+            s.push(c);
+            self.next();
+        }
+        s
+    }
+
+    fn node(&mut self) -> Result<Node, Error> {
+        self.skip_whitespace();
+        let keyword = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+        if keyword.is_empty() {
+            return Err(Error::Syntax);
+        }
+        self.skip_whitespace();
+        let close = match self.next() {
+            Some('[') => ']',
+            Some('(') => ')',
+            _ => return Err(Error::Syntax),
+        };
+        let mut values = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let value = match self.peek() {
+                Some('"') => {
+                    self.next();
+                    let text = self.take_while(|c| c != '"');
+                    if self.next() != Some('"') {
+                        return Err(Error::Syntax);
+                    }
+                    Value::Text(text)
+                }
+                Some(c) if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' => {
+                    let number = self.take_while(|c| {
+                        c.is_ascii_digit()
+                            || c == '-'
+                            || c == '+'
+                            || c == '.'
+                            || c == 'e'
+                            || c == 'E'
+                    });
+                    Value::Number(number.parse().map_err(|_| Error::Syntax)?)
+                }
+                Some(c) if c.is_ascii_alphabetic() => {
+                    let node = self.node()?;
+                    Value::Node(node)
+                }
+                _ => return Err(Error::Syntax),
+            };
+            values.push(value);
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => {}
+                Some(c) if c == close => return Ok(Node { keyword, values }),
+                _ => return Err(Error::Syntax),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datum::GRS80;
+
+    const STATE_PLANE: &str = r#"PROJCS["NAD_1983_StatePlane_Illinois_East_FIPS_1201_Feet",
+        GEOGCS["GCS_North_American_1983",
+            DATUM["D_North_American_1983",SPHEROID["GRS_1980",6378137.0,298.257222101]],
+            PRIMEM["Greenwich",0.0],UNIT["Degree",0.0174532925199433]],
+        PROJECTION["Transverse_Mercator"],
+        PARAMETER["False_Easting",984250.0],PARAMETER["False_Northing",0.0],
+        PARAMETER["Central_Meridian",-88.33333333333333],PARAMETER["Scale_Factor",0.999975],
+        PARAMETER["Latitude_Of_Origin",36.66666666666666],UNIT["Foot_US",0.3048006096012192]]"#;
+
+    #[test]
+    fn state_plane_in_feet() {
+        let prj = parse(STATE_PLANE).unwrap();
+        assert_eq!(prj.datum(), "D_North_American_1983");
+        assert_eq!(prj.utm_zone(), None);
+        let projection = prj.projection();
+        assert_eq!(projection.ellipsoid(), GRS80);
+        assert!((projection.false_easting() - 300000.).abs() < 1e-3);
+        let (northing, easting, _) = prj.forward(41.8781, -87.6298);
+        let (expected_northing, expected_easting, _) = projection.forward(41.8781, -87.6298);
+        assert!((northing * 0.3048006096012192 - expected_northing).abs() < 1e-6);
+        assert!((easting * 0.3048006096012192 - expected_easting).abs() < 1e-6);
+    }
+
+    #[cfg(feature = "inverse")]
+    #[test]
+    fn round_trip() {
+        let prj = parse(STATE_PLANE).unwrap();
+        let (northing, easting, _) = prj.forward(41.8781, -87.6298);
+        let (latitude, longitude) = prj.inverse(easting, northing);
+        assert!((latitude - 41.8781).abs() < 1e-9);
+        assert!((longitude - -87.6298).abs() < 1e-9);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            parse(r#"GEOGCS["GCS_WGS_1984",DATUM["D_WGS_1984",SPHEROID["WGS_1984",6378137.0,298.257223563]]]"#)
+                .unwrap_err(),
+            Error::NotProjected
+        );
+        assert_eq!(
+            parse(&STATE_PLANE.replace("Transverse_Mercator", "Lambert_Conformal_Conic"))
+                .unwrap_err(),
+            Error::UnsupportedProjection
+        );
+        assert_eq!(
+            parse(&STATE_PLANE.replace(r#"PARAMETER["Scale_Factor",0.999975],"#, "")).unwrap_err(),
+            Error::Missing("Scale_Factor")
+        );
+        assert_eq!(parse("PROJCS[\"unterminated").unwrap_err(), Error::Syntax);
+    }
+}
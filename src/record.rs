@@ -0,0 +1,301 @@
+//! A fixed-width binary record format for UTM coordinates.
+//!
+//! Exchanging hundreds of millions of converted points as text spends most of the time parsing
+//! it. Records are fixed width and little-endian, so a file of them can be memory-mapped and
+//! read as an array by numeric tools without any parsing. Each record is `RECORD_LEN` bytes:
+//!
+//! | Offset | Size | Field                                      |
+//! |--------|------|--------------------------------------------|
+//! | 0      | 8    | easting, IEEE 754 binary64, little-endian  |
+//! | 8      | 8    | northing, IEEE 754 binary64, little-endian |
+//! | 16     | 1    | zone number, 1 to 60                       |
+//! | 17     | 1    | zone letter, ASCII `C` to `X`              |
+//! | 18     | 1    | layout version, `VERSION`                  |
+//! | 19     | 5    | reserved, zero                             |
+//!
+//! The reserved bytes keep records eight byte aligned. The layout doesn't depend on the
+//! platform, so firmware and a ground station agree on every bit. The version byte is zero for
+//! this layout, and a later layout will use a new version and keep the zone at the same
+//! offsets, so a reader can tell which one it is looking at and rejects versions it doesn't
+//! know. Zones on their own, e.g. in a telemetry frame header, use the same two bytes as offsets
+//! 16 and 17, see `encode_zone`. There is no header, so a file is just records back to back,
+//! e.g. a NumPy `dtype([("easting", "<f8"), ("northing", "<f8"), ("zone", "u1"), ("letter",
+//! "S1"), ("version", "u1"), ("reserved", "V5")])`.
+//!
+//! `write` and `read` work on caller-provided buffers. With the standard library, `Writer` and
+//! `Reader` stream records through `std::io`.
+
+use std::fmt;
+#[cfg(not(feature = "no_std"))]
+use std::io;
+
+use super::{UtmCoordinate, ZONE_LETTERS};
+
+/// The length of a record in bytes.
+pub const RECORD_LEN: usize = 24;
+
+/// The layout version written to every record.
+pub const VERSION: u8 = 0;
+
+/// The length of an encoded zone in bytes.
+pub const ZONE_LEN: usize = 2;
+
+/// Errors that can occur when reading records.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The zone number of a record is not between 1 and 60.
+    ZoneNumOutOfRange,
+    /// The zone letter of a record is not one of C to X.
+    ZoneLetterOutOfRange,
+    /// The reserved bytes of a record are not zero.
+    Reserved,
+    /// The record has a layout version this crate doesn't know.
+    Version(u8),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::ZoneNumOutOfRange => write!(f, "Record zone num out of range"),
+            Error::ZoneLetterOutOfRange => write!(f, "Record zone letter out of range"),
+            Error::Reserved => write!(f, "Record reserved bytes are not zero"),
+            Error::Version(version) => write!(f, "Unknown record layout version {}", version),
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for Error {}
+
+/// Encodes one coordinate as a record.
+///
+/// # Examples
+///
+/// ```
+/// use utm::record::{decode, encode};
+/// use utm::UtmCoordinate;
+/// let coordinate = UtmCoordinate::new(261878., 6243186., 34, 'H');
+/// let record = encode(&coordinate);
+/// assert_eq!(&record[16..18], &[34, b'H']);
+/// assert_eq!(decode(&record), Ok(coordinate));
+/// ```
+pub fn encode(coordinate: &UtmCoordinate) -> [u8; RECORD_LEN] {
+    let mut record = [0; RECORD_LEN];
+    record[..8].copy_from_slice(&coordinate.easting.to_le_bytes());
+    record[8..16].copy_from_slice(&coordinate.northing.to_le_bytes());
+    record[16..18].copy_from_slice(&encode_zone(coordinate.zone_num, coordinate.zone_letter));
+    record[18] = VERSION;
+    record
+}
+
+/// Decodes one record.
+pub fn decode(record: &[u8; RECORD_LEN]) -> Result<UtmCoordinate, Error> {
+    let mut easting = [0; 8];
+    let mut northing = [0; 8];
+    easting.copy_from_slice(&record[..8]);
+    northing.copy_from_slice(&record[8..16]);
+    if record[18] != VERSION {
+        return Err(Error::Version(record[18]));
+    }
+    let (zone_num, zone_letter) = decode_zone(&[record[16], record[17]])?;
+    if record[19..].iter().any(|&byte| byte != 0) {
+        return Err(Error::Reserved);
+    }
+    Ok(UtmCoordinate::new(
+        f64::from_le_bytes(easting),
+        f64::from_le_bytes(northing),
+        zone_num,
+        zone_letter,
+    ))
+}
+
+/// Encodes a zone number and letter as two bytes, the number and then the ASCII letter.
+///
+/// # Examples
+///
+/// ```
+/// use utm::record::{decode_zone, encode_zone};
+/// assert_eq!(encode_zone(34, 'H'), [34, b'H']);
+/// assert_eq!(decode_zone(&[34, b'H']), Ok((34, 'H')));
+/// ```
+pub fn encode_zone(zone_num: u8, zone_letter: char) -> [u8; ZONE_LEN] {
+    // Letters outside of ASCII can't be valid, and zero never decodes.
+    let letter = if zone_letter.is_ascii() {
+        zone_letter as u8
+    } else {
+        0
+    };
+    [zone_num, letter]
+}
+
+/// Decodes a zone number and letter.
+pub fn decode_zone(bytes: &[u8; ZONE_LEN]) -> Result<(u8, char), Error> {
+    let zone_num = bytes[0];
+    let zone_letter = char::from(bytes[1]);
+    if !(1..=60).contains(&zone_num) {
+        return Err(Error::ZoneNumOutOfRange);
+    }
+    if !ZONE_LETTERS.contains(zone_letter) {
+        return Err(Error::ZoneLetterOutOfRange);
+    }
+    Ok((zone_num, zone_letter))
+}
+
+/// Writes as many coordinates as fit into `output` and returns how many were written.
+///
+/// # Examples
+///
+/// ```
+/// use utm::record::{read, write, RECORD_LEN};
+/// use utm::UtmCoordinate;
+/// let coordinates = [UtmCoordinate::new(261878., 6243186., 34, 'H'); 3];
+/// let mut buffer = [0; 2 * RECORD_LEN];
+/// assert_eq!(write(&coordinates, &mut buffer), 2);
+/// let mut decoded = [UtmCoordinate::new(0., 0., 1, 'C'); 2];
+/// assert_eq!(read(&buffer, &mut decoded), Ok(2));
+/// assert_eq!(decoded[1], coordinates[1]);
+/// ```
+pub fn write(coordinates: &[UtmCoordinate], output: &mut [u8]) -> usize {
+    let mut written = 0;
+    for (coordinate, record) in coordinates.iter().zip(output.chunks_exact_mut(RECORD_LEN)) {
+        record.copy_from_slice(&encode(coordinate));
+        written += 1;
+    }
+    written
+}
+
+/// Reads as many whole records from `input` as fit into `coordinates` and returns how many were
+/// read.
+///
+/// Reading stops at the first invalid record, leaving the outputs from that point on untouched.
+pub fn read(input: &[u8], coordinates: &mut [UtmCoordinate]) -> Result<usize, Error> {
+    let mut read = 0;
+    for (record, coordinate) in input.chunks_exact(RECORD_LEN).zip(coordinates.iter_mut()) {
+        let mut buffer = [0; RECORD_LEN];
+        buffer.copy_from_slice(record);
+        *coordinate = decode(&buffer)?;
+        read += 1;
+    }
+    Ok(read)
+}
+
+#[cfg(not(feature = "no_std"))]
+/// Streams coordinates as records to a writer.
+///
+/// Wrap the writer in a `std::io::BufWriter` if it's unbuffered.
+#[derive(Debug)]
+pub struct Writer<W> {
+    inner: W,
+}
+
+#[cfg(not(feature = "no_std"))]
+impl<W: io::Write> Writer<W> {
+    /// Creates a record writer.
+    pub fn new(inner: W) -> Writer<W> {
+        Writer { inner }
+    }
+
+    /// Writes one coordinate.
+    pub fn write(&mut self, coordinate: &UtmCoordinate) -> io::Result<()> {
+        self.inner.write_all(&encode(coordinate))
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+/// Streams coordinates from a reader of records.
+///
+/// It is an iterator over the coordinates. Invalid records are `InvalidData` errors, and a
+/// stream that ends in the middle of a record is an `UnexpectedEof` error.
+///
+/// # Examples
+///
+/// ```
+/// use utm::record::{Reader, Writer};
+/// use utm::UtmCoordinate;
+/// let mut writer = Writer::new(Vec::new());
+/// writer.write(&UtmCoordinate::new(261878., 6243186., 34, 'H')).unwrap();
+/// writer.write(&UtmCoordinate::new(385273.02, 6761077.2, 6, 'V')).unwrap();
+/// let bytes = writer.into_inner();
+/// let coordinates = Reader::new(&bytes[..])
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// assert_eq!(coordinates.len(), 2);
+/// assert_eq!(coordinates[1].zone_letter, 'V');
+/// ```
+#[derive(Debug)]
+pub struct Reader<R> {
+    inner: R,
+}
+
+#[cfg(not(feature = "no_std"))]
+impl<R: io::Read> Reader<R> {
+    /// Creates a record reader.
+    pub fn new(inner: R) -> Reader<R> {
+        Reader { inner }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl<R: io::Read> Iterator for Reader<R> {
+    type Item = io::Result<UtmCoordinate>;
+
+    fn next(&mut self) -> Option<io::Result<UtmCoordinate>> {
+        let mut record = [0; RECORD_LEN];
+        let mut len = 0;
+        while len < RECORD_LEN {
+            match self.inner.read(&mut record[len..]) {
+                Ok(0) if len == 0 => return None,
+                Ok(0) => return Some(Err(io::ErrorKind::UnexpectedEof.into())),
+                Ok(n) => len += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        Some(decode(&record).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout() {
+        let record = encode(&UtmCoordinate::new(1., -2., 60, 'X'));
+        assert_eq!(&record[..8], &1f64.to_le_bytes());
+        assert_eq!(&record[8..16], &(-2f64).to_le_bytes());
+        assert_eq!(&record[16..], &[60, b'X', 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn invalid_records() {
+        let mut record = encode(&UtmCoordinate::new(261878., 6243186., 34, 'H'));
+        record[17] = b'I';
+        assert_eq!(decode(&record), Err(Error::ZoneLetterOutOfRange));
+        record[16] = 0;
+        assert_eq!(decode(&record), Err(Error::ZoneNumOutOfRange));
+        let mut record = encode(&UtmCoordinate::new(261878., 6243186., 34, 'H'));
+        record[23] = 1;
+        assert_eq!(decode(&record), Err(Error::Reserved));
+        let mut coordinates = [UtmCoordinate::new(0., 0., 1, 'C'); 2];
+        assert_eq!(read(&record, &mut coordinates), Err(Error::Reserved));
+        record[18] = 1;
+        assert_eq!(decode(&record), Err(Error::Version(1)));
+        assert_eq!(encode_zone(34, 'Ħ'), [34, 0]);
+        assert_eq!(decode_zone(&[34, 0]), Err(Error::ZoneLetterOutOfRange));
+    }
+
+    #[cfg(not(feature = "no_std"))]
+    #[test]
+    fn truncated_stream() {
+        let record = encode(&UtmCoordinate::new(261878., 6243186., 34, 'H'));
+        let mut reader = Reader::new(&record[..RECORD_LEN - 1]);
+        let err = reader.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,143 @@
+//! Recover possible locations for UTM coordinates whose zone has been lost.
+//!
+//! An easting and northing without a zone number and hemisphere could be in any of sixty zones
+//! on either side of the equator. `candidates` lists every one of those interpretations that is
+//! geometrically possible, and ranks them by distance to a rough location hint if there is one.
+
+#[cfg(feature = "no_std")]
+use alloc::vec::Vec;
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use super::{lat_lon_to_zone_number, wsg84_utm_to_lat_lon, Hemisphere, WSG84ToLatLonError};
+
+/// The mean radius of the earth in meters, used for hint distances.
+const EARTH_RADIUS: f64 = 6371008.8;
+
+/// One possible interpretation of a zone-less easting and northing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Candidate {
+    /// The zone number.
+    pub zone: u8,
+    /// The hemisphere.
+    pub hemisphere: Hemisphere,
+    /// The latitude in decimal degrees.
+    pub latitude: f64,
+    /// The longitude in decimal degrees.
+    pub longitude: f64,
+    /// True if the location actually falls inside this zone. Coordinates far from the central
+    /// meridian can decode to a point that belongs to a neighbouring zone, which makes this
+    /// interpretation less likely.
+    pub in_zone: bool,
+    /// The great circle distance in meters to the hint, if one was given.
+    pub distance: Option<f64>,
+}
+
+/// Lists the possible locations of an easting and northing with an unknown zone.
+///
+/// Every zone is tried in each hemisphere for which the northing decodes to a latitude on the
+/// correct side of the equator and inside the UTM bands. With a `hint`, a rough latitude and
+/// longitude in decimal degrees, candidates are sorted by their distance to it, and a hint that
+/// isn't a number leaves them in hemisphere and zone order. Without one, candidates inside their
+/// zone come first, then they are ordered by hemisphere and zone.
+///
+/// # Examples
+///
+/// ```
+/// use utm::recovery::candidates;
+/// use utm::Hemisphere;
+/// // Capetown, South Africa, with a hint that the data is from the Western Cape
+/// let candidates = candidates(261878., 6243186., Some((-33., 20.))).unwrap();
+/// let best = candidates[0];
+/// assert_eq!(best.zone, 34);
+/// assert_eq!(best.hemisphere, Hemisphere::South);
+/// assert!((best.latitude - -33.92487).abs() < 1e-4);
+/// ```
+pub fn candidates(
+    easting: f64,
+    northing: f64,
+    hint: Option<(f64, f64)>,
+) -> Result<Vec<Candidate>, WSG84ToLatLonError> {
+    let mut candidates = Vec::new();
+    for &(hemisphere, letter) in &[(Hemisphere::North, 'N'), (Hemisphere::South, 'M')] {
+        for zone in 1..=60 {
+            let (latitude, longitude) = wsg84_utm_to_lat_lon(easting, northing, zone, letter)?;
+            let plausible = match hemisphere {
+                Hemisphere::North => (0. ..=84.).contains(&latitude),
+                Hemisphere::South => (-80. ..0.).contains(&latitude),
+            };
+            if !plausible {
+                // The latitude is the same in every zone, so the whole hemisphere is out.
+                break;
+            }
+            let longitude = (longitude + 540.) % 360. - 180.;
+            candidates.push(Candidate {
+                zone,
+                hemisphere,
+                latitude,
+                longitude,
+                in_zone: lat_lon_to_zone_number(latitude, longitude) == zone,
+                distance: hint.map(|(lat, lon)| haversine(lat, lon, latitude, longitude)),
+            });
+        }
+    }
+    if hint.is_some() {
+        // A total order, so a distance that isn't a number can't panic the sort.
+        let distance = |candidate: &Candidate| candidate.distance.unwrap_or(f64::NAN);
+        candidates.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+    } else {
+        candidates.sort_by_key(|candidate| !candidate.in_zone);
+    }
+    Ok(candidates)
+}
+
+fn haversine(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let dphi = phi2 - phi1;
+    let dlambda = (lon2 - lon1).to_radians();
+    let a = (dphi / 2.).sin().powi(2) + phi1.cos() * phi2.cos() * (dlambda / 2.).sin().powi(2);
+    2. * EARTH_RADIUS * a.sqrt().min(1.).asin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn both_hemispheres() {
+        let candidates = candidates(500000., 5000000., None).unwrap();
+        assert_eq!(candidates.len(), 120);
+        assert!(candidates.iter().all(|candidate| candidate.in_zone));
+        assert!(candidates
+            .iter()
+            .any(|candidate| candidate.hemisphere == Hemisphere::South));
+    }
+
+    #[test]
+    fn low_northing_is_northern_only() {
+        let candidates = candidates(500000., 500000., None).unwrap();
+        assert_eq!(candidates.len(), 60);
+        assert!(candidates
+            .iter()
+            .all(|candidate| candidate.hemisphere == Hemisphere::North));
+    }
+
+    #[test]
+    fn hint_that_is_not_a_number() {
+        let candidates = candidates(500000., 5000000., Some((f64::NAN, 20.))).unwrap();
+        assert_eq!(candidates.len(), 120);
+        assert_eq!(candidates[0].zone, 1);
+        assert_eq!(candidates[0].hemisphere, Hemisphere::North);
+        let infinite = super::candidates(500000., 5000000., Some((f64::INFINITY, 20.))).unwrap();
+        assert_eq!(infinite, candidates);
+    }
+
+    #[test]
+    fn invalid_easting() {
+        assert_eq!(
+            candidates(50., 5000000., None).unwrap_err(),
+            WSG84ToLatLonError::EastingOutOfRange
+        );
+    }
+}
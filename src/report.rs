@@ -0,0 +1,135 @@
+//! Explain how a point is projected.
+//!
+//! `report` gathers everything that goes into a point's UTM coordinate, from the zone and band
+//! it was assigned to and whether an irregular zone applied, to the grid's convergence and scale
+//! there and how close the point is to the next zone. It is meant for debugging views that
+//! answer "why did I get this coordinate?".
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use super::converter::{Converter, UTM_SCALE_FACTOR};
+use super::{UtmCoordinate, ZoneException, WGS84, ZONE_EXCEPTIONS};
+
+/// How a latitude and longitude is projected to UTM, returned by `report`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Report {
+    /// The projected coordinate, with its zone number and band letter.
+    pub coordinate: UtmCoordinate,
+    /// The central meridian of the zone, in decimal degrees.
+    pub central_meridian: f64,
+    /// The meridian convergence in radians, with the same sign convention as `to_utm_wgs84`
+    /// and `frame::convergence`.
+    pub convergence: f64,
+    /// The point scale factor, the ratio of a short grid distance to the same distance on the
+    /// ellipsoid. It is 0.9996 on the central meridian and grows away from it.
+    pub scale_factor: f64,
+    /// The distance in meters of easting to the nearer of the zone's western and eastern
+    /// edges at this latitude.
+    pub distance_to_boundary: f64,
+    /// The irregular zone the point fell into, if any. See `ZONE_EXCEPTIONS`.
+    pub exception: Option<ZoneException>,
+}
+
+/// Reports how a latitude and longitude in decimal degrees is projected to UTM.
+///
+/// Returns `None` if the latitude is not between 80°S and 84°N or the longitude is not between
+/// -180° and 180°.
+///
+/// # Examples
+///
+/// ```
+/// use utm::report::report;
+/// // Bergen is in zone 32V, which is widened westwards over zone 31.
+/// let report = report(60.39, 5.32).unwrap();
+/// assert_eq!(report.coordinate.zone_num, 32);
+/// assert_eq!(report.central_meridian, 9.);
+/// assert_eq!(report.exception.unwrap().min_longitude, 3.);
+/// assert!(report.scale_factor > 0.9996);
+/// ```
+pub fn report(latitude: f64, longitude: f64) -> Option<Report> {
+    let converter = Converter::new();
+    let coordinate = converter.to_utm(latitude, longitude)?;
+    let projection = converter.projection(coordinate.zone_num, coordinate.zone_letter >= 'N');
+    let (_, _, convergence) = projection.forward(latitude, longitude);
+    let central_meridian = projection.central_meridian();
+    let exception = ZONE_EXCEPTIONS
+        .iter()
+        .find(|exception| exception.contains(latitude, longitude))
+        .cloned();
+    let (west, east) = match exception {
+        Some(exception) => (exception.min_longitude, exception.max_longitude),
+        None => (central_meridian - 3., central_meridian + 3.),
+    };
+    let distance_to_boundary = [west, east]
+        .iter()
+        .map(|&edge| {
+            let (_, easting, _) = projection.forward(latitude, edge);
+            (easting - coordinate.easting).abs()
+        })
+        .fold(f64::INFINITY, f64::min);
+    Some(Report {
+        coordinate,
+        central_meridian,
+        convergence,
+        scale_factor: scale_factor(latitude, longitude - central_meridian),
+        distance_to_boundary,
+        exception,
+    })
+}
+
+/// Returns the point scale factor at a latitude and a longitude relative to the central
+/// meridian, in decimal degrees, using the series in Snyder (1987), equation 8-11.
+fn scale_factor(latitude: f64, longitude: f64) -> f64 {
+    let e2 = WGS84.f * (2. - WGS84.f);
+    let ep2 = e2 / (1. - e2);
+    let latitude = latitude.to_radians();
+    let t = latitude.tan().powi(2);
+    let c = ep2 * latitude.cos().powi(2);
+    let a2 = (longitude.to_radians() * latitude.cos()).powi(2);
+    UTM_SCALE_FACTOR
+        * (1.
+            + (1. + c) * a2 / 2.
+            + (5. - 4. * t + 42. * c + 13. * c * c - 28. * ep2) * a2 * a2 / 24.
+            + (61. - 148. * t + 16. * t * t) * a2 * a2 * a2 / 720.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regular_zone() {
+        let report = report(-33.92487, 18.42406).unwrap();
+        assert_eq!(report.coordinate.zone_num, 34);
+        assert_eq!(report.coordinate.zone_letter, 'H');
+        assert_eq!(report.central_meridian, 21.);
+        assert_eq!(report.exception, None);
+        // West of the central meridian in the south, so grid north is east of true north.
+        assert!(report.convergence < 0.);
+        // 18.42406°E is 0.42° from zone 33, about 39 km at this latitude.
+        assert!((report.distance_to_boundary - 39000.).abs() < 1000.);
+    }
+
+    #[cfg(feature = "forward")]
+    #[test]
+    fn convergence_matches_frame() {
+        for &(latitude, longitude) in &[(-33.92487, 18.42406), (47.5, -119.2), (-0.5, -119.2)] {
+            let report = report(latitude, longitude).unwrap();
+            assert_eq!(
+                report.convergence,
+                ::frame::convergence(latitude, longitude, report.coordinate.zone_num)
+            );
+        }
+    }
+
+    #[test]
+    fn scale_factor_grows_away_from_central_meridian() {
+        assert_eq!(report(45., 9.).unwrap().scale_factor, UTM_SCALE_FACTOR);
+        // About 1.001 at the edge of a zone at the equator.
+        let edge = report(0., 11.999).unwrap().scale_factor;
+        assert!((edge - 1.00097).abs() < 1e-4);
+        assert!(report(85., 0.).is_none());
+    }
+}
@@ -0,0 +1,140 @@
+//! Forward and inverse conversions that are checked against each other.
+//!
+//! Simulations that bounce between latitude and longitude and the grid every step accumulate
+//! whatever the two directions disagree by. Mixing algorithms, e.g. the series forward with
+//! Krüger's inverse, makes that drift meters rather than nanometers. A `RoundTripper` does both
+//! directions with the same projections from a `workspace::Workspace`, and in debug builds
+//! asserts that every conversion comes back within a stated tolerance.
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use super::converter::Converter;
+use super::workspace::Workspace;
+use super::{UtmCoordinate, WSG84ToLatLonError};
+
+/// Paired forward and inverse conversions with a round trip tolerance.
+///
+/// # Examples
+///
+/// ```
+/// use utm::roundtrip::RoundTripper;
+/// let round_tripper = RoundTripper::new(1e-6);
+/// let mut latitude = -33.92487;
+/// let mut longitude = 18.42406;
+/// for _ in 0..100 {
+///     let coordinate = round_tripper.forward(latitude, longitude).unwrap();
+///     (latitude, longitude) = round_tripper.inverse(&coordinate).unwrap();
+/// }
+/// assert!((latitude + 33.92487).abs() < 1e-9 && (longitude - 18.42406).abs() < 1e-9);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoundTripper {
+    workspace: Workspace,
+    tolerance: f64,
+}
+
+impl RoundTripper {
+    /// Creates a round tripper for standard UTM on WGS84 with a tolerance in meters.
+    pub fn new(tolerance: f64) -> RoundTripper {
+        RoundTripper::with_converter(Converter::new(), tolerance)
+    }
+
+    /// Creates a round tripper for a converter's grid constants with a tolerance in meters.
+    pub fn with_converter(converter: Converter, tolerance: f64) -> RoundTripper {
+        RoundTripper {
+            workspace: Workspace::with_converter(converter),
+            tolerance,
+        }
+    }
+
+    /// Returns the tolerance in meters.
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    /// Converts a latitude and longitude in decimal degrees to a coordinate in its own zone
+    /// and band, like `Converter::to_utm`.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if converting the coordinate back and forth again moves it by
+    /// more than the tolerance.
+    pub fn forward(&self, latitude: f64, longitude: f64) -> Option<UtmCoordinate> {
+        let coordinate = self.workspace.to_utm(latitude, longitude)?;
+        #[cfg(debug_assertions)]
+        self.assert_round_trip(&coordinate);
+        Some(coordinate)
+    }
+
+    /// Converts a coordinate to a latitude and longitude in decimal degrees, like
+    /// `Converter::to_lat_lon`.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if converting the result forward again, into the coordinate's
+    /// zone, moves it by more than the tolerance.
+    pub fn inverse(&self, coordinate: &UtmCoordinate) -> Result<(f64, f64), WSG84ToLatLonError> {
+        let lat_lon = self.workspace.to_lat_lon(coordinate)?;
+        #[cfg(debug_assertions)]
+        self.assert_round_trip(coordinate);
+        Ok(lat_lon)
+    }
+
+    /// Returns how far in meters a coordinate moves on the grid when it is converted to a
+    /// latitude and longitude and back into its zone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::roundtrip::RoundTripper;
+    /// use utm::UtmCoordinate;
+    /// let round_tripper = RoundTripper::new(1e-6);
+    /// let coordinate = UtmCoordinate::new(261878., 6243186., 34, 'H');
+    /// assert!(round_tripper.round_trip_error(&coordinate).unwrap() < 1e-6);
+    /// ```
+    pub fn round_trip_error(&self, coordinate: &UtmCoordinate) -> Result<f64, WSG84ToLatLonError> {
+        let (latitude, longitude) = self.workspace.to_lat_lon(coordinate)?;
+        let projection = self
+            .workspace
+            .projection(coordinate.zone_num, coordinate.zone_letter >= 'N')
+            .ok_or(WSG84ToLatLonError::ZoneNumOutOfRange)?;
+        let (northing, easting, _) = projection.forward(latitude, longitude);
+        Ok((easting - coordinate.easting).hypot(northing - coordinate.northing))
+    }
+
+    #[cfg(debug_assertions)]
+    fn assert_round_trip(&self, coordinate: &UtmCoordinate) {
+        if let Ok(error) = self.round_trip_error(coordinate) {
+            assert!(
+                error <= self.tolerance,
+                "{} moved {} m in a round trip, more than the tolerance of {} m",
+                coordinate,
+                error,
+                self.tolerance
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_errors_are_tiny() {
+        let round_tripper = RoundTripper::new(1e-6);
+        for &(latitude, longitude) in &[(0., 0.), (-79.9, -177.1), (83.9, 41.9), (45., 11.99)] {
+            let coordinate = round_tripper.forward(latitude, longitude).unwrap();
+            assert!(round_tripper.round_trip_error(&coordinate).unwrap() < 1e-6);
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "more than the tolerance")]
+    fn impossible_tolerance() {
+        RoundTripper::new(-1.).forward(45., 9.);
+    }
+}
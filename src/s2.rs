@@ -0,0 +1,373 @@
+//! Conversions to and from S2 cell IDs, behind the `s2` feature.
+//!
+//! S2 divides the sphere into six cube faces, each a quadtree of cells ordered along a Hilbert
+//! curve, and numbers every cell with a 64-bit ID. These functions let a pipeline whose spatial
+//! index is S2 key its data on cells while using this crate for its UTM coordinates. Like S2
+//! itself, they treat WGS84 latitudes and longitudes as if they were on a sphere.
+
+use std::fmt;
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+#[cfg(feature = "inverse")]
+use super::wsg84_utm_to_lat_lon;
+use super::WSG84ToLatLonError;
+#[cfg(feature = "forward")]
+use super::{to_utm_wgs84, to_utm_wgs84_no_zone};
+
+/// The level of the smallest cells, about a centimeter across.
+pub const MAX_LEVEL: u8 = 30;
+
+/// The number of leaf cells along each side of a face.
+const MAX_SIZE: u32 = 1 << MAX_LEVEL;
+
+/// The Hilbert curve position of each (i, j) quadrant, for each of the four orientations.
+const IJ_TO_POS: [[u8; 4]; 4] = [[0, 1, 3, 2], [0, 3, 1, 2], [2, 3, 1, 0], [2, 1, 3, 0]];
+
+/// The (i, j) quadrant of each Hilbert curve position, for each of the four orientations.
+const POS_TO_IJ: [[u8; 4]; 4] = [[0, 1, 3, 2], [0, 2, 3, 1], [3, 2, 0, 1], [3, 1, 0, 2]];
+
+/// How the orientation of the curve changes in each position.
+const POS_TO_ORIENTATION: [u8; 4] = [1, 0, 0, 3];
+
+/// Errors that can occur when converting to an S2 cell.
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The latitude or longitude is not a finite number.
+    InvalidLatLon,
+    /// The level is greater than 30.
+    InvalidLevel,
+    /// The UTM coordinate could not be converted to a latitude and longitude.
+    Utm(WSG84ToLatLonError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidLatLon => write!(f, "Latitude and longitude must be finite"),
+            Error::InvalidLevel => write!(f, "S2 level must be between 0 and 30"),
+            Error::Utm(err) => err.fmt(f),
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for Error {}
+
+impl From<WSG84ToLatLonError> for Error {
+    fn from(err: WSG84ToLatLonError) -> Error {
+        Error::Utm(err)
+    }
+}
+
+/// An S2 cell ID.
+///
+/// It displays as its token, the hexadecimal ID without trailing zeros.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CellId(u64);
+
+impl CellId {
+    /// Creates a cell ID from its 64-bit representation, or returns `None` if it isn't a valid
+    /// cell.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::s2::CellId;
+    /// assert!(CellId::from_id(0x1000000000000000).is_some());
+    /// assert!(CellId::from_id(0).is_none());
+    /// assert!(CellId::from_id(1 << 62).is_none());
+    /// ```
+    pub fn from_id(id: u64) -> Option<CellId> {
+        if id >> 61 < 6 && id.trailing_zeros().is_multiple_of(2) && id.trailing_zeros() <= 60 {
+            Some(CellId(id))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the 64-bit representation.
+    pub fn id(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the cube face, between 0 and 5.
+    pub fn face(&self) -> u8 {
+        (self.0 >> 61) as u8
+    }
+
+    /// Returns the level, between 0 for a whole face and 30 for a leaf cell.
+    pub fn level(&self) -> u8 {
+        MAX_LEVEL - (self.0.trailing_zeros() / 2) as u8
+    }
+
+    /// Returns the cell at a coarser level that contains this one, or `None` if the level is
+    /// finer than this cell's.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::s2::lat_lon_to_cell;
+    /// let leaf = lat_lon_to_cell(48.8566, 2.3522, 30).unwrap();
+    /// assert_eq!(leaf.parent(12), Some(lat_lon_to_cell(48.8566, 2.3522, 12).unwrap()));
+    /// ```
+    pub fn parent(&self, level: u8) -> Option<CellId> {
+        if level > self.level() {
+            None
+        } else {
+            let lsb = lsb_for_level(level);
+            Some(CellId((self.0 & lsb.wrapping_neg()) | lsb))
+        }
+    }
+}
+
+impl fmt::Display for CellId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = 16 - self.0.trailing_zeros() / 4;
+        write!(
+            f,
+            "{:0width$x}",
+            self.0 >> (4 * (16 - digits)),
+            width = digits as usize
+        )
+    }
+}
+
+/// Returns the S2 cell containing a latitude and longitude in decimal degrees.
+///
+/// # Examples
+///
+/// ```
+/// use utm::s2::lat_lon_to_cell;
+/// let cell = lat_lon_to_cell(0., 0., 30).unwrap();
+/// assert_eq!(cell.id(), 0x1000000000000001);
+/// assert_eq!(lat_lon_to_cell(0., 0., 0).unwrap().to_string(), "1");
+/// ```
+pub fn lat_lon_to_cell(latitude: f64, longitude: f64, level: u8) -> Result<CellId, Error> {
+    if !latitude.is_finite() || !longitude.is_finite() {
+        return Err(Error::InvalidLatLon);
+    }
+    if level > MAX_LEVEL {
+        return Err(Error::InvalidLevel);
+    }
+    let (latitude, longitude) = (latitude.to_radians(), longitude.to_radians());
+    let point = [
+        latitude.cos() * longitude.cos(),
+        latitude.cos() * longitude.sin(),
+        latitude.sin(),
+    ];
+    let (face, u, v) = xyz_to_face_uv(point);
+    let i = st_to_ij(uv_to_st(u));
+    let j = st_to_ij(uv_to_st(v));
+
+    let mut orientation = face & 1;
+    let mut position = 0u64;
+    for bit in (0..MAX_LEVEL).rev() {
+        let ij = (((i >> bit) & 1) << 1 | ((j >> bit) & 1)) as usize;
+        let quadrant = IJ_TO_POS[orientation as usize][ij];
+        position = position << 2 | u64::from(quadrant);
+        orientation ^= POS_TO_ORIENTATION[quadrant as usize];
+    }
+    let leaf = u64::from(face) << 61 | position << 1 | 1;
+    Ok(CellId(leaf).parent(level).unwrap())
+}
+
+/// Returns the latitude and longitude of the center of an S2 cell, in decimal degrees.
+///
+/// # Examples
+///
+/// ```
+/// use utm::s2::{cell_to_lat_lon, lat_lon_to_cell};
+/// let cell = lat_lon_to_cell(48.8566, 2.3522, 20).unwrap();
+/// let (latitude, longitude) = cell_to_lat_lon(cell);
+/// assert!((latitude - 48.8566).abs() < 1e-4);
+/// assert!((longitude - 2.3522).abs() < 1e-4);
+/// ```
+pub fn cell_to_lat_lon(cell: CellId) -> (f64, f64) {
+    let face = cell.face();
+    let size = 1u32 << (MAX_LEVEL - cell.level());
+    let mut orientation = face & 1;
+    let (mut i, mut j) = (0u32, 0u32);
+    // The position of the cell's first leaf, whose (i, j) is the cell's corner once aligned.
+    let position = (cell.0 - (lsb_for_level(cell.level()) - 1)) >> 1;
+    for bit in (0..MAX_LEVEL).rev() {
+        let quadrant = ((position >> (2 * u32::from(bit))) & 3) as usize;
+        let ij = POS_TO_IJ[orientation as usize][quadrant];
+        i = i << 1 | u32::from(ij >> 1);
+        j = j << 1 | u32::from(ij & 1);
+        orientation ^= POS_TO_ORIENTATION[quadrant];
+    }
+    let center =
+        |ij: u32| (f64::from(ij & !(size - 1)) + f64::from(size) / 2.) / f64::from(MAX_SIZE);
+    let (x, y, z) = face_uv_to_xyz(face, st_to_uv(center(i)), st_to_uv(center(j)));
+    (
+        z.atan2((x * x + y * y).sqrt()).to_degrees(),
+        y.atan2(x).to_degrees(),
+    )
+}
+
+#[cfg(feature = "inverse")]
+/// Returns the S2 cell containing a UTM coordinate.
+///
+/// # Examples
+///
+/// ```
+/// use utm::s2::utm_to_cell;
+/// // Capetown, South Africa
+/// let cell = utm_to_cell(261878., 6243186., 34, 'H', 16).unwrap();
+/// assert_eq!(cell.level(), 16);
+/// ```
+pub fn utm_to_cell(
+    easting: f64,
+    northing: f64,
+    zone_num: u8,
+    zone_letter: char,
+    level: u8,
+) -> Result<CellId, Error> {
+    let (latitude, longitude) = wsg84_utm_to_lat_lon(easting, northing, zone_num, zone_letter)?;
+    lat_lon_to_cell(latitude, longitude, level)
+}
+
+#[cfg(feature = "forward")]
+/// Returns the UTM coordinates of the center of an S2 cell in the cell's own zone.
+///
+/// Returns the northing, easting, and meridian convergence, like `to_utm_wgs84_no_zone`.
+pub fn cell_to_utm(cell: CellId) -> (f64, f64, f64) {
+    let (latitude, longitude) = cell_to_lat_lon(cell);
+    to_utm_wgs84_no_zone(latitude, longitude)
+}
+
+#[cfg(feature = "forward")]
+/// Returns the UTM coordinates of the center of an S2 cell in the given zone.
+///
+/// Returns the northing, easting, and meridian convergence, like `to_utm_wgs84`.
+pub fn cell_to_utm_in_zone(cell: CellId, zone: u8) -> (f64, f64, f64) {
+    let (latitude, longitude) = cell_to_lat_lon(cell);
+    to_utm_wgs84(latitude, longitude, zone)
+}
+
+fn lsb_for_level(level: u8) -> u64 {
+    1 << (2 * u32::from(MAX_LEVEL - level))
+}
+
+/// Projects a point onto the cube face it falls on.
+fn xyz_to_face_uv([x, y, z]: [f64; 3]) -> (u8, f64, f64) {
+    let (ax, ay, az) = (x.abs(), y.abs(), z.abs());
+    let axis = if ax > ay && ax > az {
+        0
+    } else if ay > az {
+        1
+    } else {
+        2
+    };
+    let negative = [x, y, z][axis] < 0.;
+    let face = axis as u8 + if negative { 3 } else { 0 };
+    let (u, v) = match face {
+        0 => (y / x, z / x),
+        1 => (-x / y, z / y),
+        2 => (-x / z, -y / z),
+        3 => (z / x, y / x),
+        4 => (z / y, -x / y),
+        _ => (-y / z, -x / z),
+    };
+    (face, u, v)
+}
+
+fn face_uv_to_xyz(face: u8, u: f64, v: f64) -> (f64, f64, f64) {
+    match face {
+        0 => (1., u, v),
+        1 => (-u, 1., v),
+        2 => (-u, -v, 1.),
+        3 => (-1., -v, -u),
+        4 => (v, -1., -u),
+        _ => (v, u, -1.),
+    }
+}
+
+/// S2's quadratic transform, which makes cells closer to equal in area than a plain projection
+/// onto the cube.
+fn uv_to_st(u: f64) -> f64 {
+    if u >= 0. {
+        0.5 * (1. + 3. * u).sqrt()
+    } else {
+        1. - 0.5 * (1. - 3. * u).sqrt()
+    }
+}
+
+fn st_to_uv(s: f64) -> f64 {
+    if s >= 0.5 {
+        (4. * s * s - 1.) / 3.
+    } else {
+        (1. - 4. * (1. - s) * (1. - s)) / 3.
+    }
+}
+
+fn st_to_ij(s: f64) -> u32 {
+    ((f64::from(MAX_SIZE) * s).floor().max(0.) as u32).min(MAX_SIZE - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faces() {
+        let points = [
+            (0., 0.),
+            (0., 90.),
+            (90., 0.),
+            (0., 180.),
+            (0., -90.),
+            (-90., 0.),
+        ];
+        for (face, &(latitude, longitude)) in points.iter().enumerate() {
+            let cell = lat_lon_to_cell(latitude, longitude, 0).unwrap();
+            assert_eq!(cell.face(), face as u8);
+            assert_eq!(cell.id(), (2 * face as u64 + 1) << 60);
+        }
+    }
+
+    #[test]
+    fn token() {
+        // New York City
+        let cell = lat_lon_to_cell(40.7128, -74.006, 8).unwrap();
+        assert_eq!(cell.to_string(), "89c25");
+        assert_eq!(CellId::from_id(cell.id()), Some(cell));
+    }
+
+    #[test]
+    fn round_trip() {
+        for &(latitude, longitude) in &[(48.8566, 2.3522), (-33.92487, 18.42406), (71., -156.8)] {
+            let cell = lat_lon_to_cell(latitude, longitude, 30).unwrap();
+            assert_eq!(cell.level(), 30);
+            let (lat, lon) = cell_to_lat_lon(cell);
+            assert!((lat - latitude).abs() < 1e-6 && (lon - longitude).abs() < 1e-6);
+            assert_eq!(lat_lon_to_cell(lat, lon, 30).unwrap(), cell);
+            for level in 0..30 {
+                let parent = cell.parent(level).unwrap();
+                assert_eq!(parent.level(), level);
+                assert_eq!(lat_lon_to_cell(latitude, longitude, level).unwrap(), parent);
+                let (lat, lon) = cell_to_lat_lon(parent);
+                assert_eq!(lat_lon_to_cell(lat, lon, level).unwrap(), parent);
+            }
+        }
+    }
+
+    #[cfg(all(feature = "forward", feature = "inverse"))]
+    #[test]
+    fn utm_round_trip() {
+        let cell = utm_to_cell(313784., 5427057., 60, 'G', 20).unwrap();
+        let (northing, easting, _) = cell_to_utm_in_zone(cell, 60);
+        // Level 20 cells are about 10 meters across.
+        assert!((easting - 313784.).abs() < 15.);
+        assert!((northing - 5427057.).abs() < 15.);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(lat_lon_to_cell(0., 0., 31), Err(Error::InvalidLevel));
+        assert_eq!(lat_lon_to_cell(f64::NAN, 0., 5), Err(Error::InvalidLatLon));
+    }
+}
@@ -0,0 +1,172 @@
+//! Serialize a `UtmCoordinate` field as a string, behind the `serde` feature.
+//!
+//! Use it with `#[serde(with = "utm::serde_utm_string")]` to store a coordinate as
+//! `33T 315000 5741000` instead of a struct of its fields. The `option` submodule does the same
+//! for an `Option<UtmCoordinate>`, with `None` serialized as a missing value.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate serde;
+//! # extern crate serde_json;
+//! # extern crate utm;
+//! use serde::{Deserialize, Serialize};
+//! use utm::UtmCoordinate;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Station {
+//!     name: String,
+//!     #[serde(with = "utm::serde_utm_string")]
+//!     location: UtmCoordinate,
+//! }
+//!
+//! # fn main() {
+//! let station = Station {
+//!     name: "Berlin".to_string(),
+//!     location: UtmCoordinate::new(391000., 5820000., 33, 'U'),
+//! };
+//! let json = serde_json::to_string(&station).unwrap();
+//! assert_eq!(json, r#"{"name":"Berlin","location":"33U 391000 5820000"}"#);
+//! let station: Station = serde_json::from_str(&json).unwrap();
+//! assert_eq!(station.location.zone_num, 33);
+//! # }
+//! ```
+
+use std::fmt;
+
+use serde::de::{self, Deserializer, Visitor};
+use serde::Serializer;
+
+use super::UtmCoordinate;
+
+/// Serializes a coordinate as a string like `33T 315000 5741000`.
+pub fn serialize<S: Serializer>(
+    coordinate: &UtmCoordinate,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(coordinate)
+}
+
+/// Deserializes a coordinate from a string like `33T 315000 5741000`.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<UtmCoordinate, D::Error> {
+    deserializer.deserialize_str(UtmCoordinateVisitor)
+}
+
+struct UtmCoordinateVisitor;
+
+impl<'de> Visitor<'de> for UtmCoordinateVisitor {
+    type Value = UtmCoordinate;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a UTM coordinate like 33T 315000 5741000")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<UtmCoordinate, E> {
+        value.parse().map_err(E::custom)
+    }
+}
+
+/// Serialize an `Option<UtmCoordinate>` field as an optional string.
+pub mod option {
+    use std::fmt;
+
+    use serde::de::{Deserializer, Visitor};
+    use serde::Serializer;
+
+    use super::UtmCoordinateVisitor;
+    use UtmCoordinate;
+
+    /// Serializes `Some` coordinate as a string like `33T 315000 5741000`, and `None` as none.
+    pub fn serialize<S: Serializer>(
+        coordinate: &Option<UtmCoordinate>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match coordinate {
+            Some(coordinate) => serializer.serialize_some(&Display(coordinate)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserializes an optional coordinate from a string like `33T 315000 5741000`.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<UtmCoordinate>, D::Error> {
+        deserializer.deserialize_option(OptionVisitor)
+    }
+
+    struct Display<'a>(&'a UtmCoordinate);
+
+    impl<'a> serde::Serialize for Display<'a> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            super::serialize(self.0, serializer)
+        }
+    }
+
+    struct OptionVisitor;
+
+    impl<'de> Visitor<'de> for OptionVisitor {
+        type Value = Option<UtmCoordinate>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "an optional UTM coordinate like 33T 315000 5741000")
+        }
+
+        fn visit_none<E: serde::de::Error>(self) -> Result<Option<UtmCoordinate>, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: serde::de::Error>(self) -> Result<Option<UtmCoordinate>, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Option<UtmCoordinate>, D::Error> {
+            deserializer.deserialize_str(UtmCoordinateVisitor).map(Some)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json;
+    use UtmCoordinate;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Fix {
+        #[serde(with = "super::option")]
+        location: Option<UtmCoordinate>,
+    }
+
+    #[test]
+    fn option() {
+        let fix = Fix {
+            location: Some(UtmCoordinate::new(315000.5, 5741000., 33, 'T')),
+        };
+        let json = serde_json::to_string(&fix).unwrap();
+        assert_eq!(json, r#"{"location":"33T 315000.5 5741000"}"#);
+        assert_eq!(serde_json::from_str::<Fix>(&json).unwrap(), fix);
+        let json = r#"{"location":null}"#;
+        assert_eq!(
+            serde_json::from_str::<Fix>(json).unwrap(),
+            Fix { location: None }
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(serde_json::from_str::<Fix>(r#"{"location":"61T 315000 5741000"}"#).is_err());
+    }
+
+    #[test]
+    fn fields() {
+        let coordinate = UtmCoordinate::new(315000., 5741000., 33, 'T');
+        let json = serde_json::to_string(&coordinate).unwrap();
+        assert_eq!(
+            json,
+            r#"{"easting":315000.0,"northing":5741000.0,"zone_num":33,"zone_letter":"T"}"#
+        );
+    }
+}
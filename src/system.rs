@@ -0,0 +1,314 @@
+//! Zoned transverse Mercator systems with configurable zones.
+//!
+//! UTM is one member of a family: Gauss-Krüger grids use 3° or 6° zones with a scale factor of
+//! one and the zone number prefixed to the easting, and many historical national systems shift
+//! where the zones start. A `ZoneSystem` describes the whole family with a zone width, the
+//! meridian zone 1 starts at, the grid constants, and how latitudes are banded, and projects
+//! every zone with Krüger's series from `tm`.
+//!
+//! Zones are uniform, so the Norway and Svalbard exceptions of UTM don't apply.
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use super::converter::{UTM_FALSE_EASTING, UTM_FALSE_NORTHING_SOUTH, UTM_SCALE_FACTOR};
+use super::tm::TransverseMercator;
+use super::{lat_to_zone_letter, Ellipsoid, WGS84};
+
+/// How a zone system divides latitudes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Bands {
+    /// The UTM latitude bands C to X, from 80°S to 84°N.
+    Utm,
+    /// The northern and southern hemispheres, from pole to pole, with no band letters.
+    Hemispheres,
+}
+
+/// A coordinate in a zone system.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SystemCoordinate {
+    /// The easting in meters, including any zone prefix.
+    pub easting: f64,
+    /// The northing in meters.
+    pub northing: f64,
+    /// The zone number, starting at 1.
+    pub zone: u16,
+    /// Whether the coordinate uses the northern hemisphere's false northing.
+    pub northern: bool,
+    /// The latitude band letter, if the system has bands.
+    pub band: Option<char>,
+}
+
+/// A family of transverse Mercator zones.
+///
+/// The default system is standard UTM on WGS84 without the zone exceptions.
+///
+/// # Examples
+///
+/// ```
+/// use utm::datum::BESSEL_1841;
+/// use utm::system::{Bands, ZoneSystem};
+/// // German Gauss-Krüger: 3° zones centered on multiples of 3°, zone number prefixed
+/// let system = ZoneSystem::new()
+///     .with_ellipsoid(BESSEL_1841)
+///     .with_zones(3., 1.5)
+///     .with_scale_factor(1.)
+///     .with_zone_prefix(1000000.)
+///     .with_false_northing(0., 0.)
+///     .with_bands(Bands::Hemispheres);
+/// assert_eq!(system.zone_of(12.4), Some(4));
+/// let coordinate = system.forward(52.5, 13.4).unwrap();
+/// assert_eq!(coordinate.zone, 4);
+/// assert!(coordinate.easting > 4500000. && coordinate.easting < 4600000.);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ZoneSystem {
+    ellipsoid: Ellipsoid,
+    zone_width: f64,
+    first_meridian: f64,
+    scale_factor: f64,
+    false_easting: f64,
+    zone_prefix: f64,
+    false_northing_north: f64,
+    false_northing_south: f64,
+    bands: Bands,
+}
+
+impl ZoneSystem {
+    /// Creates a system with the zones and constants of standard UTM on WGS84.
+    pub fn new() -> ZoneSystem {
+        ZoneSystem {
+            ellipsoid: WGS84,
+            zone_width: 6.,
+            first_meridian: -180.,
+            scale_factor: UTM_SCALE_FACTOR,
+            false_easting: UTM_FALSE_EASTING,
+            zone_prefix: 0.,
+            false_northing_north: 0.,
+            false_northing_south: UTM_FALSE_NORTHING_SOUTH,
+            bands: Bands::Utm,
+        }
+    }
+
+    /// Returns this system on another ellipsoid.
+    pub fn with_ellipsoid(mut self, ellipsoid: Ellipsoid) -> ZoneSystem {
+        self.ellipsoid = ellipsoid;
+        self
+    }
+
+    /// Returns this system with zones of another width, in decimal degrees, with zone 1
+    /// starting at the western edge `first_meridian`.
+    ///
+    /// The last zone is narrower if the width doesn't divide 360°.
+    pub fn with_zones(mut self, zone_width: f64, first_meridian: f64) -> ZoneSystem {
+        self.zone_width = zone_width;
+        self.first_meridian = first_meridian;
+        self
+    }
+
+    /// Returns this system with another scale factor on the central meridians.
+    pub fn with_scale_factor(mut self, scale_factor: f64) -> ZoneSystem {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// Returns this system with another false easting, in meters.
+    pub fn with_false_easting(mut self, false_easting: f64) -> ZoneSystem {
+        self.false_easting = false_easting;
+        self
+    }
+
+    /// Returns this system with the zone number times `multiplier` added to every easting,
+    /// e.g. 1,000,000 m for Gauss-Krüger.
+    pub fn with_zone_prefix(mut self, multiplier: f64) -> ZoneSystem {
+        self.zone_prefix = multiplier;
+        self
+    }
+
+    /// Returns this system with other false northings for the northern and southern
+    /// hemispheres, in meters.
+    pub fn with_false_northing(mut self, north: f64, south: f64) -> ZoneSystem {
+        self.false_northing_north = north;
+        self.false_northing_south = south;
+        self
+    }
+
+    /// Returns this system with another band scheme.
+    pub fn with_bands(mut self, bands: Bands) -> ZoneSystem {
+        self.bands = bands;
+        self
+    }
+
+    /// Returns the number of zones around the globe.
+    pub fn zone_count(&self) -> u16 {
+        (360. / self.zone_width).ceil() as u16
+    }
+
+    /// Returns the zone containing a longitude in decimal degrees, or `None` if the longitude is
+    /// not between -180° and 180°.
+    ///
+    /// Points on a zone boundary belong to the zone to their east.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::system::ZoneSystem;
+    /// let system = ZoneSystem::new();
+    /// assert_eq!(system.zone_of(-180.), Some(1));
+    /// assert_eq!(system.zone_of(18.42406), Some(34));
+    /// assert_eq!(system.zone_of(180.), Some(1));
+    /// ```
+    pub fn zone_of(&self, longitude: f64) -> Option<u16> {
+        if !(-180. ..=180.).contains(&longitude) {
+            return None;
+        }
+        let offset = modulo(longitude - self.first_meridian, 360.);
+        let zone = (offset / self.zone_width).floor() as u16 + 1;
+        Some(zone.min(self.zone_count()))
+    }
+
+    /// Returns the central meridian of a zone in decimal degrees, between -180° and 180°, or
+    /// `None` if there is no such zone.
+    pub fn central_meridian(&self, zone: u16) -> Option<f64> {
+        if zone == 0 || zone > self.zone_count() {
+            return None;
+        }
+        Some(wrap(
+            self.first_meridian + self.zone_width * (f64::from(zone) - 0.5),
+        ))
+    }
+
+    /// Returns the projection of a zone in one hemisphere, or `None` if there is no such zone.
+    pub fn projection(&self, zone: u16, northern: bool) -> Option<TransverseMercator> {
+        let central_meridian = self.central_meridian(zone)?;
+        Some(TransverseMercator::new(
+            self.ellipsoid,
+            0.,
+            central_meridian,
+            self.scale_factor,
+            self.false_easting + f64::from(zone) * self.zone_prefix,
+            if northern {
+                self.false_northing_north
+            } else {
+                self.false_northing_south
+            },
+        ))
+    }
+
+    /// Converts a latitude and longitude in decimal degrees to a coordinate in its own zone.
+    ///
+    /// Returns `None` if the longitude is not between -180° and 180° or the latitude is outside
+    /// the bands.
+    pub fn forward(&self, latitude: f64, longitude: f64) -> Option<SystemCoordinate> {
+        let zone = self.zone_of(longitude)?;
+        let band = match self.bands {
+            Bands::Utm => Some(lat_to_zone_letter(latitude)?),
+            Bands::Hemispheres => {
+                if !(-90. ..=90.).contains(&latitude) {
+                    return None;
+                }
+                None
+            }
+        };
+        let northern = band.map_or(latitude >= 0., |band| band >= 'N');
+        let projection = self.projection(zone, northern)?;
+        let longitude =
+            projection.central_meridian() + wrap(longitude - projection.central_meridian());
+        let (northing, easting, _) = projection.forward(latitude, longitude);
+        Some(SystemCoordinate {
+            easting,
+            northing,
+            zone,
+            northern,
+            band,
+        })
+    }
+
+    #[cfg(feature = "inverse")]
+    /// Converts a coordinate to a latitude and longitude in decimal degrees, or returns `None`
+    /// if its zone doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::system::ZoneSystem;
+    /// let system = ZoneSystem::new().with_zones(3., 1.5);
+    /// let coordinate = system.forward(-33.92487, 18.42406).unwrap();
+    /// assert_eq!(coordinate.zone, 6);
+    /// let (latitude, longitude) = system.inverse(&coordinate).unwrap();
+    /// assert!((latitude + 33.92487).abs() < 1e-9 && (longitude - 18.42406).abs() < 1e-9);
+    /// ```
+    pub fn inverse(&self, coordinate: &SystemCoordinate) -> Option<(f64, f64)> {
+        let projection = self.projection(coordinate.zone, coordinate.northern)?;
+        let (latitude, longitude) = projection.inverse(coordinate.easting, coordinate.northing);
+        Some((latitude, wrap(longitude)))
+    }
+}
+
+impl Default for ZoneSystem {
+    fn default() -> ZoneSystem {
+        ZoneSystem::new()
+    }
+}
+
+/// Wraps a longitude in decimal degrees into [-180°, 180°).
+fn wrap(longitude: f64) -> f64 {
+    modulo(longitude + 180., 360.) - 180.
+}
+
+/// Returns the non-negative remainder of `x` divided by `m`.
+fn modulo(x: f64, m: f64) -> f64 {
+    x - m * (x / m).floor()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use converter::Converter;
+
+    #[test]
+    fn default_is_utm() {
+        let system = ZoneSystem::new();
+        assert_eq!(system.zone_count(), 60);
+        for &(latitude, longitude) in &[(-33.92487, 18.42406), (45., -0.1), (0., 179.9)] {
+            let expected = Converter::new().to_utm(latitude, longitude).unwrap();
+            let coordinate = system.forward(latitude, longitude).unwrap();
+            assert_eq!(coordinate.zone, u16::from(expected.zone_num));
+            assert_eq!(coordinate.band, Some(expected.zone_letter));
+            assert!((coordinate.easting - expected.easting).abs() < 1e-6);
+            assert!((coordinate.northing - expected.northing).abs() < 1e-6);
+        }
+        assert_eq!(system.forward(85., 0.), None);
+    }
+
+    #[test]
+    fn antimeridian() {
+        let system = ZoneSystem::new()
+            .with_zones(3., 1.5)
+            .with_bands(Bands::Hemispheres);
+        assert_eq!(system.zone_count(), 120);
+        assert_eq!(system.zone_of(-179.), Some(60));
+        assert_eq!(system.zone_of(179.), Some(60));
+        assert_eq!(system.central_meridian(60), Some(-180.));
+        let west = system.forward(10., 179.).unwrap();
+        let east = system.forward(10., -179.).unwrap();
+        assert!((west.northing - east.northing).abs() < 1e-6);
+        assert!((west.easting + east.easting - 1000000.).abs() < 1e-6);
+        #[cfg(feature = "inverse")]
+        {
+            let (_, longitude) = system.inverse(&east).unwrap();
+            assert!((longitude + 179.).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn hemispheres() {
+        let system = ZoneSystem::new().with_bands(Bands::Hemispheres);
+        let coordinate = system.forward(-89., 0.).unwrap();
+        assert!(!coordinate.northern);
+        assert_eq!(coordinate.band, None);
+        assert!(system.forward(0., 0.).unwrap().northern);
+        assert_eq!(system.forward(90.1, 0.), None);
+    }
+}
@@ -0,0 +1,374 @@
+//! A general transverse Mercator projection.
+//!
+//! UTM is one member of a large family of transverse Mercator grids that differ only in their
+//! ellipsoid, origin, scale factor, and false easting and northing. `TransverseMercator` covers
+//! all of them using Krüger's series to sixth order in the third flattening, as described by
+//! Karney (2011), "Transverse Mercator with an accuracy of a few nanometers". The result is
+//! accurate to well below a millimeter within a few thousand kilometers of the central meridian.
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use super::Ellipsoid;
+
+/// Settings for the Newton iteration that recovers latitude in `TransverseMercator::inverse`.
+///
+/// The iteration solves for the tangent of the latitude and stops once a step changes it by
+/// less than `tolerance`, relative to its magnitude (or absolutely, below a magnitude of one).
+/// Near the equator that is roughly a tolerance in radians of latitude. Convergence is
+/// quadratic, so the default settles in two or three iterations.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Iteration {
+    /// The relative step size below which the iteration stops.
+    pub tolerance: f64,
+    /// The maximum number of Newton steps.
+    pub max_iterations: u32,
+}
+
+impl Default for Iteration {
+    fn default() -> Iteration {
+        Iteration {
+            tolerance: 1e-12,
+            max_iterations: 5,
+        }
+    }
+}
+
+/// How the Newton iteration of an inverse conversion went.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Diagnostics {
+    /// The number of Newton steps taken.
+    pub iterations: u32,
+    /// True if the last step was within the tolerance, false if the iteration ran out of
+    /// steps first.
+    pub converged: bool,
+    /// The size of the last step, relative to the tangent of the latitude.
+    pub last_step: f64,
+}
+
+/// A transverse Mercator projection on an arbitrary ellipsoid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransverseMercator {
+    ellipsoid: Ellipsoid,
+    latitude_of_origin: f64,
+    central_meridian: f64,
+    scale_factor: f64,
+    false_easting: f64,
+    false_northing: f64,
+    e: f64,
+    a_rectifying: f64,
+    alpha: [f64; 6],
+    beta: [f64; 6],
+    origin_northing: f64,
+    iteration: Iteration,
+}
+
+impl TransverseMercator {
+    /// Creates a new transverse Mercator projection.
+    ///
+    /// Latitude of origin and central meridian are in decimal degrees, false easting and false
+    /// northing are in meters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::datum::GRS80;
+    /// use utm::tm::TransverseMercator;
+    /// // A Gauss-Krüger style 3° zone centered on 12°E
+    /// let projection = TransverseMercator::new(GRS80, 0., 12., 1., 4500000., 0.);
+    /// ```
+    pub fn new(
+        ellipsoid: Ellipsoid,
+        latitude_of_origin: f64,
+        central_meridian: f64,
+        scale_factor: f64,
+        false_easting: f64,
+        false_northing: f64,
+    ) -> TransverseMercator {
+        let f = ellipsoid.f;
+        let n = f / (2. - f);
+        let n2 = n * n;
+        let n3 = n2 * n;
+        let n4 = n3 * n;
+        let n5 = n4 * n;
+        let n6 = n5 * n;
+        let a_rectifying = ellipsoid.a / (1. + n) * (1. + n2 / 4. + n4 / 64. + n6 / 256.);
+        let alpha = [
+            n / 2. - 2. * n2 / 3. + 5. * n3 / 16. + 41. * n4 / 180. - 127. * n5 / 288.
+                + 7891. * n6 / 37800.,
+            13. * n2 / 48. - 3. * n3 / 5. + 557. * n4 / 1440. + 281. * n5 / 630.
+                - 1983433. * n6 / 1935360.,
+            61. * n3 / 240. - 103. * n4 / 140. + 15061. * n5 / 26880. + 167603. * n6 / 181440.,
+            49561. * n4 / 161280. - 179. * n5 / 168. + 6601661. * n6 / 7257600.,
+            34729. * n5 / 80640. - 3418889. * n6 / 1995840.,
+            212378941. * n6 / 319334400.,
+        ];
+        let beta = [
+            n / 2. - 2. * n2 / 3. + 37. * n3 / 96. - n4 / 360. - 81. * n5 / 512.
+                + 96199. * n6 / 604800.,
+            n2 / 48. + n3 / 15. - 437. * n4 / 1440. + 46. * n5 / 105. - 1118711. * n6 / 3870720.,
+            17. * n3 / 480. - 37. * n4 / 840. - 209. * n5 / 4480. + 5569. * n6 / 90720.,
+            4397. * n4 / 161280. - 11. * n5 / 504. - 830251. * n6 / 7257600.,
+            4583. * n5 / 161280. - 108847. * n6 / 3991680.,
+            20648693. * n6 / 638668800.,
+        ];
+        let mut projection = TransverseMercator {
+            ellipsoid,
+            latitude_of_origin,
+            central_meridian,
+            scale_factor,
+            false_easting,
+            false_northing,
+            e: (f * (2. - f)).sqrt(),
+            a_rectifying,
+            alpha,
+            beta,
+            origin_northing: 0.,
+            iteration: Iteration::default(),
+        };
+        let (xi, _, _, _) = projection.xi_eta(latitude_of_origin.to_radians(), 0.);
+        projection.origin_northing = scale_factor * a_rectifying * xi;
+        projection
+    }
+
+    /// Returns the ellipsoid.
+    pub fn ellipsoid(&self) -> Ellipsoid {
+        self.ellipsoid
+    }
+
+    /// Returns the latitude of origin in decimal degrees.
+    pub fn latitude_of_origin(&self) -> f64 {
+        self.latitude_of_origin
+    }
+
+    /// Returns the central meridian in decimal degrees.
+    pub fn central_meridian(&self) -> f64 {
+        self.central_meridian
+    }
+
+    /// Returns the scale factor on the central meridian.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Returns the false easting in meters.
+    pub fn false_easting(&self) -> f64 {
+        self.false_easting
+    }
+
+    /// Returns the false northing in meters.
+    pub fn false_northing(&self) -> f64 {
+        self.false_northing
+    }
+
+    /// Returns the Newton iteration settings used by the inverse.
+    pub fn iteration(&self) -> Iteration {
+        self.iteration
+    }
+
+    /// Returns this projection with different Newton iteration settings for the inverse.
+    ///
+    /// Loosening the tolerance or capping the iterations trades latitude accuracy for speed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::datum::GRS80;
+    /// use utm::tm::{Iteration, TransverseMercator};
+    /// let projection = TransverseMercator::new(GRS80, 0., -147., 0.9996, 500000., 0.)
+    ///     .with_iteration(Iteration { tolerance: 1e-9, max_iterations: 2 });
+    /// ```
+    pub fn with_iteration(mut self, iteration: Iteration) -> TransverseMercator {
+        self.iteration = iteration;
+        self
+    }
+
+    /// Projects a latitude and longitude in decimal degrees.
+    ///
+    /// Returns the northing, easting, and meridian convergence, in the same order and with the
+    /// same convergence convention (radians) as `to_utm_wgs84`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::datum::GRS80;
+    /// use utm::tm::TransverseMercator;
+    /// let projection = TransverseMercator::new(GRS80, 0., -147., 0.9996, 500000., 0.);
+    /// let (northing, easting, meridian_convergence) =
+    ///     projection.forward(60.9679875497, -149.119325194);
+    /// assert!((easting - 385273.02).abs() < 1e-2);
+    /// assert!((northing - 6761077.20).abs() < 1e-2);
+    /// ```
+    pub fn forward(&self, latitude: f64, longitude: f64) -> (f64, f64, f64) {
+        let phi = latitude.to_radians();
+        let lambda = (longitude - self.central_meridian).to_radians();
+        let (xi, eta, p, q) = self.xi_eta(phi, lambda);
+        let (_, taup) = self.taup(phi.tan());
+        let convergence = (taup / (1. + taup * taup).sqrt() * lambda.tan()).atan() + q.atan2(p);
+        let k0_a = self.scale_factor * self.a_rectifying;
+        (
+            k0_a * xi - self.origin_northing + self.false_northing,
+            k0_a * eta + self.false_easting,
+            -convergence,
+        )
+    }
+
+    #[cfg(feature = "inverse")]
+    /// Converts an easting and northing in meters back to a latitude and longitude in decimal
+    /// degrees.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::datum::GRS80;
+    /// use utm::tm::TransverseMercator;
+    /// let projection = TransverseMercator::new(GRS80, 0., -147., 0.9996, 500000., 0.);
+    /// let (latitude, longitude) = projection.inverse(385273.02, 6761077.20);
+    /// assert!((latitude - 60.9679875497).abs() < 1e-6);
+    /// assert!((longitude - -149.119325194).abs() < 1e-6);
+    /// ```
+    pub fn inverse(&self, easting: f64, northing: f64) -> (f64, f64) {
+        let (latitude, longitude, _) = self.inverse_with_diagnostics(easting, northing);
+        (latitude, longitude)
+    }
+
+    #[cfg(feature = "inverse")]
+    /// Like `inverse`, but also reports how the Newton iteration for the latitude went.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::datum::GRS80;
+    /// use utm::tm::{Iteration, TransverseMercator};
+    /// let projection = TransverseMercator::new(GRS80, 0., -147., 0.9996, 500000., 0.);
+    /// let (_, _, diagnostics) = projection.inverse_with_diagnostics(385273.02, 6761077.20);
+    /// assert!(diagnostics.converged);
+    ///
+    /// let projection = projection.with_iteration(Iteration { tolerance: 0., max_iterations: 1 });
+    /// let (_, _, diagnostics) = projection.inverse_with_diagnostics(385273.02, 6761077.20);
+    /// assert_eq!(diagnostics.iterations, 1);
+    /// assert!(!diagnostics.converged);
+    /// ```
+    pub fn inverse_with_diagnostics(&self, easting: f64, northing: f64) -> (f64, f64, Diagnostics) {
+        let k0_a = self.scale_factor * self.a_rectifying;
+        let xi = (northing - self.false_northing + self.origin_northing) / k0_a;
+        let eta = (easting - self.false_easting) / k0_a;
+        let mut xip = xi;
+        let mut etap = eta;
+        for (j, beta) in self.beta.iter().enumerate() {
+            let j2 = 2. * (j + 1) as f64;
+            xip -= beta * (j2 * xi).sin() * (j2 * eta).cosh();
+            etap -= beta * (j2 * xi).cos() * (j2 * eta).sinh();
+        }
+        let sinh_etap = etap.sinh();
+        let cos_xip = xip.cos();
+        let taup = xip.sin() / (sinh_etap * sinh_etap + cos_xip * cos_xip).sqrt();
+        let lambda = sinh_etap.atan2(cos_xip);
+        let (tau, diagnostics) = self.tau(taup);
+        (
+            tau.atan().to_degrees(),
+            lambda.to_degrees() + self.central_meridian,
+            diagnostics,
+        )
+    }
+
+    /// Returns the unscaled Gauss-Krüger coordinates ξ and η along with the series terms p and
+    /// q used for convergence.
+    fn xi_eta(&self, phi: f64, lambda: f64) -> (f64, f64, f64, f64) {
+        let (_, taup) = self.taup(phi.tan());
+        let cos_lambda = lambda.cos();
+        let xip = taup.atan2(cos_lambda);
+        let etap = (lambda.sin() / (taup * taup + cos_lambda * cos_lambda).sqrt()).asinh();
+        let mut xi = xip;
+        let mut eta = etap;
+        let mut p = 1.;
+        let mut q = 0.;
+        for (j, alpha) in self.alpha.iter().enumerate() {
+            let j2 = 2. * (j + 1) as f64;
+            let (sin, cos) = ((j2 * xip).sin(), (j2 * xip).cos());
+            let (sinh, cosh) = ((j2 * etap).sinh(), (j2 * etap).cosh());
+            xi += alpha * sin * cosh;
+            eta += alpha * cos * sinh;
+            p += j2 * alpha * cos * cosh;
+            q += j2 * alpha * sin * sinh;
+        }
+        (xi, eta, p, q)
+    }
+
+    /// Returns σ and the tangent of the conformal latitude, τ', for a tangent of latitude τ.
+    fn taup(&self, tau: f64) -> (f64, f64) {
+        let e = self.e;
+        let sigma = (e * (e * tau / (1. + tau * tau).sqrt()).atanh()).sinh();
+        (
+            sigma,
+            tau * (1. + sigma * sigma).sqrt() - sigma * (1. + tau * tau).sqrt(),
+        )
+    }
+
+    #[cfg(feature = "inverse")]
+    /// Inverts `taup` with Newton's method.
+    fn tau(&self, taup: f64) -> (f64, Diagnostics) {
+        let e2m = 1. - self.e * self.e;
+        let mut tau = taup / e2m;
+        let mut diagnostics = Diagnostics {
+            iterations: 0,
+            converged: false,
+            last_step: f64::INFINITY,
+        };
+        while diagnostics.iterations < self.iteration.max_iterations {
+            let (_, taupa) = self.taup(tau);
+            let dtau = (taup - taupa) * (1. + e2m * tau * tau)
+                / (e2m * (1. + tau * tau).sqrt() * (1. + taupa * taupa).sqrt());
+            tau += dtau;
+            diagnostics.iterations += 1;
+            diagnostics.last_step = dtau.abs() / tau.abs().max(1.);
+            if diagnostics.last_step <= self.iteration.tolerance {
+                diagnostics.converged = true;
+                break;
+            }
+        }
+        (tau, diagnostics)
+    }
+}
+
+#[cfg(all(test, feature = "inverse"))]
+mod tests {
+    use super::*;
+    use datum::GRS80;
+
+    #[test]
+    fn latitude_of_origin() {
+        let projection = TransverseMercator::new(GRS80, 36., 139. + 50. / 60., 0.9999, 0., 0.);
+        let (northing, easting, _) = projection.forward(36., 139. + 50. / 60.);
+        assert!(northing.abs() < 1e-9);
+        assert!(easting.abs() < 1e-9);
+    }
+
+    #[test]
+    fn round_trip_far_from_central_meridian() {
+        let projection = TransverseMercator::new(GRS80, 0., 3., 0.9996, 500000., 0.);
+        let (northing, easting, _) = projection.forward(-45., 20.);
+        let (latitude, longitude) = projection.inverse(easting, northing);
+        assert!((latitude - -45.).abs() < 1e-9);
+        assert!((longitude - 20.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn default_iteration_converges_quickly() {
+        let projection = TransverseMercator::new(GRS80, 0., 3., 0.9996, 500000., 0.);
+        for &latitude in &[-84., -45., 0., 30., 60., 84.] {
+            let (northing, easting, _) = projection.forward(latitude, 5.);
+            let (_, _, diagnostics) = projection.inverse_with_diagnostics(easting, northing);
+            assert!(diagnostics.converged);
+            assert!(diagnostics.iterations <= 3);
+        }
+    }
+
+    #[test]
+    fn convergence_matches_legacy() {
+        let projection = TransverseMercator::new(GRS80, 0., -147., 0.9996, 500000., 0.);
+        let (_, _, meridian_convergence) = projection.forward(60.9679875497, -149.119325194);
+        assert!((0.0323 - meridian_convergence).abs() < 1e-4);
+    }
+}
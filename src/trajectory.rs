@@ -0,0 +1,339 @@
+//! Detect when a trajectory crosses UTM zone and latitude band boundaries.
+//!
+//! Feed a sequence of latitude and longitude fixes into `crossings` and it yields a `Crossing`
+//! each time the standard zone or band changes between consecutive fixes. The crossing point is
+//! located on the straight line (in latitude and longitude) between the two fixes by bisection,
+//! so it lands on the boundary to within floating point precision, including the irregular
+//! Norway and Svalbard boundaries.
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use super::{lat_lon_to_zone_number, lat_to_zone_letter};
+
+/// A zone or band change between two consecutive fixes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Crossing {
+    /// The standard UTM zone changed.
+    Zone {
+        /// The index of the first fix after the crossing.
+        index: usize,
+        /// The zone before the crossing.
+        from: u8,
+        /// The zone after the crossing.
+        to: u8,
+        /// The latitude of the crossing point, in decimal degrees.
+        latitude: f64,
+        /// The longitude of the crossing point, in decimal degrees.
+        longitude: f64,
+    },
+    /// The latitude band changed. `None` means the point is outside of the UTM bands, i.e.
+    /// north of 84°N or south of 80°S.
+    Band {
+        /// The index of the first fix after the crossing.
+        index: usize,
+        /// The band before the crossing.
+        from: Option<char>,
+        /// The band after the crossing.
+        to: Option<char>,
+        /// The latitude of the crossing point, in decimal degrees.
+        latitude: f64,
+        /// The longitude of the crossing point, in decimal degrees.
+        longitude: f64,
+    },
+}
+
+/// Returns an iterator over the zone and band crossings of a sequence of fixes.
+///
+/// Fixes are latitude and longitude pairs in decimal degrees. Crossings between two fixes are
+/// yielded in the order they happen along the segment. A segment that crosses the antimeridian
+/// is interpolated the short way around.
+///
+/// # Examples
+///
+/// ```
+/// use utm::trajectory::{crossings, Crossing};
+/// let fixes = vec![(45., -121.), (45., -119.), (39., -119.)];
+/// let events: Vec<Crossing> = crossings(fixes).collect();
+/// assert_eq!(events.len(), 2);
+/// match events[0] {
+///     Crossing::Zone { index, from, to, longitude, .. } => {
+///         assert_eq!((index, from, to), (1, 10, 11));
+///         assert!((longitude - -120.).abs() < 1e-9);
+///     }
+///     _ => unreachable!(),
+/// }
+/// match events[1] {
+///     Crossing::Band { from, to, latitude, .. } => {
+///         assert_eq!((from, to), (Some('T'), Some('S')));
+///         assert!((latitude - 40.).abs() < 1e-9);
+///     }
+///     _ => unreachable!(),
+/// }
+/// ```
+pub fn crossings<I>(fixes: I) -> Crossings<I::IntoIter>
+where
+    I: IntoIterator<Item = (f64, f64)>,
+{
+    Crossings {
+        fixes: fixes.into_iter(),
+        previous: None,
+        index: 0,
+        segment: None,
+    }
+}
+
+/// An iterator over zone and band crossings, created by `crossings`.
+#[derive(Debug)]
+pub struct Crossings<I> {
+    fixes: I,
+    previous: Option<(f64, f64)>,
+    index: usize,
+    segment: Option<Segment>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Segment {
+    start: (f64, f64),
+    delta: (f64, f64),
+    index: usize,
+    zone_t: f64,
+    band_t: f64,
+}
+
+impl Segment {
+    fn new(start: (f64, f64), end: (f64, f64), index: usize) -> Segment {
+        let mut dlon = end.1 - start.1;
+        if dlon > 180. {
+            dlon -= 360.;
+        } else if dlon < -180. {
+            dlon += 360.;
+        }
+        Segment {
+            start,
+            delta: (end.0 - start.0, dlon),
+            index,
+            zone_t: 0.,
+            band_t: 0.,
+        }
+    }
+
+    fn point(&self, t: f64) -> (f64, f64) {
+        let latitude = self.start.0 + t * self.delta.0;
+        let mut longitude = self.start.1 + t * self.delta.1;
+        if longitude >= 180. {
+            longitude -= 360.;
+        } else if longitude < -180. {
+            longitude += 360.;
+        }
+        (latitude, longitude)
+    }
+
+    fn zone(&self, t: f64) -> u8 {
+        let (latitude, longitude) = self.point(t);
+        lat_lon_to_zone_number(latitude, longitude)
+    }
+
+    fn band(&self, t: f64) -> Option<char> {
+        lat_to_zone_letter(self.point(t).0)
+    }
+
+    /// Returns the first parameter after `t` at which the segment meets a band edge or a
+    /// meridian at a multiple of 3°, or 1 if there is none.
+    ///
+    /// Every zone and band boundary, including the Norway and Svalbard exceptions, lies on one
+    /// of those, so the zone and band are constant between consecutive knots.
+    fn next_knot(&self, t: f64) -> f64 {
+        let mut knot: f64 = 1.;
+        if self.delta.0 != 0. {
+            for edge in (0..20).map(|i| f64::from(-80 + 8 * i)).chain(Some(84.)) {
+                let candidate = (edge - self.start.0) / self.delta.0;
+                if candidate > t && candidate < knot {
+                    knot = candidate;
+                }
+            }
+        }
+        if self.delta.1 != 0. {
+            let longitude = self.start.1 + t * self.delta.1;
+            let step = 3f64.copysign(self.delta.1);
+            let mut meridian = (longitude / 3.).floor() * 3.;
+            // The meridian at or behind the current longitude, then at most two more
+            for _ in 0..3 {
+                let candidate = (meridian - self.start.1) / self.delta.1;
+                if candidate > t {
+                    knot = knot.min(candidate);
+                    break;
+                }
+                meridian += step;
+            }
+        }
+        knot
+    }
+
+    /// Finds the first parameter after `t` at which `f` changes value, if it changes before
+    /// the end of the segment.
+    ///
+    /// `f` is sampled at every knot and halfway between them, so a segment that enters and
+    /// leaves a zone or band is caught even when both ends are in the same one.
+    fn next_change<T: PartialEq, F: Fn(f64) -> T>(&self, t: f64, f: F) -> Option<f64> {
+        let value = f(t);
+        let mut lo = t;
+        while lo < 1. {
+            let knot = self.next_knot(lo);
+            for &hi in &[lo + (knot - lo) / 2., knot] {
+                if f(hi) != value {
+                    return Some(bisect(lo, hi, |t| f(t) == value));
+                }
+                lo = hi;
+            }
+        }
+        None
+    }
+}
+
+/// Narrows `lo` and `hi`, where `same` is true at `lo` and false at `hi`, down to adjacent
+/// floats, and returns `hi`.
+fn bisect<F: Fn(f64) -> bool>(mut lo: f64, mut hi: f64, same: F) -> f64 {
+    while hi - lo > f64::EPSILON {
+        let mid = lo + (hi - lo) / 2.;
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        if same(mid) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    hi
+}
+
+impl<I: Iterator<Item = (f64, f64)>> Iterator for Crossings<I> {
+    type Item = Crossing;
+
+    fn next(&mut self) -> Option<Crossing> {
+        loop {
+            if let Some(mut segment) = self.segment {
+                let zone_t = segment.next_change(segment.zone_t, |t| segment.zone(t));
+                let band_t = segment.next_change(segment.band_t, |t| segment.band(t));
+                let crossing = match (zone_t, band_t) {
+                    (Some(zone_t), Some(band_t)) if band_t < zone_t => Some((false, band_t)),
+                    (Some(zone_t), _) => Some((true, zone_t)),
+                    (None, Some(band_t)) => Some((false, band_t)),
+                    (None, None) => None,
+                };
+                if let Some((is_zone, t)) = crossing {
+                    let (latitude, longitude) = segment.point(t);
+                    let crossing = if is_zone {
+                        let from = segment.zone(segment.zone_t);
+                        segment.zone_t = t;
+                        Crossing::Zone {
+                            index: segment.index,
+                            from,
+                            to: segment.zone(t),
+                            latitude,
+                            longitude,
+                        }
+                    } else {
+                        let from = segment.band(segment.band_t);
+                        segment.band_t = t;
+                        Crossing::Band {
+                            index: segment.index,
+                            from,
+                            to: segment.band(t),
+                            latitude,
+                            longitude,
+                        }
+                    };
+                    self.segment = Some(segment);
+                    return Some(crossing);
+                }
+                self.segment = None;
+            }
+            let fix = self.fixes.next()?;
+            if let Some(previous) = self.previous {
+                self.segment = Some(Segment::new(previous, fix, self.index));
+            }
+            self.previous = Some(fix);
+            self.index += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn antimeridian() {
+        let mut events = crossings(vec![(10., 179.5), (10., -179.5)]);
+        match events.next().unwrap() {
+            Crossing::Zone {
+                from,
+                to,
+                longitude,
+                ..
+            } => {
+                assert_eq!((from, to), (60, 1));
+                assert!((longitude.abs() - 180.).abs() < 1e-9);
+            }
+            crossing => panic!("unexpected crossing: {:?}", crossing),
+        }
+        assert!(events.next().is_none());
+    }
+
+    #[test]
+    fn several_zones_in_one_segment() {
+        let zones: Vec<(u8, u8)> = crossings(vec![(0.5, 0.5), (0.5, 13.)])
+            .map(|crossing| match crossing {
+                Crossing::Zone { from, to, .. } => (from, to),
+                crossing => panic!("unexpected crossing: {:?}", crossing),
+            })
+            .collect();
+        assert_eq!(zones, vec![(31, 32), (32, 33)]);
+    }
+
+    #[test]
+    fn in_and_out_of_a_zone() {
+        // Due north along 4°E, into the Norway exception's zone 32 and back out into 31
+        let events: Vec<Crossing> = crossings(vec![(55., 4.), (65., 4.)]).collect();
+        let zones: Vec<(u8, u8, f64)> = events
+            .iter()
+            .filter_map(|crossing| match *crossing {
+                Crossing::Zone {
+                    from, to, latitude, ..
+                } => Some((from, to, latitude)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(zones.len(), 2);
+        assert_eq!((zones[0].0, zones[0].1), (31, 32));
+        assert!((zones[0].2 - 56.).abs() < 1e-9);
+        assert_eq!((zones[1].0, zones[1].1), (32, 31));
+        assert!((zones[1].2 - 64.).abs() < 1e-9);
+        assert_eq!(events.len(), 4);
+        // And west to east through a Svalbard zone that neither end is in
+        let zones: Vec<(u8, u8)> = crossings(vec![(75., 8.), (75., 22.)])
+            .map(|crossing| match crossing {
+                Crossing::Zone { from, to, .. } => (from, to),
+                crossing => panic!("unexpected crossing: {:?}", crossing),
+            })
+            .collect();
+        assert_eq!(zones, vec![(31, 33), (33, 35)]);
+        // Fixes that aren't numbers still end
+        crossings(vec![(f64::NAN, 1.), (1., f64::NAN), (2., 2.)]).count();
+    }
+
+    #[test]
+    fn norway_exception() {
+        let events: Vec<Crossing> = crossings(vec![(60., 2.), (60., 4.)]).collect();
+        match events[0] {
+            Crossing::Zone { to, longitude, .. } => {
+                assert_eq!(to, 32);
+                assert!((longitude - 3.).abs() < 1e-9);
+            }
+            crossing => panic!("unexpected crossing: {:?}", crossing),
+        }
+    }
+}
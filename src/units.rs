@@ -0,0 +1,110 @@
+//! Newtypes that keep eastings and northings apart.
+//!
+//! Both are plain meters, so nothing stops an easting from being passed where a northing is
+//! expected. `Easting` and `Northing` are a lightweight alternative to `UtmCoordinate` for code
+//! that mostly passes scalars around. `to_lat_lon` takes them instead of `f64`, so wrapping the
+//! arguments is enough to have the compiler reject a swap:
+//!
+//! ```compile_fail
+//! use utm::units::{to_lat_lon, Easting, Northing};
+//! let _ = to_lat_lon(Northing(6243186.), Easting(261878.), 34, 'H');
+//! ```
+//!
+//! Adding or subtracting meters keeps the type, and the difference between two eastings or two
+//! northings is plain meters.
+
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+#[cfg(feature = "inverse")]
+use super::{wsg84_utm_to_lat_lon, WSG84ToLatLonError};
+
+macro_rules! meters {
+    ($name:ident, $doc:expr) => {
+        #[doc = $doc]
+        #[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+        pub struct $name(pub f64);
+
+        impl From<f64> for $name {
+            fn from(meters: f64) -> $name {
+                $name(meters)
+            }
+        }
+
+        impl From<$name> for f64 {
+            fn from(value: $name) -> f64 {
+                value.0
+            }
+        }
+
+        impl Add<f64> for $name {
+            type Output = $name;
+            fn add(self, meters: f64) -> $name {
+                $name(self.0 + meters)
+            }
+        }
+
+        impl Sub<f64> for $name {
+            type Output = $name;
+            fn sub(self, meters: f64) -> $name {
+                $name(self.0 - meters)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = f64;
+            fn sub(self, other: $name) -> f64 {
+                self.0 - other.0
+            }
+        }
+
+        impl AddAssign<f64> for $name {
+            fn add_assign(&mut self, meters: f64) {
+                self.0 += meters;
+            }
+        }
+
+        impl SubAssign<f64> for $name {
+            fn sub_assign(&mut self, meters: f64) {
+                self.0 -= meters;
+            }
+        }
+    };
+}
+
+meters!(Easting, "A UTM easting in meters.");
+meters!(Northing, "A UTM northing in meters.");
+
+#[cfg(feature = "inverse")]
+/// Converts a UTM coordinate to a latitude and longitude, like `wsg84_utm_to_lat_lon`.
+///
+/// # Examples
+///
+/// ```
+/// use utm::units::{to_lat_lon, Easting, Northing};
+/// let (lat, _) = to_lat_lon(Easting(261878.), Northing(6243186.), 34, 'H').unwrap();
+/// assert!((lat + 33.92487).abs() < 3e-5);
+/// ```
+pub fn to_lat_lon(
+    easting: Easting,
+    northing: Northing,
+    zone_num: u8,
+    zone_letter: char,
+) -> Result<(f64, f64), WSG84ToLatLonError> {
+    wsg84_utm_to_lat_lon(easting.0, northing.0, zone_num, zone_letter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let mut easting = Easting(500000.) + 20.;
+        easting -= 5.;
+        assert_eq!(easting, Easting(500015.));
+        assert_eq!(easting - Easting(500000.), 15.);
+        let mut northing = Northing::from(100.) - 1.;
+        northing += 0.5;
+        assert_eq!(f64::from(northing), 99.5);
+    }
+}
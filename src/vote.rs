@@ -0,0 +1,207 @@
+//! Choose one UTM zone for a whole dataset.
+//!
+//! A survey that straddles a zone boundary often has to be projected into a single grid anyway.
+//! `recommend_zone` picks that zone and reports how far the points outside of it end up from
+//! its central meridian, so the distortion can be checked before committing to it.
+
+use std::ops::RangeInclusive;
+
+use super::converter::UTM_FALSE_EASTING;
+use super::{lat_lon_to_zone_number, series_to_utm_wgs84};
+
+/// How `recommend_zone` picks the zone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Strategy {
+    /// The zone containing the most points.
+    Majority,
+    /// The zone covering the largest share of the dataset's bounding box.
+    ///
+    /// Unlike `Majority`, this isn't swayed by densely sampled areas. The bounding box is taken
+    /// in plain longitudes, so a dataset that crosses the antimeridian spans every zone, and one
+    /// with no extent in longitude falls back to `Majority`.
+    AreaWeighted,
+    /// The zone whose central meridian keeps the farthest point closest, which minimizes the
+    /// worst scale distortion.
+    MinimaxDistortion,
+}
+
+/// A recommended zone, with statistics on how well it fits the dataset.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Recommendation {
+    /// The recommended zone number.
+    pub zone: u8,
+    /// The number of points that belong to another zone.
+    pub out_of_zone: usize,
+    /// How many degrees of longitude the farthest point is outside of the zone's standard six
+    /// degree column, or zero if every point is inside it.
+    pub max_degrees_outside: f64,
+    /// The largest distance in meters of a point's easting from the central meridian.
+    pub max_easting_offset: f64,
+}
+
+/// Recommends a single zone for a set of latitudes and longitudes in decimal degrees.
+///
+/// Returns `None` if there are no points. Ties go to the lowest zone number.
+///
+/// # Examples
+///
+/// ```
+/// use utm::vote::{recommend_zone, Strategy};
+/// // A survey along the boundary of zones 31 and 32, mostly sampled in zone 32
+/// let points = [(45., 5.5), (45., 6.2), (45., 6.4), (45.1, 6.3)];
+/// let recommendation = recommend_zone(&points, Strategy::Majority).unwrap();
+/// assert_eq!(recommendation.zone, 32);
+/// assert_eq!(recommendation.out_of_zone, 1);
+/// assert!((recommendation.max_degrees_outside - 0.5).abs() < 1e-9);
+/// ```
+pub fn recommend_zone(lat_lons: &[(f64, f64)], strategy: Strategy) -> Option<Recommendation> {
+    if lat_lons.is_empty() {
+        return None;
+    }
+    let zone = match strategy {
+        Strategy::Majority => majority(lat_lons),
+        Strategy::AreaWeighted => area_weighted(lat_lons).unwrap_or_else(|| majority(lat_lons)),
+        Strategy::MinimaxDistortion => minimax_distortion(lat_lons),
+    };
+    Some(recommendation(lat_lons, zone))
+}
+
+fn majority(lat_lons: &[(f64, f64)]) -> u8 {
+    let mut counts = [0usize; 60];
+    for &(latitude, longitude) in lat_lons {
+        counts[usize::from(lat_lon_to_zone_number(latitude, longitude)) - 1] += 1;
+    }
+    best(|zone| counts[usize::from(zone) - 1] as f64, 1..=60)
+}
+
+fn area_weighted(lat_lons: &[(f64, f64)]) -> Option<u8> {
+    let (west, east) = longitude_range(lat_lons);
+    if west >= east {
+        return None;
+    }
+    Some(best(
+        |zone| {
+            let column_west = f64::from(zone) * 6. - 186.;
+            (east.min(column_west + 6.) - west.max(column_west)).max(0.)
+        },
+        1..=60,
+    ))
+}
+
+fn minimax_distortion(lat_lons: &[(f64, f64)]) -> u8 {
+    let (west, east) = longitude_range(lat_lons);
+    let first = lat_lon_to_zone_number(0., west);
+    let last = lat_lon_to_zone_number(0., east).max(first);
+    best(
+        |zone| {
+            -lat_lons
+                .iter()
+                .map(|&(latitude, longitude)| easting_offset(latitude, longitude, zone))
+                .fold(0., f64::max)
+        },
+        first..=last,
+    )
+}
+
+/// Returns the zone in `zones` with the largest score, preferring the first on ties.
+fn best<F: Fn(u8) -> f64>(score: F, zones: RangeInclusive<u8>) -> u8 {
+    let mut best = (*zones.start(), f64::NEG_INFINITY);
+    for zone in zones {
+        let score = score(zone);
+        if score > best.1 {
+            best = (zone, score);
+        }
+    }
+    best.0
+}
+
+fn recommendation(lat_lons: &[(f64, f64)], zone: u8) -> Recommendation {
+    let mut recommendation = Recommendation {
+        zone,
+        out_of_zone: 0,
+        max_degrees_outside: 0.,
+        max_easting_offset: 0.,
+    };
+    for &(latitude, longitude) in lat_lons {
+        if lat_lon_to_zone_number(latitude, longitude) != zone {
+            recommendation.out_of_zone += 1;
+        }
+        let outside = relative_longitude(longitude, zone).abs() - 3.;
+        recommendation.max_degrees_outside = recommendation.max_degrees_outside.max(outside);
+        recommendation.max_easting_offset = recommendation
+            .max_easting_offset
+            .max(easting_offset(latitude, longitude, zone));
+    }
+    recommendation
+}
+
+fn longitude_range(lat_lons: &[(f64, f64)]) -> (f64, f64) {
+    lat_lons.iter().fold(
+        (f64::INFINITY, f64::NEG_INFINITY),
+        |(west, east), &(_, longitude)| (west.min(longitude), east.max(longitude)),
+    )
+}
+
+/// Returns the longitude relative to the zone's central meridian, between -180° and 180°.
+fn relative_longitude(longitude: f64, zone: u8) -> f64 {
+    let central_meridian = f64::from(zone) * 6. - 183.;
+    (longitude - central_meridian + 540.) % 360. - 180.
+}
+
+fn easting_offset(latitude: f64, longitude: f64, zone: u8) -> f64 {
+    let central_meridian = f64::from(zone) * 6. - 183.;
+    let longitude = central_meridian + relative_longitude(longitude, zone);
+    // Distortion is a property of standard UTM, whatever the global converter is.
+    let (_, easting, _) = series_to_utm_wgs84(latitude, longitude, zone);
+    (easting - UTM_FALSE_EASTING).abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strategies_disagree() {
+        // Dense sampling in zone 33, with a sparse line far into zone 34.
+        let mut points = [(50., 17.5); 10];
+        points[7] = (50., 18.5);
+        points[8] = (50., 19.5);
+        points[9] = (50., 20.5);
+        assert_eq!(
+            recommend_zone(&points, Strategy::Majority).unwrap().zone,
+            33
+        );
+        assert_eq!(
+            recommend_zone(&points, Strategy::AreaWeighted)
+                .unwrap()
+                .zone,
+            34
+        );
+        assert_eq!(
+            recommend_zone(&points, Strategy::MinimaxDistortion)
+                .unwrap()
+                .zone,
+            34
+        );
+    }
+
+    #[test]
+    fn statistics() {
+        let recommendation =
+            recommend_zone(&[(0., 2.), (0., 9.)], Strategy::MinimaxDistortion).unwrap();
+        assert_eq!(recommendation.zone, 31);
+        assert_eq!(recommendation.out_of_zone, 1);
+        assert!((recommendation.max_degrees_outside - 3.).abs() < 1e-9);
+        let (_, easting, _) = series_to_utm_wgs84(0., 9., 31);
+        assert_eq!(recommendation.max_easting_offset, easting - 500000.);
+        assert!(recommend_zone(&[], Strategy::Majority).is_none());
+    }
+
+    #[test]
+    fn antimeridian() {
+        let points = [(-17., 179.9), (-17., -179.9), (-17., -179.8)];
+        let recommendation = recommend_zone(&points, Strategy::MinimaxDistortion).unwrap();
+        assert_eq!(recommendation.zone, 1);
+        assert!(recommendation.max_easting_offset < 400000.);
+    }
+}
@@ -0,0 +1,213 @@
+//! Conversions with all of their setup done up front, for real-time loops.
+//!
+//! Nothing in this crate allocates while converting, but Krüger's series has coefficients that
+//! `tm::TransverseMercator::new` computes for every projection, and `converter::Converter`
+//! builds a projection on every call. That setup costs more than the conversion itself. A
+//! `Workspace` builds the projections of all sixty zones in both hemispheres once, so every
+//! later conversion is only the series, with the same cost on every call.
+//!
+//! For a bounded inverse, also cap the Newton iteration with `Workspace::with_iteration`.
+//! A workspace is about twenty kilobytes, so keep one around rather than building it in the
+//! loop.
+
+#[cfg(feature = "no_std")]
+#[allow(unused_imports)]
+use num::traits::float::Float;
+
+use super::converter::Converter;
+use super::tm::{Iteration, TransverseMercator};
+use super::{lat_lon_to_zone_number, lat_to_zone_letter, UtmCoordinate};
+#[cfg(feature = "inverse")]
+use super::{WSG84ToLatLonError, ZONE_LETTERS};
+
+/// The projections of every zone and hemisphere of a `Converter`.
+///
+/// # Examples
+///
+/// ```
+/// use utm::workspace::Workspace;
+/// let workspace = Workspace::new();
+/// for _ in 0..1000 {
+///     let coordinate = workspace.to_utm(-33.92487, 18.42406).unwrap();
+///     assert_eq!(coordinate.zone_num, 34);
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Workspace {
+    converter: Converter,
+    projections: [TransverseMercator; 120],
+}
+
+impl Workspace {
+    /// Creates a workspace for standard UTM on WGS84.
+    pub fn new() -> Workspace {
+        Workspace::with_converter(Converter::new())
+    }
+
+    /// Creates a workspace for a converter's grid constants.
+    pub fn with_converter(converter: Converter) -> Workspace {
+        let mut projections = [converter.projection(1, true); 120];
+        for (index, projection) in projections.iter_mut().enumerate() {
+            *projection = converter.projection(index as u8 / 2 + 1, index % 2 == 0);
+        }
+        Workspace {
+            converter,
+            projections,
+        }
+    }
+
+    /// Returns this workspace with different Newton iteration settings for the inverse.
+    ///
+    /// A negative tolerance never stops early, so every inverse runs for exactly
+    /// `max_iterations` steps and costs the same for every point. Three steps are enough for
+    /// full precision in the UTM bands.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::tm::Iteration;
+    /// use utm::workspace::Workspace;
+    /// let workspace = Workspace::new().with_iteration(Iteration {
+    ///     tolerance: -1.,
+    ///     max_iterations: 3,
+    /// });
+    /// ```
+    pub fn with_iteration(mut self, iteration: Iteration) -> Workspace {
+        for projection in self.projections.iter_mut() {
+            *projection = projection.with_iteration(iteration);
+        }
+        self
+    }
+
+    /// Returns the converter this workspace was built from.
+    pub fn converter(&self) -> Converter {
+        self.converter
+    }
+
+    /// Returns the projection of a zone in one hemisphere, or `None` if the zone is not between
+    /// 1 and 60.
+    pub fn projection(&self, zone_num: u8, northern: bool) -> Option<&TransverseMercator> {
+        if !(1..=60).contains(&zone_num) {
+            return None;
+        }
+        let index = 2 * usize::from(zone_num - 1) + usize::from(!northern);
+        Some(&self.projections[index])
+    }
+
+    /// Converts a latitude and longitude in decimal degrees to a coordinate in its own zone
+    /// and band, like `Converter::to_utm`.
+    pub fn to_utm(&self, latitude: f64, longitude: f64) -> Option<UtmCoordinate> {
+        if !(-180. ..=180.).contains(&longitude) {
+            return None;
+        }
+        let zone_letter = lat_to_zone_letter(latitude)?;
+        let zone_num = lat_lon_to_zone_number(latitude, longitude);
+        let (northing, easting, _) = self
+            .projection(zone_num, zone_letter >= 'N')?
+            .forward(latitude, longitude);
+        Some(UtmCoordinate::new(easting, northing, zone_num, zone_letter))
+    }
+
+    #[cfg(feature = "inverse")]
+    /// Converts a coordinate to a latitude and longitude in decimal degrees, like
+    /// `Converter::to_lat_lon`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::workspace::Workspace;
+    /// use utm::UtmCoordinate;
+    /// let workspace = Workspace::new();
+    /// let coordinate = UtmCoordinate::new(261878., 6243186., 34, 'H');
+    /// let (latitude, longitude) = workspace.to_lat_lon(&coordinate).unwrap();
+    /// assert!((latitude + 33.92487).abs() < 1e-4 && (longitude - 18.42406).abs() < 1e-4);
+    /// ```
+    pub fn to_lat_lon(&self, coordinate: &UtmCoordinate) -> Result<(f64, f64), WSG84ToLatLonError> {
+        if !ZONE_LETTERS.contains(coordinate.zone_letter) {
+            return Err(WSG84ToLatLonError::ZoneLetterOutOfRange);
+        }
+        let projection = self
+            .projection(coordinate.zone_num, coordinate.zone_letter >= 'N')
+            .ok_or(WSG84ToLatLonError::ZoneNumOutOfRange)?;
+        Ok(projection.inverse(coordinate.easting, coordinate.northing))
+    }
+
+    /// Converts latitudes and longitudes in decimal degrees to coordinates in their own zones
+    /// and returns how many were written.
+    ///
+    /// Entries that `to_utm` can't convert are written as `None`, so one bad fix doesn't hold
+    /// up the rest of a real-time batch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::workspace::Workspace;
+    /// let workspace = Workspace::new();
+    /// let fixes = [(-33.92487, 18.42406), (85., 0.)];
+    /// let mut coordinates = [None; 2];
+    /// assert_eq!(workspace.to_utm_batch(&fixes, &mut coordinates), 2);
+    /// assert!(coordinates[0].is_some() && coordinates[1].is_none());
+    /// ```
+    pub fn to_utm_batch(
+        &self,
+        lat_lons: &[(f64, f64)],
+        coordinates: &mut [Option<UtmCoordinate>],
+    ) -> usize {
+        let len = lat_lons.len().min(coordinates.len());
+        for (&(latitude, longitude), coordinate) in lat_lons.iter().zip(coordinates.iter_mut()) {
+            *coordinate = self.to_utm(latitude, longitude);
+        }
+        len
+    }
+}
+
+impl Default for Workspace {
+    fn default() -> Workspace {
+        Workspace::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_converter() {
+        let converter = Converter::new().with_scale_factor(1.);
+        let workspace = Workspace::with_converter(converter);
+        for &(latitude, longitude) in &[(-33.92487, 18.42406), (60.5, 5.), (0.1, -177.), (0., 180.)]
+        {
+            let expected = converter.to_utm(latitude, longitude).unwrap();
+            assert_eq!(workspace.to_utm(latitude, longitude), Some(expected));
+            #[cfg(feature = "inverse")]
+            assert_eq!(
+                workspace.to_lat_lon(&expected),
+                converter.to_lat_lon(&expected)
+            );
+        }
+        assert!(workspace.projection(0, true).is_none());
+        assert!(workspace.projection(61, false).is_none());
+        assert_eq!(
+            workspace.projection(60, false).unwrap().central_meridian(),
+            177.
+        );
+    }
+
+    #[cfg(feature = "inverse")]
+    #[test]
+    fn fixed_iterations() {
+        let workspace = Workspace::new().with_iteration(Iteration {
+            tolerance: -1.,
+            max_iterations: 3,
+        });
+        let coordinate = UtmCoordinate::new(385273.02, 6761077.20, 6, 'V');
+        let (_, _, diagnostics) = workspace
+            .projection(6, true)
+            .unwrap()
+            .inverse_with_diagnostics(coordinate.easting, coordinate.northing);
+        assert_eq!(diagnostics.iterations, 3);
+        let (latitude, _) = workspace.to_lat_lon(&coordinate).unwrap();
+        let (expected, _) = Workspace::new().to_lat_lon(&coordinate).unwrap();
+        assert!((latitude - expected).abs() < 1e-12);
+    }
+}
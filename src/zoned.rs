@@ -0,0 +1,190 @@
+//! UTM coordinates with their zone and hemisphere carried in the type.
+//!
+//! Easting and northing values from different zones live in different planes, so subtracting
+//! or comparing them is almost always a bug. `ZonedCoordinate` makes the zone a const generic
+//! parameter so the compiler rejects those operations unless the coordinates are first moved
+//! into a common zone with `rezone`.
+//!
+//! Both directions always use the classic series on standard UTM, even if a global converter
+//! was set with `converter::set_global`, so a coordinate converts back to where it came from.
+//!
+//! ```compile_fail
+//! use utm::zoned::{North, ZonedCoordinate};
+//! let a = ZonedCoordinate::<10, North>::new(500000., 4500000.);
+//! let b = ZonedCoordinate::<11, North>::new(500000., 4500000.);
+//! let _ = a - b;
+//! ```
+
+use std::marker::PhantomData;
+use std::ops::Sub;
+
+use super::converter::UTM_FALSE_NORTHING_SOUTH;
+use super::{series_to_utm_wgs84, utm_to_lat_lon_unchecked};
+
+/// The hemisphere a `ZonedCoordinate` is referenced to.
+pub trait HemisphereTag {
+    /// True if northings are measured from the equator, false if they carry the 10,000,000 m
+    /// southern false northing.
+    const NORTHERN: bool;
+}
+
+/// Northern hemisphere tag.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct North;
+
+/// Southern hemisphere tag.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct South;
+
+impl HemisphereTag for North {
+    const NORTHERN: bool = true;
+}
+
+impl HemisphereTag for South {
+    const NORTHERN: bool = false;
+}
+
+/// A WGS84 UTM easting and northing whose zone and hemisphere are part of the type.
+///
+/// Coordinates outside of the nominal zone width are allowed, so points can be re-zoned into a
+/// neighbouring zone and compared there.
+pub struct ZonedCoordinate<const ZONE: u8, H> {
+    easting: f64,
+    northing: f64,
+    hemisphere: PhantomData<H>,
+}
+
+impl<const ZONE: u8, H: HemisphereTag> ZonedCoordinate<ZONE, H> {
+    const VALID_ZONE: () = assert!(ZONE >= 1 && ZONE <= 60, "UTM zones run from 1 to 60");
+
+    /// Creates a new coordinate from an easting and northing in this zone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::zoned::{North, ZonedCoordinate};
+    /// let coordinate = ZonedCoordinate::<10, North>::new(385273.02, 6761077.20);
+    /// assert_eq!(coordinate.easting(), 385273.02);
+    /// ```
+    pub fn new(easting: f64, northing: f64) -> ZonedCoordinate<ZONE, H> {
+        let () = Self::VALID_ZONE;
+        ZonedCoordinate {
+            easting,
+            northing,
+            hemisphere: PhantomData,
+        }
+    }
+
+    /// Projects a latitude and longitude in decimal degrees into this zone and hemisphere.
+    ///
+    /// The point does not need to lie inside the zone. Points on the other side of the equator
+    /// get a northing in this hemisphere's convention, i.e. negative or above 10,000,000 m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::zoned::{North, ZonedCoordinate};
+    /// let coordinate = ZonedCoordinate::<6, North>::from_lat_lon(60.9679875497, -149.119325194);
+    /// assert!((coordinate.easting() - 385273.02).abs() < 1e-2);
+    /// ```
+    pub fn from_lat_lon(latitude: f64, longitude: f64) -> ZonedCoordinate<ZONE, H> {
+        let (northing, easting, _) = series_to_utm_wgs84(latitude, longitude, ZONE);
+        let northing = match (H::NORTHERN, latitude >= 0.) {
+            (true, false) => northing - UTM_FALSE_NORTHING_SOUTH,
+            (false, true) => northing + UTM_FALSE_NORTHING_SOUTH,
+            _ => northing,
+        };
+        ZonedCoordinate::new(easting, northing)
+    }
+
+    /// Returns the zone number.
+    pub fn zone(&self) -> u8 {
+        ZONE
+    }
+
+    /// Returns the easting in meters.
+    pub fn easting(&self) -> f64 {
+        self.easting
+    }
+
+    /// Returns the northing in meters.
+    pub fn northing(&self) -> f64 {
+        self.northing
+    }
+
+    /// Converts this coordinate back to a latitude and longitude in decimal degrees.
+    pub fn to_lat_lon(&self) -> (f64, f64) {
+        utm_to_lat_lon_unchecked(self.easting, self.northing, ZONE, H::NORTHERN)
+    }
+
+    /// Re-projects this coordinate into another zone and/or hemisphere.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utm::zoned::{North, ZonedCoordinate};
+    /// let a = ZonedCoordinate::<10, North>::from_lat_lon(45., -120.1);
+    /// let b = ZonedCoordinate::<11, North>::from_lat_lon(45., -119.9);
+    /// let (de, _) = a.rezone::<11, North>() - b;
+    /// assert!((de + 15770.).abs() < 100.);
+    /// ```
+    pub fn rezone<const OTHER: u8, H2: HemisphereTag>(&self) -> ZonedCoordinate<OTHER, H2> {
+        let (latitude, longitude) = self.to_lat_lon();
+        ZonedCoordinate::from_lat_lon(latitude, longitude)
+    }
+}
+
+impl<const ZONE: u8, H> Clone for ZonedCoordinate<ZONE, H> {
+    fn clone(&self) -> ZonedCoordinate<ZONE, H> {
+        *self
+    }
+}
+
+impl<const ZONE: u8, H> Copy for ZonedCoordinate<ZONE, H> {}
+
+impl<const ZONE: u8, H> std::fmt::Debug for ZonedCoordinate<ZONE, H> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("ZonedCoordinate")
+            .field("zone", &ZONE)
+            .field("easting", &self.easting)
+            .field("northing", &self.northing)
+            .finish()
+    }
+}
+
+impl<const ZONE: u8, H> PartialEq for ZonedCoordinate<ZONE, H> {
+    fn eq(&self, other: &ZonedCoordinate<ZONE, H>) -> bool {
+        self.easting == other.easting && self.northing == other.northing
+    }
+}
+
+impl<const ZONE: u8, H> Sub for ZonedCoordinate<ZONE, H> {
+    /// The easting and northing differences, in meters.
+    type Output = (f64, f64);
+
+    fn sub(self, other: ZonedCoordinate<ZONE, H>) -> (f64, f64) {
+        (self.easting - other.easting, self.northing - other.northing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rezone_round_trip() {
+        let a = ZonedCoordinate::<33, North>::from_lat_lon(52.5, 13.4);
+        let b = a.rezone::<32, North>().rezone::<33, North>();
+        let (de, dn) = a - b;
+        assert!(de.abs() < 0.5);
+        assert!(dn.abs() < 0.5);
+    }
+
+    #[test]
+    fn hemisphere_convention() {
+        let north = ZonedCoordinate::<31, North>::from_lat_lon(-0.5, 3.);
+        let south = north.rezone::<31, South>();
+        assert!(north.northing() < 0.);
+        assert!((south.northing() - north.northing() - 10000000.).abs() < 1e-2);
+    }
+}
@@ -0,0 +1,130 @@
+//! Modules that work on standard UTM must not change with a global converter.
+//!
+//! The global converter can only be set once per process, so these tests live in their own test
+//! binary, which installs one with a different ellipsoid and false origin before every test.
+
+#![cfg(all(feature = "forward", feature = "inverse", not(feature = "no_std")))]
+
+extern crate utm;
+
+use utm::bbox::{LatLonBbox, UtmBbox};
+use utm::converter::{self, Converter};
+use utm::datum::{Geodetic, Wgs84, INTERNATIONAL_1924};
+use utm::easting::to_lat_lon_strict;
+use utm::frame::GeodeticPose;
+use utm::northing::{self, Convention};
+use utm::vote::{recommend_zone, Strategy};
+use utm::zoned::{North, ZonedCoordinate};
+use utm::Hemisphere;
+
+fn install() {
+    let global = Converter::new()
+        .with_ellipsoid(INTERNATIONAL_1924)
+        .with_false_easting(1000000.)
+        .with_false_northing(1000000., 20000000.);
+    // Every test installs the same one, so it doesn't matter which goes first.
+    converter::set_global(global).ok();
+    let (_, easting, _) = utm::to_utm_wgs84(48., 9.5, 32);
+    assert!(easting > 1000000.);
+}
+
+#[test]
+fn zoned_round_trips() {
+    install();
+    let coordinate = ZonedCoordinate::<32, North>::from_lat_lon(48., 9.5);
+    assert!(coordinate.easting() < 600000.);
+    let (latitude, longitude) = coordinate.to_lat_lon();
+    assert!((latitude - 48.).abs() < 1e-5 && (longitude - 9.5).abs() < 1e-5);
+    let (latitude, longitude) = coordinate.rezone::<33, North>().to_lat_lon();
+    assert!((latitude - 48.).abs() < 1e-5 && (longitude - 9.5).abs() < 1e-5);
+}
+
+#[test]
+fn bbox_round_trips() {
+    install();
+    let bbox = LatLonBbox {
+        min_latitude: -34.,
+        min_longitude: 18.,
+        max_latitude: -33.,
+        max_longitude: 19.,
+    };
+    let grid = UtmBbox::from_lat_lon_bbox(&bbox, 34, Hemisphere::South, 16);
+    assert!(grid.min_easting > 100000. && grid.max_easting < 500000.);
+    let back = grid.to_lat_lon_bbox(16);
+    assert!(back.min_latitude <= -34. && back.max_latitude >= -33.);
+    assert!((back.min_latitude + 34.).abs() < 0.1 && (back.max_longitude - 19.).abs() < 0.1);
+}
+
+#[test]
+fn northing_conventions_use_the_standard_false_northing() {
+    install();
+    let (northing, easting, _) = northing::to_utm(-33.92487, 18.42406, 34, Convention::Negative);
+    assert!((northing + 3756814.).abs() < 1. && (easting - 261878.).abs() < 1.);
+    let (latitude, longitude) =
+        northing::to_lat_lon(easting, northing, 34, 'H', Convention::Negative).unwrap();
+    assert!((latitude + 33.92487).abs() < 1e-5 && (longitude - 18.42406).abs() < 1e-5);
+}
+
+#[test]
+fn strict_easting_checks_standard_coordinates() {
+    install();
+    let (latitude, longitude) = to_lat_lon_strict(261878., 6243186., 34, 'H', 0.).unwrap();
+    assert!((latitude + 33.92487).abs() < 1e-4 && (longitude - 18.42406).abs() < 1e-4);
+}
+
+#[cfg(feature = "mgrs")]
+#[test]
+fn mgrs_uses_standard_eastings() {
+    use utm::mgrs::{Lettering, Mgrs};
+    install();
+    let mgrs = Mgrs::from_lat_lon(38.88949, -77.03525, 3).unwrap();
+    assert_eq!(mgrs.to_string(), "18SUJ234064");
+    let (latitude, longitude) = mgrs.to_lat_lon().unwrap();
+    assert!((latitude - 38.88949).abs() < 1e-3 && (longitude + 77.03525).abs() < 1e-3);
+    let neighbors = mgrs.neighbors(Lettering::AA).unwrap();
+    assert_eq!(neighbors[2].unwrap().to_string(), "18SUJ235064");
+}
+
+#[test]
+fn pose_position_and_heading_agree() {
+    install();
+    let pose = GeodeticPose {
+        latitude: -33.92487,
+        longitude: 18.42406,
+        heading: 1.2,
+    };
+    let grid = pose.to_grid(34).unwrap();
+    assert!((grid.coordinate.easting - 261878.).abs() < 1.);
+    assert!((grid.coordinate.northing - 6243186.).abs() < 1.);
+    let back = grid.to_geodetic().unwrap();
+    assert!((back.heading - 1.2).abs() < 1e-6);
+    assert!((back.latitude - pose.latitude).abs() < 1e-5);
+}
+
+#[test]
+fn wgs84_geodetic_stays_on_wgs84() {
+    install();
+    let (northing, easting, _) = Geodetic::<Wgs84>::new(60.9679875497, -149.119325194).to_utm(6);
+    assert!((easting - 385273.02).abs() < 1e-2 && (northing - 6761077.20).abs() < 1e-2);
+}
+
+#[test]
+fn vote_measures_from_the_central_meridian() {
+    install();
+    let points = [(45., 5.5), (45., 6.2), (45., 6.4), (45.1, 6.3)];
+    let recommendation = recommend_zone(&points, Strategy::Majority).unwrap();
+    assert!(recommendation.max_easting_offset < 300000.);
+}
+
+#[test]
+fn unchecked_batch_skips_the_global() {
+    use utm::batch::to_utm_unchecked;
+    use utm::UtmCoordinate;
+    install();
+    let mut coordinates = [UtmCoordinate::new(0., 0., 1, 'C')];
+    assert_eq!(
+        to_utm_unchecked(&[(-33.92487, 18.42406)], &mut coordinates),
+        1
+    );
+    assert!((coordinates[0].easting - 261878.).abs() < 1.);
+}